@@ -6,8 +6,8 @@ use parquet2::metadata::ColumnDescriptor;
 use parquet2::page::{split_buffer, DataPage};
 use parquet2::schema::types::ParquetType;
 
-fn deserialize(page: &DataPage, descriptor: &ColumnDescriptor) {
-    let (_rep_levels, _def_levels, _values_buffer) = split_buffer(page, descriptor);
+fn deserialize(page: &DataPage, descriptor: &ColumnDescriptor) -> Result<()> {
+    let (_rep_levels, _def_levels, _values_buffer) = split_buffer(page, descriptor)?;
 
     if let ParquetType::PrimitiveType {
         physical_type,
@@ -90,7 +90,7 @@ fn main() -> Result<()> {
         let page = maybe_page?;
         let page = parquet2::read::decompress(page, &mut decompress_buffer)?;
 
-        let _array = deserialize(&page, column_metadata.descriptor());
+        deserialize(&page, column_metadata.descriptor())?;
     }
     // ANCHOR_END: decompress
     Ok(())
@@ -1,13 +1,12 @@
 /// Serialization to Rust's Native types.
 /// In comparison to Arrow, this in-memory format does not leverage logical types nor SIMD operations,
 /// but OTOH it has no external dependencies and is very familiar to Rust developers.
-mod binary;
-mod boolean;
-mod primitive;
-mod primitive_nested;
+mod nested;
 mod struct_;
-mod utils;
 
+use parquet::deserialize::{
+    BinaryValues, BooleanValues, FixedLenValues, NativeValues, NestedDecoder, OptionalValues,
+};
 use parquet::error::ParquetError;
 use parquet::error::Result;
 use parquet::metadata::ColumnChunkMetaData;
@@ -23,66 +22,103 @@ use parquet::FallibleStreamingIterator;
 
 use crate::Array;
 
+/// Returns the values of `page`, whose non-null values are `values`. The values of nested
+/// columns are only the non-null ones, since their nulls are assembled from their levels.
+fn collect<I: Iterator>(
+    page: &DataPage,
+    descriptor: &ColumnDescriptor,
+    values: I,
+) -> Result<Vec<Option<I::Item>>> {
+    if descriptor.max_rep_level() == 0 {
+        Ok(OptionalValues::try_new(page, values)?.collect())
+    } else {
+        Ok(values.map(Some).collect())
+    }
+}
+
 /// Reads a page into an [`Array`].
+/// The [`Array`] of a page of a nested column only contains the non-null values of its leaf.
 /// This is CPU-intensive: decompress, decode and de-serialize.
 pub fn page_to_array(page: &DataPage, descriptor: &ColumnDescriptor) -> Result<Array> {
-    match (descriptor.type_(), descriptor.max_rep_level()) {
-        (ParquetType::PrimitiveType { physical_type, .. }, 0) => match page.dictionary_page() {
-            Some(_) => match physical_type {
-                PhysicalType::Int32 => {
-                    Ok(Array::Int32(primitive::page_dict_to_vec(page, descriptor)?))
-                }
-                PhysicalType::Int64 => {
-                    Ok(Array::Int64(primitive::page_dict_to_vec(page, descriptor)?))
-                }
-                PhysicalType::Int96 => {
-                    Ok(Array::Int96(primitive::page_dict_to_vec(page, descriptor)?))
-                }
-                PhysicalType::Float => Ok(Array::Float32(primitive::page_dict_to_vec(
-                    page, descriptor,
-                )?)),
-                PhysicalType::Double => Ok(Array::Float64(primitive::page_dict_to_vec(
-                    page, descriptor,
-                )?)),
-                PhysicalType::ByteArray => {
-                    Ok(Array::Binary(binary::page_dict_to_vec(page, descriptor)?))
-                }
-                _ => todo!(),
-            },
-            None => match physical_type {
-                PhysicalType::Boolean => {
-                    Ok(Array::Boolean(boolean::page_to_vec(page, descriptor)?))
-                }
-                PhysicalType::Int32 => Ok(Array::Int32(primitive::page_to_vec(page, descriptor)?)),
-                PhysicalType::Int64 => Ok(Array::Int64(primitive::page_to_vec(page, descriptor)?)),
-                PhysicalType::Int96 => Ok(Array::Int96(primitive::page_to_vec(page, descriptor)?)),
-                PhysicalType::Float => {
-                    Ok(Array::Float32(primitive::page_to_vec(page, descriptor)?))
-                }
-                PhysicalType::Double => {
-                    Ok(Array::Float64(primitive::page_to_vec(page, descriptor)?))
-                }
-                PhysicalType::ByteArray => {
-                    Ok(Array::Binary(binary::page_to_vec(page, descriptor)?))
-                }
-                _ => todo!(),
-            },
+    match descriptor.type_() {
+        ParquetType::PrimitiveType { physical_type, .. } => match physical_type {
+            PhysicalType::Boolean => Ok(Array::Boolean(collect(
+                page,
+                descriptor,
+                BooleanValues::try_new(page)?,
+            )?)),
+            PhysicalType::Int32 => Ok(Array::Int32(collect(
+                page,
+                descriptor,
+                NativeValues::try_new(page)?,
+            )?)),
+            PhysicalType::Int64 => Ok(Array::Int64(collect(
+                page,
+                descriptor,
+                NativeValues::try_new(page)?,
+            )?)),
+            PhysicalType::Int96 => Ok(Array::Int96(collect(
+                page,
+                descriptor,
+                NativeValues::try_new(page)?,
+            )?)),
+            PhysicalType::Float => Ok(Array::Float32(collect(
+                page,
+                descriptor,
+                NativeValues::try_new(page)?,
+            )?)),
+            PhysicalType::Double => Ok(Array::Float64(collect(
+                page,
+                descriptor,
+                NativeValues::try_new(page)?,
+            )?)),
+            PhysicalType::ByteArray => Ok(Array::Binary(collect(
+                page,
+                descriptor,
+                BinaryValues::try_new(page)?.map(|x| x.into_owned()),
+            )?)),
+            PhysicalType::FixedLenByteArray(_) => Ok(Array::Binary(collect(
+                page,
+                descriptor,
+                FixedLenValues::try_new(page)?.map(|x| x.into_owned()),
+            )?)),
         },
-        (ParquetType::PrimitiveType { physical_type, .. }, _) => match page.dictionary_page() {
-            None => match physical_type {
-                PhysicalType::Int64 => {
-                    Ok(primitive_nested::page_to_array::<i64>(page, descriptor)?)
-                }
-                _ => todo!(),
-            },
-            Some(_) => match physical_type {
-                PhysicalType::Int64 => Ok(primitive_nested::page_dict_to_array::<i64>(
-                    page, descriptor,
-                )?),
-                _ => todo!(),
-            },
-        },
-        _ => todo!("Nested types are not supported by this in-memory format"),
+        _ => unreachable!("The type of a column is primitive"),
+    }
+}
+
+/// Reads the pages of a column chunk into an [`Array`], extending `validity` with the
+/// definition levels of its pages if given.
+fn pages_to_array<I>(
+    pages: I,
+    descriptor: &ColumnDescriptor,
+    mut validity: Option<&mut Vec<bool>>,
+) -> Result<Array>
+where
+    I: Iterator<Item = Result<CompressedDataPage>>,
+{
+    let mut decoder = if descriptor.max_rep_level() > 0 {
+        Some(NestedDecoder::try_new(descriptor)?)
+    } else {
+        None
+    };
+
+    let mut arrays = vec![];
+    let mut iterator = BasicDecompressor::new(pages, vec![]);
+    while let Some(page) = iterator.next()? {
+        if let Some(validity) = validity.as_mut() {
+            struct_::extend_validity(validity, page, descriptor)?;
+        }
+        if let Some(decoder) = decoder.as_mut() {
+            decoder.extend_from_page(page)?;
+        }
+        arrays.push(page_to_array(page, descriptor)?)
+    }
+    let array = nested::concat(arrays)?;
+
+    match decoder {
+        Some(decoder) => nested::assemble(&decoder.finish(), array),
+        None => Ok(array),
     }
 }
 
@@ -98,14 +134,12 @@ where
     let mut arrays = vec![];
     while let State::Some(mut new_iter) = columns.advance()? {
         if let Some((pages, column)) = new_iter.get() {
-            let mut iterator = BasicDecompressor::new(pages, vec![]);
-            while let Some(page) = iterator.next()? {
-                if !has_filled {
-                    struct_::extend_validity(&mut validity, page, column.descriptor());
-                }
-                // todo: this is wrong: multiple pages -> array
-                arrays.push(page_to_array(page, column.descriptor())?)
-            }
+            let validity = if has_filled {
+                None
+            } else {
+                Some(&mut validity)
+            };
+            arrays.push(pages_to_array(pages, column.descriptor(), validity)?);
         }
         has_filled = true;
         columns = new_iter;
@@ -251,7 +285,7 @@ pub(crate) mod tests {
         if let Array::Int96(array) = array {
             let a = array
                 .into_iter()
-                .map(|x| x.map(int96_to_i64_ns))
+                .map(|x| x.and_then(int96_to_i64_ns))
                 .collect::<Vec<_>>();
             assert_eq!(expected, a);
         } else {
@@ -0,0 +1,116 @@
+use parquet::deserialize::Nested;
+use parquet::error::{ParquetError, Result};
+
+use super::Array;
+
+macro_rules! map_array {
+    ($array:expr, $values:ident => $expr:expr) => {
+        match $array {
+            Array::UInt32($values) => Ok(Array::UInt32($expr)),
+            Array::Int32($values) => Ok(Array::Int32($expr)),
+            Array::Int64($values) => Ok(Array::Int64($expr)),
+            Array::Int96($values) => Ok(Array::Int96($expr)),
+            Array::Float32($values) => Ok(Array::Float32($expr)),
+            Array::Float64($values) => Ok(Array::Float64($expr)),
+            Array::Boolean($values) => Ok(Array::Boolean($expr)),
+            Array::Binary($values) => Ok(Array::Binary($expr)),
+            Array::List($values) => Ok(Array::List($expr)),
+            Array::Struct(_, _) => Err(ParquetError::General(
+                "Structs in lists are not supported by this in-memory format".to_string(),
+            )),
+        }
+    };
+}
+
+/// Concatenates `arrays`, that must have the same type, into a single [`Array`].
+/// # Error
+/// Errors iff `arrays` is empty or its arrays are structs or of different types.
+pub fn concat(arrays: Vec<Array>) -> Result<Array> {
+    let mut arrays = arrays.into_iter();
+    let mut result = arrays
+        .next()
+        .ok_or_else(|| ParquetError::General("There are no arrays to concatenate".to_string()))?;
+    for array in arrays {
+        match (&mut result, array) {
+            (Array::UInt32(a), Array::UInt32(b)) => a.extend(b),
+            (Array::Int32(a), Array::Int32(b)) => a.extend(b),
+            (Array::Int64(a), Array::Int64(b)) => a.extend(b),
+            (Array::Int96(a), Array::Int96(b)) => a.extend(b),
+            (Array::Float32(a), Array::Float32(b)) => a.extend(b),
+            (Array::Float64(a), Array::Float64(b)) => a.extend(b),
+            (Array::Boolean(a), Array::Boolean(b)) => a.extend(b),
+            (Array::Binary(a), Array::Binary(b)) => a.extend(b),
+            (Array::List(a), Array::List(b)) => a.extend(b),
+            _ => {
+                return Err(ParquetError::General(
+                    "Only arrays of the same (non-struct) type can be concatenated".to_string(),
+                ))
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the first `length` values of `array`, removing them from it.
+fn take(array: &mut Array, length: usize) -> Result<Array> {
+    map_array!(array, values => values.drain(..length).collect())
+}
+
+/// Inserts a null in every invalid slot of `validity`, consuming `values` in the valid ones.
+fn expand(array: Array, validity: &[bool]) -> Result<Array> {
+    map_array!(array, values => {
+        let mut values = values.into_iter();
+        validity
+            .iter()
+            .map(|is_valid| if *is_valid { values.next().unwrap() } else { None })
+            .collect()
+    })
+}
+
+/// Sets every invalid slot of `validity` to null.
+fn mask(array: Array, validity: &[bool]) -> Result<Array> {
+    map_array!(array, values => values
+        .into_iter()
+        .zip(validity.iter())
+        .map(|(value, is_valid)| if *is_valid { value } else { None })
+        .collect())
+}
+
+/// Assembles the (non-null) values of the leaf of a nested column into an [`Array`], following
+/// the [`Nested`] structure of every node in the path of the column.
+/// # Error
+/// Errors iff the leaf is a struct, which this in-memory format cannot nest in lists.
+pub fn assemble(nested: &[Nested], values: Array) -> Result<Array> {
+    // the nodes after the last repeated node have the same slots as the leaf
+    let leaf_start = nested
+        .iter()
+        .rposition(|node| matches!(node, Nested::Repeated(_)))
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    // the deepest optional node of those is only valid when all the others are
+    let validity = nested[leaf_start..]
+        .iter()
+        .rev()
+        .find_map(|node| match node {
+            Nested::Optional(validity) => Some(validity),
+            _ => None,
+        });
+    let mut array = match validity {
+        Some(validity) => expand(values, validity)?,
+        None => values,
+    };
+
+    for node in nested[..leaf_start].iter().rev() {
+        array = match node {
+            Nested::Repeated(offsets) => Array::List(
+                offsets
+                    .windows(2)
+                    .map(|window| take(&mut array, (window[1] - window[0]) as usize).map(Some))
+                    .collect::<Result<_>>()?,
+            ),
+            Nested::Optional(validity) => mask(array, validity)?,
+            Nested::Required(_) => array,
+        }
+    }
+    Ok(array)
+}
@@ -27,7 +27,6 @@ mod tests {
 
     use crate::tests::{alltypes_plain, alltypes_statistics};
 
-    use parquet::compression::Compression;
     use parquet::error::Result;
     use parquet::metadata::SchemaDescriptor;
     use parquet::statistics::Statistics;
@@ -40,13 +39,38 @@ mod tests {
         Ok((a, statistics))
     }
 
+    /// Writes `pages` as the only column of `schema` in a single row group and returns the file
+    pub(super) fn write_column(
+        schema: SchemaDescriptor,
+        pages: Vec<Result<EncodedPage>>,
+        options: WriteOptions,
+    ) -> Result<Vec<u8>> {
+        let num_rows = pages
+            .iter()
+            .map(|page| match page {
+                Ok(EncodedPage::Data(page)) => page.num_rows().unwrap_or(0),
+                _ => 0,
+            })
+            .sum();
+        let pages = DynStreamingIterator::new(Compressor::new_from_vec(
+            DynIter::new(pages.into_iter()),
+            options.compression,
+            vec![],
+        ));
+        let columns = std::iter::once(Ok(pages));
+
+        let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+        writer.start()?;
+        writer.write(DynIter::new(columns), num_rows)?;
+        Ok(writer.end(None)?.1.into_inner())
+    }
+
     fn test_column(column: usize) -> Result<()> {
         let array = alltypes_plain(column);
 
         let options = WriteOptions {
-            write_statistics: true,
-            compression: Compression::Uncompressed,
             version: Version::V1,
+            ..Default::default()
         };
 
         // prepare schema
@@ -63,24 +87,8 @@ mod tests {
             a
         ))?;
 
-        let a = schema.columns();
-
-        let num_rows = array.len();
-        let pages = DynStreamingIterator::new(Compressor::new_from_vec(
-            DynIter::new(std::iter::once(array_to_page(&array, &options, &a[0]))),
-            options.compression,
-            vec![],
-        ));
-        let columns = std::iter::once(Ok(pages));
-
-        let writer = Cursor::new(vec![]);
-        let mut writer = FileWriter::new(writer, schema, options, None);
-
-        writer.start()?;
-        writer.write(DynIter::new(columns), num_rows)?;
-        let writer = writer.end(None)?.1;
-
-        let data = writer.into_inner();
+        let page = array_to_page(&array, &options, &schema.columns()[0]);
+        let data = write_column(schema, vec![page], options)?;
 
         let (result, statistics) = read_column(&mut Cursor::new(data))?;
         assert_eq!(array, result);
@@ -137,16 +145,37 @@ mod tests {
 #[cfg(test)]
 mod tests2 {
     use std::io::Cursor;
+    use std::sync::Arc;
 
     use super::*;
 
-    use crate::write::primitive::array_to_page_v1;
+    use super::tests::write_column;
+    use crate::read::columns_to_array;
+    use crate::write::primitive::{array_to_page_v1, array_to_page_v1_encoded};
     use parquet::{
-        compression::Compression,
-        error::Result,
-        metadata::SchemaDescriptor,
-        read::read_metadata,
-        write::{Compressor, DynIter, DynStreamingIterator, FileWriter, Version},
+        bloom_filter,
+        compression::{Compression, CompressionOptions},
+        encoding::Encoding,
+        encryption::{
+            ColumnEncryptionKey, EncryptionAlgorithm, FileDecryptionProperties,
+            FileEncryptionProperties,
+        },
+        error::{ParquetError, Result},
+        fallible_streaming_iterator,
+        indexes::{select_pages, BoundaryOrder, Interval, NativeIndex, PageIndex, PageLocation},
+        metadata::{FileMetaData, SchemaDescriptor, SortOrd},
+        read::{
+            get_column_iterator, get_page_iterator, get_page_stream_with_options,
+            read_columns_indexes, read_metadata, read_metadata_with_decryption,
+            read_pages_locations, IndexedPageReader, PageStreamOptions,
+        },
+        statistics::PrimitiveStatistics,
+        types::NativeType,
+        write::{
+            compress, shred, BinaryDictEncoder, BloomFilterOptions, ColumnWriteOptions, Compressor,
+            DynIter, DynStreamingIterator, FileWriter, PrimitiveDictEncoder, Value, Version,
+            DEFAULT_MAX_DICTIONARY_SIZE,
+        },
     };
 
     #[test]
@@ -163,18 +192,372 @@ mod tests2 {
 
         let options = WriteOptions {
             write_statistics: false,
-            compression: Compression::Uncompressed,
             version: Version::V1,
+            ..Default::default()
+        };
+
+        let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+        let page = array_to_page_v1(&array, &options, &schema.columns()[0]);
+        let data = write_column(schema, vec![page], options)?;
+        let mut reader = Cursor::new(data);
+
+        let metadata = read_metadata(&mut reader)?;
+
+        // validated against an equivalent array produced by pyarrow.
+        let expected = 51;
+        assert_eq!(
+            metadata.row_groups[0].columns()[0].uncompressed_size(),
+            expected
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn compression_options() -> Result<()> {
+        let array = (0..1000).map(|x| Some(x % 10)).collect::<Vec<_>>();
+        for compression in [
+            CompressionOptions::Zstd(19),
+            CompressionOptions::Gzip(9),
+            CompressionOptions::Brotli(5, 20),
+            CompressionOptions::Lz4Raw,
+        ] {
+            let options = WriteOptions {
+                write_statistics: false,
+                compression,
+                version: Version::V1,
+                ..Default::default()
+            };
+            let schema =
+                SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+            let page = array_to_page_v1(&array, &options, &schema.columns()[0]);
+            let data = write_column(schema, vec![page], options)?;
+
+            // only the codec is written, not its parameters
+            let metadata = read_metadata(&mut Cursor::new(data.clone()))?;
+            assert_eq!(
+                metadata.row_groups[0].columns()[0].compression(),
+                compression.into()
+            );
+            let (result, _) = super::super::read::tests::read_column(&mut Cursor::new(data), 0, 0)?;
+            assert_eq!(result, Array::Int32(array.clone()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn column_options() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            compression: CompressionOptions::Snappy,
+            version: Version::V1,
+            ..Default::default()
+        };
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema { OPTIONAL INT64 id; OPTIONAL FLOAT payload; OPTIONAL INT32 other; }",
+        )?;
+        let ids = vec![Some(3), None, Some(1), Some(3)];
+        let payload = vec![Some(0.5), Some(1.5), None, Some(-1.0)];
+        let other = vec![Some(1), Some(2), Some(3), None];
+
+        let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+        writer.set_column_options(
+            "id",
+            ColumnWriteOptions {
+                compression: Some(CompressionOptions::Zstd(3)),
+                write_statistics: Some(true),
+                dictionary: Some(true),
+                ..Default::default()
+            },
+        )?;
+        writer.set_column_options(
+            "payload",
+            ColumnWriteOptions {
+                compression: Some(CompressionOptions::Uncompressed),
+                write_statistics: Some(false),
+                dictionary: Some(false),
+                encoding: Some(Encoding::ByteStreamSplit),
+            },
+        )?;
+        assert!(writer
+            .set_column_options("unknown", Default::default())
+            .is_err());
+
+        // pages are encoded and compressed with the options of their column
+        let (id_options, column) = writer.column_options("id").unwrap();
+        assert_eq!(column.dictionary, Some(true));
+        let mut encoder = PrimitiveDictEncoder::<i64>::try_new(
+            schema.columns()[0].clone(),
+            DEFAULT_MAX_DICTIONARY_SIZE,
+        )?;
+        let page = encoder.encode(&ids, &id_options)?;
+        let id_pages = vec![Ok(encoder.dict_page().unwrap()), Ok(page)];
+
+        let (payload_options, column) = writer.column_options("payload").unwrap();
+        let payload_pages = vec![array_to_page_v1_encoded(
+            &payload,
+            &payload_options,
+            &schema.columns()[1],
+            column.encoding.unwrap(),
+        )];
+
+        let (other_options, _) = writer.column_options("other").unwrap();
+        assert_eq!(other_options, options);
+        let other_pages = vec![array_to_page_v1(
+            &other,
+            &other_options,
+            &schema.columns()[2],
+        )];
+
+        let columns = vec![
+            (id_pages, id_options),
+            (payload_pages, payload_options),
+            (other_pages, other_options),
+        ]
+        .into_iter()
+        .map(|(pages, options)| {
+            Ok(DynStreamingIterator::new(Compressor::new_from_vec(
+                DynIter::new(pages.into_iter()),
+                options.compression,
+                vec![],
+            )))
+        });
+
+        writer.start()?;
+        writer.write(DynIter::new(columns), ids.len())?;
+        let data = writer.end(None)?.1.into_inner();
+
+        let metadata = read_metadata(&mut Cursor::new(data.clone()))?;
+        let chunks = metadata.row_groups[0].columns();
+        assert_eq!(chunks[0].compression(), Compression::Zstd);
+        assert!(chunks[0].statistics().is_some());
+        assert_eq!(chunks[1].compression(), Compression::Uncompressed);
+        assert!(chunks[1].statistics().is_none());
+        assert_eq!(chunks[2].compression(), Compression::Snappy);
+        assert!(chunks[2].statistics().is_none());
+
+        let read =
+            |field| super::super::read::tests::read_column(&mut Cursor::new(&data), 0, field);
+        assert_eq!(read(0)?.0, Array::Int64(ids));
+        assert_eq!(read(1)?.0, Array::Float32(payload));
+        assert_eq!(read(2)?.0, Array::Int32(other));
+        Ok(())
+    }
+
+    #[test]
+    fn column_options_contradicted() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            compression: CompressionOptions::Snappy,
+            version: Version::V1,
+            ..Default::default()
+        };
+        let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+        let array = vec![Some(1), None, Some(3)];
+        let descriptor = &schema.columns()[0];
+
+        let plain = || array_to_page_v1(&array, &options, descriptor);
+        let split =
+            || array_to_page_v1_encoded(&array, &options, descriptor, Encoding::ByteStreamSplit);
+        let dictionary = || -> Result<Vec<EncodedPage>> {
+            let mut encoder = PrimitiveDictEncoder::<i32>::try_new(
+                descriptor.clone(),
+                DEFAULT_MAX_DICTIONARY_SIZE,
+            )?;
+            let page = encoder.encode(&array, &options)?;
+            Ok(vec![encoder.dict_page().unwrap(), page])
+        };
+
+        let write = |column: ColumnWriteOptions,
+                     pages: Vec<EncodedPage>,
+                     compression: &[CompressionOptions]|
+         -> Result<Vec<u8>> {
+            let pages = pages
+                .into_iter()
+                .zip(compression.iter().cycle())
+                .map(|(page, compression)| compress(page, vec![], *compression))
+                .collect::<Result<Vec<_>>>()?;
+            let pages = DynStreamingIterator::new(fallible_streaming_iterator::convert(
+                pages.iter().map(Ok::<_, ParquetError>),
+            ));
+
+            let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+            writer.set_column_options("col", column)?;
+            writer.start()?;
+            writer.write(DynIter::new(std::iter::once(Ok(pages))), array.len())?;
+            Ok(writer.end(None)?.1.into_inner())
+        };
+        let snappy = [CompressionOptions::Snappy];
+
+        // the codec is the one of the pages, that must agree
+        let data = write(
+            Default::default(),
+            vec![plain()?, plain()?],
+            &[CompressionOptions::Zstd(1)],
+        )?;
+        let metadata = read_metadata(&mut Cursor::new(data))?;
+        assert_eq!(
+            metadata.row_groups[0].columns()[0].compression(),
+            Compression::Zstd
+        );
+        assert!(write(
+            Default::default(),
+            vec![plain()?, plain()?],
+            &[CompressionOptions::Snappy, CompressionOptions::Zstd(1)],
+        )
+        .is_err());
+
+        let column = |dictionary, encoding| ColumnWriteOptions {
+            dictionary,
+            encoding,
+            ..Default::default()
+        };
+        let mut fallback = dictionary()?;
+        fallback.push(plain()?);
+        assert!(write(column(Some(true), None), fallback, &snappy).is_ok());
+        assert!(write(column(Some(true), None), vec![plain()?], &snappy).is_err());
+        assert!(write(column(Some(false), None), dictionary()?, &snappy).is_err());
+        assert!(write(column(Some(false), None), vec![plain()?], &snappy).is_ok());
+
+        let split_column = column(None, Some(Encoding::ByteStreamSplit));
+        assert!(write(split_column, vec![split()?], &snappy).is_ok());
+        assert!(write(split_column, dictionary()?, &snappy).is_ok());
+        assert!(write(split_column, vec![plain()?], &snappy).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn indexes() -> Result<()> {
+        let array1 = vec![Some(1), None, Some(3)];
+        let array2 = vec![Some(6), Some(4)];
+
+        let options = WriteOptions {
+            version: Version::V1,
+            ..Default::default()
         };
 
         let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
 
+        let pages = vec![
+            array_to_page_v1(&array1, &options, &schema.columns()[0]),
+            array_to_page_v1(&array2, &options, &schema.columns()[0]),
+        ];
+        let data = write_column(schema.clone(), pages, options)?;
+        let mut reader = Cursor::new(data);
+
+        let metadata = read_metadata(&mut reader)?;
+        let columns = metadata.row_groups[0].columns();
+
+        let indexes = read_columns_indexes(&mut reader, columns)?;
+        let expected = NativeIndex::<i32> {
+            descriptor: schema.columns()[0].clone(),
+            indexes: vec![
+                PageIndex {
+                    min: Some(1),
+                    max: Some(3),
+                    null_count: Some(1),
+                },
+                PageIndex {
+                    min: Some(4),
+                    max: Some(6),
+                    null_count: Some(0),
+                },
+            ],
+            boundary_order: BoundaryOrder::Ascending,
+        };
+        assert_eq!(
+            indexes[0]
+                .as_ref()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<NativeIndex<i32>>(),
+            Some(&expected)
+        );
+
+        let locations = read_pages_locations(&mut reader, columns)?;
+        let locations = locations[0].as_ref().unwrap();
+        assert_eq!(locations.len(), 2);
+        assert_eq!(locations[0].offset, columns[0].data_page_offset());
+        assert_eq!(
+            locations[1],
+            PageLocation {
+                offset: locations[0].offset + locations[0].compressed_page_size as i64,
+                compressed_page_size: locations[1].compressed_page_size,
+                first_row_index: 3,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn indexed_pages() -> Result<()> {
+        let arrays = vec![
+            vec![Some(1), None, Some(3)],
+            vec![Some(4), Some(5)],
+            vec![None, Some(7), Some(8), Some(9)],
+        ];
+
+        let options = WriteOptions {
+            version: Version::V1,
+            ..Default::default()
+        };
+
+        let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+        let pages = arrays
+            .iter()
+            .map(|array| array_to_page_v1(array, &options, &schema.columns()[0]))
+            .collect::<Vec<_>>();
+        let data = write_column(schema, pages, options)?;
+        let mut reader = Cursor::new(data);
+
+        let metadata = read_metadata(&mut reader)?;
+        let columns = metadata.row_groups[0].columns();
+        let num_rows = metadata.row_groups[0].num_rows() as usize;
+
+        let locations = read_pages_locations(&mut reader, columns)?;
+        let locations = locations[0].as_ref().unwrap();
+
+        // rows 2 and 6..8 are in the first and last page only
+        let intervals = vec![Interval::new(2, 1), Interval::new(6, 2)];
+        let pages = select_pages(&intervals, locations, num_rows)?;
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].first_row_index, 0);
+        assert_eq!(pages[1].first_row_index, 5);
+
+        let pages = IndexedPageReader::new(reader, &columns[0], pages, vec![])
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].num_values(), 3);
+        assert_eq!(pages[0].selected_rows(), Some(&[Interval::new(2, 1)][..]));
+        assert_eq!(pages[1].num_values(), 4);
+        assert_eq!(pages[1].selected_rows(), Some(&[Interval::new(1, 2)][..]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bloom_filter() -> Result<()> {
+        let array1 = vec![Some(1), None, Some(3)];
+        let array2 = vec![Some(6), Some(4)];
+
+        let options = WriteOptions {
+            compression: CompressionOptions::Snappy,
+            version: Version::V1,
+            ..Default::default()
+        };
+
+        let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+        let pages = vec![
+            array_to_page_v1(&array1, &options, &schema.columns()[0]),
+            array_to_page_v1(&array2, &options, &schema.columns()[0]),
+        ];
         let pages = DynStreamingIterator::new(Compressor::new_from_vec(
-            DynIter::new(std::iter::once(array_to_page_v1(
-                &array,
-                &options,
-                &schema.columns()[0],
-            ))),
+            DynIter::new(pages.into_iter()),
             options.compression,
             vec![],
         ));
@@ -182,23 +565,496 @@ mod tests2 {
 
         let writer = Cursor::new(vec![]);
         let mut writer = FileWriter::new(writer, schema, options, None);
+        writer.set_bloom_filter("col", Some(BloomFilterOptions { ndv: 4, fpp: 0.01 }))?;
+        assert!(writer.bloom_filter("col").is_some());
+        assert!(writer.set_bloom_filter("unknown", None).is_err());
 
         writer.start()?;
-        writer.write(DynIter::new(columns), 7)?;
+        writer.write(DynIter::new(columns), 5)?;
         let writer = writer.end(None)?.1;
 
         let data = writer.into_inner();
         let mut reader = Cursor::new(data);
 
         let metadata = read_metadata(&mut reader)?;
+        let column = &metadata.row_groups[0].columns()[0];
 
-        // validated against an equivalent array produced by pyarrow.
-        let expected = 51;
-        assert_eq!(
-            metadata.row_groups[0].columns()[0].uncompressed_size(),
-            expected
+        let filter = bloom_filter::read(column, &mut reader)?.unwrap();
+        for value in [1i32, 3, 4, 6] {
+            assert!(filter.contains(bloom_filter::hash_native(value)));
+        }
+        assert!(!filter.contains(bloom_filter::hash_native(2i32)));
+
+        // the bloom filter is not part of the pages
+        let pages = get_page_iterator(column, &mut reader, None, vec![])?;
+        assert_eq!(pages.count(), 2);
+
+        Ok(())
+    }
+
+    const FOOTER_KEY: &[u8] = b"0123456789012345";
+    const COLUMN_KEY: &[u8] = b"012345678901234567890123";
+
+    fn encrypted_arrays(row_group: usize) -> [Vec<Option<i32>>; 2] {
+        let a = vec![Some(1), None, Some(3)];
+        let b = vec![Some(6), Some(4), None];
+        if row_group == 0 {
+            [a, b]
+        } else {
+            [b, a]
+        }
+    }
+
+    /// Writes two row groups of two columns (`a` and `b`) encrypted with `properties`
+    fn write_encrypted(properties: FileEncryptionProperties) -> Result<Vec<u8>> {
+        let options = WriteOptions {
+            compression: CompressionOptions::Snappy,
+            version: Version::V1,
+            ..Default::default()
+        };
+
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema { OPTIONAL INT32 a; OPTIONAL INT32 b; }",
+        )?;
+
+        let writer = Cursor::new(vec![]);
+        let mut writer = FileWriter::new(writer, schema.clone(), options, None);
+        writer.set_encryption(Some(properties))?;
+
+        writer.start()?;
+        for row_group in 0..2 {
+            let columns = encrypted_arrays(row_group)
+                .iter()
+                .zip(schema.columns())
+                .map(|(array, descriptor)| {
+                    Ok(DynStreamingIterator::new(Compressor::new_from_vec(
+                        DynIter::new(std::iter::once(array_to_page_v1(
+                            array, &options, descriptor,
+                        ))),
+                        options.compression,
+                        vec![],
+                    )))
+                })
+                .collect::<Vec<_>>();
+            writer.write(DynIter::new(columns.into_iter()), 3)?;
+        }
+        Ok(writer.end(None)?.1.into_inner())
+    }
+
+    fn read_encrypted(
+        data: &[u8],
+        metadata: &FileMetaData,
+        row_group: usize,
+        field: usize,
+    ) -> Result<Array> {
+        let mut reader = Cursor::new(data);
+        let columns = get_column_iterator(&mut reader, metadata, row_group, field, None, vec![]);
+        columns_to_array(columns, &metadata.schema().fields()[field])
+    }
+
+    #[test]
+    fn encrypted_footer() -> Result<()> {
+        let mut properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+        properties.algorithm = EncryptionAlgorithm::AesGcmCtrV1;
+        let data = write_encrypted(properties)?;
+        assert_eq!(&data[..4], b"PARE");
+        assert_eq!(&data[data.len() - 4..], b"PARE");
+
+        // the metadata can't be read without keys
+        assert!(read_metadata(&mut Cursor::new(&data)).is_err());
+        let properties = FileDecryptionProperties::new(COLUMN_KEY.to_vec());
+        assert!(read_metadata_with_decryption(&mut Cursor::new(&data), &properties).is_err());
+
+        let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+        for row_group in 0..2 {
+            for (field, array) in encrypted_arrays(row_group).iter().enumerate() {
+                let column = &metadata.row_groups[row_group].columns()[field];
+                assert!(column.is_encrypted());
+                assert!(column.statistics().is_some());
+                let result = read_encrypted(&data, &metadata, row_group, field)?;
+                assert_eq!(result, Array::Int32(array.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn encrypted_footer_column_keys() -> Result<()> {
+        let mut properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+        properties.column_keys.insert(
+            "b".to_string(),
+            ColumnEncryptionKey {
+                key: COLUMN_KEY.to_vec(),
+                key_metadata: Some(b"b".to_vec()),
+            },
+        );
+        let data = write_encrypted(properties)?;
+        assert_eq!(&data[data.len() - 4..], b"PARE");
+
+        // without the column key, only the column fails to be read
+        let footer_only = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &footer_only)?;
+        let columns = metadata.row_groups[0].columns();
+        assert!(columns[0].has_metadata());
+        assert!(!columns[1].has_metadata());
+        assert!(columns[1].statistics().is_none());
+        let result = read_encrypted(&data, &metadata, 0, 0)?;
+        assert_eq!(result, Array::Int32(encrypted_arrays(0)[0].clone()));
+        assert!(read_encrypted(&data, &metadata, 0, 1).is_err());
+        assert!(bloom_filter::read(&columns[1], &mut Cursor::new(&data)).is_err());
+
+        let mut properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        properties
+            .column_keys
+            .insert("b".to_string(), COLUMN_KEY.to_vec());
+        let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+        for row_group in 0..2 {
+            assert!(metadata.row_groups[row_group].total_byte_size() > 0);
+            for (field, array) in encrypted_arrays(row_group).iter().enumerate() {
+                // only the column with a key is encrypted
+                let column = &metadata.row_groups[row_group].columns()[field];
+                assert_eq!(column.is_encrypted(), field == 1);
+                assert!(column.statistics().is_some());
+                let result = read_encrypted(&data, &metadata, row_group, field)?;
+                assert_eq!(result, Array::Int32(array.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn plaintext_footer() -> Result<()> {
+        let mut properties = FileEncryptionProperties::new(FOOTER_KEY.to_vec());
+        properties.column_keys.insert(
+            "b".to_string(),
+            ColumnEncryptionKey {
+                key: COLUMN_KEY.to_vec(),
+                key_metadata: Some(b"b".to_vec()),
+            },
         );
+        properties.aad_prefix = Some(b"file.parquet".to_vec());
+        properties.store_aad_prefix = false;
+        properties.plaintext_footer = true;
+        let data = write_encrypted(properties)?;
+        assert_eq!(&data[data.len() - 4..], b"PAR1");
+
+        // without keys, only the plaintext column can be read
+        let metadata = read_metadata(&mut Cursor::new(&data))?;
+        let columns = metadata.row_groups[1].columns();
+        assert!(!columns[0].is_encrypted());
+        assert!(columns[1].is_encrypted());
+        assert!(columns[1].statistics().is_none());
+        let result = read_encrypted(&data, &metadata, 1, 0)?;
+        assert_eq!(result, Array::Int32(encrypted_arrays(1)[0].clone()));
+
+        // the AAD prefix is required to verify the footer
+        let mut properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        properties
+            .column_keys
+            .insert("b".to_string(), COLUMN_KEY.to_vec());
+        assert!(read_metadata_with_decryption(&mut Cursor::new(&data), &properties).is_err());
+
+        properties.aad_prefix = Some(b"file.parquet".to_vec());
+
+        // without the column key, only its pages can't be read
+        let mut footer_only = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        footer_only.aad_prefix = properties.aad_prefix.clone();
+        let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &footer_only)?;
+        let result = read_encrypted(&data, &metadata, 1, 0)?;
+        assert_eq!(result, Array::Int32(encrypted_arrays(1)[0].clone()));
+        assert!(read_encrypted(&data, &metadata, 1, 1).is_err());
+
+        let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+        for row_group in 0..2 {
+            for (field, array) in encrypted_arrays(row_group).iter().enumerate() {
+                let column = &metadata.row_groups[row_group].columns()[field];
+                assert!(column.statistics().is_some());
+                let result = read_encrypted(&data, &metadata, row_group, field)?;
+                assert_eq!(result, Array::Int32(array.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn page_crc() -> Result<()> {
+        let array = vec![Some(1), None, Some(3)];
+
+        let options = WriteOptions {
+            version: Version::V1,
+            write_page_crc: true,
+            ..Default::default()
+        };
+
+        let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+        let page = array_to_page_v1(&array, &options, &schema.columns()[0]);
+        let mut data = write_column(schema, vec![page], options)?;
+
+        let metadata = read_metadata(&mut Cursor::new(&data))?;
+        let column = &metadata.row_groups[0].columns()[0];
+        let (start, length) = column.byte_range();
+
+        let mut pages = get_page_iterator(column, Cursor::new(&data), None, vec![])?;
+        pages.set_verify_crc(true);
+        assert_eq!(pages.count(), 1);
+
+        // corrupt the last byte of the page
+        data[(start + length) as usize - 1] ^= 1;
+        let mut pages = get_page_iterator(column, Cursor::new(&data), None, vec![])?;
+        pages.set_verify_crc(true);
+        match pages.next() {
+            Some(Err(ParquetError::InvalidChecksum(column, offset))) => {
+                assert_eq!(column, "col");
+                assert_eq!(offset, start);
+            }
+            _ => panic!("the corrupted page must fail verification"),
+        }
+
+        // the checksum is not verified by default
+        let pages = get_page_iterator(column, Cursor::new(&data), None, vec![])?;
+        assert_eq!(pages.count(), 1);
+
+        // pages read via the offset index
+        let mut reader = Cursor::new(&data);
+        let locations = read_pages_locations(&mut reader, &metadata.row_groups[0].columns()[..1])?;
+        let pages = select_pages(&[Interval::new(0, 3)], locations[0].as_ref().unwrap(), 3)?;
+        let mut pages = IndexedPageReader::new(reader, column, pages, vec![]);
+        pages.set_verify_crc(true);
+        assert!(matches!(
+            pages.next(),
+            Some(Err(ParquetError::InvalidChecksum(_, offset))) if offset == start
+        ));
+
+        // pages read asynchronously
+        let mut reader = futures::io::Cursor::new(&data);
+        let result = futures::executor::block_on(async {
+            let options = PageStreamOptions { verify_crc: true };
+            let pages = get_page_stream_with_options(
+                column,
+                &mut reader,
+                vec![],
+                Arc::new(|_, _| true),
+                options,
+            )
+            .await?;
+            futures::pin_mut!(pages);
+            futures::StreamExt::next(&mut pages).await.unwrap()
+        });
+        assert!(matches!(
+            result,
+            Err(ParquetError::InvalidChecksum(_, offset)) if offset == start
+        ));
+        Ok(())
+    }
+
+    fn byte_stream_split_column<T: NativeType + SortOrd>(
+        array: &[Option<T>],
+        physical_type: &str,
+    ) -> Result<Array> {
+        let options = WriteOptions {
+            write_statistics: false,
+            compression: CompressionOptions::Zstd(1),
+            version: Version::V1,
+            ..Default::default()
+        };
+
+        let schema = SchemaDescriptor::try_from_message(&format!(
+            "message schema {{ OPTIONAL {} col; }}",
+            physical_type
+        ))?;
+
+        let page = array_to_page_v1_encoded(
+            array,
+            &options,
+            &schema.columns()[0],
+            Encoding::ByteStreamSplit,
+        );
+        let data = write_column(schema, vec![page], options)?;
+
+        let (result, _) = super::super::read::tests::read_column(&mut Cursor::new(data), 0, 0)?;
+        Ok(result)
+    }
+
+    #[test]
+    fn byte_stream_split() -> Result<()> {
+        let array = vec![Some(1.5f32), None, Some(-3.25), Some(f32::MAX)];
+        let result = byte_stream_split_column(&array, "FLOAT")?;
+        assert_eq!(result, Array::Float32(array));
+
+        let array = vec![Some(1.5f64), Some(0.1), None];
+        let result = byte_stream_split_column(&array, "DOUBLE")?;
+        assert_eq!(result, Array::Float64(array));
+
+        let array = vec![None, Some(i32::MIN), Some(7)];
+        let result = byte_stream_split_column(&array, "INT32")?;
+        assert_eq!(result, Array::Int32(array));
+
+        let array = vec![Some(i64::MAX), Some(-7), None];
+        let result = byte_stream_split_column(&array, "INT64")?;
+        assert_eq!(result, Array::Int64(array));
+        Ok(())
+    }
+
+    fn write_dict_pages(
+        physical_type: &str,
+        version: Version,
+        pages: Vec<EncodedPage>,
+    ) -> Result<Array> {
+        let options = WriteOptions {
+            write_statistics: false,
+            compression: CompressionOptions::Snappy,
+            version,
+            ..Default::default()
+        };
+
+        let schema = SchemaDescriptor::try_from_message(&format!(
+            "message schema {{ OPTIONAL {} col; }}",
+            physical_type
+        ))?;
+
+        let data = write_column(schema, pages.into_iter().map(Ok).collect(), options)?;
+
+        let (result, _) = super::super::read::tests::read_column(&mut Cursor::new(data), 0, 0)?;
+        Ok(result)
+    }
+
+    #[test]
+    fn dictionary() -> Result<()> {
+        for version in [Version::V1, Version::V2] {
+            let options = WriteOptions {
+                write_statistics: false,
+                compression: CompressionOptions::Snappy,
+                version,
+                ..Default::default()
+            };
+            let schema =
+                SchemaDescriptor::try_from_message("message schema { OPTIONAL INT64 col; }")?;
+            let array = vec![Some(10), None, Some(-1), Some(10), Some(i64::MAX), None];
+            let mut encoder = PrimitiveDictEncoder::<i64>::try_new(
+                schema.columns()[0].clone(),
+                DEFAULT_MAX_DICTIONARY_SIZE,
+            )?;
+            let page = encoder.encode(&array, &options)?;
+            let pages = vec![encoder.dict_page().unwrap(), page];
+            let result = write_dict_pages("INT64", version, pages)?;
+            assert_eq!(result, Array::Int64(array));
+
+            let schema =
+                SchemaDescriptor::try_from_message("message schema { OPTIONAL BYTE_ARRAY col; }")?;
+            let array = vec![
+                Some(b"aa".to_vec()),
+                Some(vec![]),
+                None,
+                Some(b"aa".to_vec()),
+            ];
+            let mut encoder = BinaryDictEncoder::try_new(
+                schema.columns()[0].clone(),
+                DEFAULT_MAX_DICTIONARY_SIZE,
+            )?;
+            let page = encoder.encode(&array, &options)?;
+            let pages = vec![encoder.dict_page().unwrap(), page];
+            let result = write_dict_pages("BYTE_ARRAY", version, pages)?;
+            assert_eq!(result, Array::Binary(array));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn nested() -> Result<()> {
+        let list = |values: &[Option<i64>]| {
+            Value::Group(vec![Value::List(
+                values
+                    .iter()
+                    .map(|x| x.map_or(Value::Null, Value::Int64))
+                    .collect(),
+            )])
+        };
+        // two pages: [[1, None], None, []] and [[2, 3]]
+        let rows = [
+            vec![
+                list(&[Some(1), None]),
+                Value::Group(vec![Value::Null]),
+                list(&[]),
+            ],
+            vec![list(&[Some(2), Some(3)])],
+        ];
+
+        for version in [Version::V1, Version::V2] {
+            let options = WriteOptions {
+                write_statistics: false,
+                compression: CompressionOptions::Snappy,
+                version,
+                ..Default::default()
+            };
+            let schema = SchemaDescriptor::try_from_message(
+                "message schema {
+                    OPTIONAL group col (LIST) {
+                        REPEATED group list {
+                            OPTIONAL INT64 element;
+                        }
+                    }
+                }",
+            )?;
+            let pages = rows
+                .iter()
+                .map(|rows| shred(&schema, rows)?[0].to_page(&options))
+                .collect::<Vec<_>>();
+            let data = write_column(schema, pages, options)?;
+
+            let (result, _) = super::super::read::tests::read_column(&mut Cursor::new(data), 0, 0)?;
+            let expected = Array::List(vec![
+                Some(Array::Int64(vec![Some(1), None])),
+                None,
+                Some(Array::Int64(vec![])),
+                Some(Array::Int64(vec![Some(2), Some(3)])),
+            ]);
+            assert_eq!(result, expected);
+        }
+        Ok(())
+    }
+
+    fn write_distinct_count(write_distinct_count: bool) -> Result<Option<i64>> {
+        let options = WriteOptions {
+            write_distinct_count,
+            ..Default::default()
+        };
+        let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT64 col; }")?;
+        // a dictionary of at most 1000 values
+        let mut encoder = PrimitiveDictEncoder::<i64>::try_new(schema.columns()[0].clone(), 8000)?;
+        // 1500 distinct values over two pages, the second one of them PLAIN-encoded
+        let pages = [0..1000, 500..1500]
+            .iter()
+            .map(|values| {
+                let values = values.clone().map(Some).chain([None]).collect::<Vec<_>>();
+                encoder.encode(&values, &options)
+            })
+            .collect::<Vec<_>>();
+        assert!(encoder.is_fallback());
+        let pages = std::iter::once(Ok(encoder.dict_page().unwrap()))
+            .chain(pages)
+            .collect();
+        let data = write_column(schema, pages, options)?;
+
+        let metadata = read_metadata(&mut Cursor::new(data))?;
+        let statistics = metadata.row_groups[0].columns()[0].statistics().unwrap()?;
+        let statistics = statistics
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<i64>>()
+            .unwrap();
+        assert_eq!(statistics.null_count, Some(2));
+        assert_eq!(statistics.min_value, Some(0));
+        assert_eq!(statistics.max_value, Some(1499));
+        Ok(statistics.distinct_count)
+    }
+
+    #[test]
+    fn distinct_count() -> Result<()> {
+        let distinct_count = write_distinct_count(true)?.unwrap();
+        assert!((distinct_count - 1500).abs() < 75, "{}", distinct_count);
 
+        assert_eq!(write_distinct_count(false)?, None);
         Ok(())
     }
 }
@@ -1,11 +1,14 @@
 use parquet::{
-    encoding::Encoding,
-    metadata::ColumnDescriptor,
+    encoding::{byte_stream_split, Encoding},
+    metadata::{ColumnDescriptor, SortOrd},
     page::{DataPage, DataPageHeader, DataPageHeaderV1, EncodedPage},
-    statistics::{serialize_statistics, PrimitiveStatistics, Statistics},
+    statistics::PrimitiveStatisticsBuilder,
     types::NativeType,
     write::WriteOptions,
-    {encoding::hybrid_rle::encode_bool, error::Result},
+    {
+        encoding::hybrid_rle::encode_bool,
+        error::{ParquetError, Result},
+    },
 };
 
 fn unzip_option<T: NativeType>(array: &[Option<T>]) -> Result<(Vec<u8>, Vec<u8>)> {
@@ -40,31 +43,46 @@ fn unzip_option<T: NativeType>(array: &[Option<T>]) -> Result<(Vec<u8>, Vec<u8>)
     Ok((values, validity))
 }
 
-pub fn array_to_page_v1<T: NativeType>(
+pub fn array_to_page_v1<T: NativeType + SortOrd>(
     array: &[Option<T>],
     options: &WriteOptions,
     descriptor: &ColumnDescriptor,
+) -> Result<EncodedPage> {
+    array_to_page_v1_encoded(array, options, descriptor, Encoding::Plain)
+}
+
+/// Encodes `array` into a page whose values are encoded with `encoding`
+/// (either plain or byte stream split)
+pub fn array_to_page_v1_encoded<T: NativeType + SortOrd>(
+    array: &[Option<T>],
+    options: &WriteOptions,
+    descriptor: &ColumnDescriptor,
+    encoding: Encoding,
 ) -> Result<EncodedPage> {
     let (values, mut buffer) = unzip_option(array)?;
 
-    buffer.extend_from_slice(&values);
+    match encoding {
+        Encoding::Plain => buffer.extend_from_slice(&values),
+        Encoding::ByteStreamSplit => {
+            byte_stream_split::encode_fixed_len(&values, std::mem::size_of::<T>(), &mut buffer)
+        }
+        _ => {
+            return Err(ParquetError::General(format!(
+                "Encoding {:?} is not supported by this in-memory format",
+                encoding
+            )))
+        }
+    }
 
-    let statistics = if options.write_statistics {
-        let statistics = &PrimitiveStatistics {
-            descriptor: descriptor.clone(),
-            null_count: Some((array.len() - array.iter().flatten().count()) as i64),
-            distinct_count: None,
-            max_value: array.iter().flatten().max_by(|x, y| x.ord(y)).copied(),
-            min_value: array.iter().flatten().min_by(|x, y| x.ord(y)).copied(),
-        } as &dyn Statistics;
-        Some(serialize_statistics(statistics))
-    } else {
-        None
-    };
+    let statistics = options.write_statistics.then(|| {
+        let mut statistics = PrimitiveStatisticsBuilder::new(descriptor);
+        statistics.extend(array.iter().copied());
+        statistics.finish()
+    });
 
     let header = DataPageHeaderV1 {
         num_values: array.len() as i32,
-        encoding: Encoding::Plain.into(),
+        encoding: encoding.into(),
         definition_level_encoding: Encoding::Rle.into(),
         repetition_level_encoding: Encoding::Rle.into(),
         statistics,
@@ -0,0 +1,33 @@
+use xxhash_rust::xxh64::xxh64;
+
+use crate::types::NativeType;
+
+const SEED: u64 = 0;
+
+/// (xxh64) hash of a [`NativeType`], i.e. of the physical types
+/// `INT32`, `INT64`, `INT96`, `FLOAT` and `DOUBLE`.
+#[inline]
+pub fn hash_native<T: NativeType>(value: T) -> u64 {
+    xxh64(value.to_le_bytes().as_ref(), SEED)
+}
+
+/// (xxh64) hash of a sequence of bytes (e.g. of the physical types
+/// `BYTE_ARRAY` and `FIXED_LEN_BYTE_ARRAY`).
+#[inline]
+pub fn hash_byte<A: AsRef<[u8]>>(value: A) -> u64 {
+    xxh64(value.as_ref(), SEED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values() {
+        assert_eq!(hash_byte(b""), 0xef46db3751d8e999);
+        assert_eq!(hash_byte(b"a"), 0xd24ec4f1a98c6e5b);
+        // the hash of a native type is the hash of its plain (little endian) encoding
+        assert_eq!(hash_native(1i32), hash_byte(1i32.to_le_bytes()));
+        assert_eq!(hash_native(1.5f64), hash_byte(1.5f64.to_le_bytes()));
+    }
+}
@@ -0,0 +1,14 @@
+//! API to read and use bloom filters of column chunks.
+//!
+//! Parquet only supports the split block bloom filter (SBBF) whose hashes are computed
+//! with xxhash64 (seed 0) over the plain encoding of the values. See
+//! [the spec](https://github.com/apache/parquet-format/blob/master/BloomFilter.md).
+mod hash;
+mod read;
+mod split_block;
+
+pub use hash::{hash_byte, hash_native};
+pub use read::read;
+#[cfg(feature = "stream")]
+pub use read::read_async;
+pub use split_block::{optimal_num_of_bytes, BloomFilter};
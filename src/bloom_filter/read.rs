@@ -0,0 +1,98 @@
+use std::convert::TryFrom;
+use std::io::{Read, Seek, SeekFrom};
+
+use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
+use parquet_format_async_temp::BloomFilterHeader;
+
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnChunkMetaData;
+
+use super::BloomFilter;
+
+fn bitset_len(header: &BloomFilterHeader) -> Result<usize> {
+    if header.num_bytes < 0 {
+        return Err(ParquetError::OutOfSpec(
+            "The number of bytes of a bloom filter must be positive".to_string(),
+        ));
+    }
+    Ok(header.num_bytes as usize)
+}
+
+/// Returns the offset of the bloom filter of `column_metadata`, if any.
+/// # Error
+/// Errors iff the offset is negative, or the column chunk is encrypted and may have a bloom
+/// filter, since bloom filters of encrypted columns are encrypted, which is not supported.
+fn bloom_filter_offset(column_metadata: &ColumnChunkMetaData) -> Result<Option<u64>> {
+    // without its metadata, whether the column has a bloom filter is unknown
+    if column_metadata.is_encrypted()
+        && (!column_metadata.has_metadata() || column_metadata.bloom_filter_offset().is_some())
+    {
+        return Err(general_err!(
+            "The bloom filter of the encrypted column \"{}\" is encrypted, which is not supported",
+            column_metadata.descriptor().path_in_schema().join(".")
+        ));
+    }
+    column_metadata
+        .bloom_filter_offset()
+        .map(|offset| {
+            u64::try_from(offset).map_err(|_| {
+                ParquetError::OutOfSpec(format!(
+                    "The offset of a bloom filter must be positive, but it is {}",
+                    offset
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Reads the bloom filter associated to [`ColumnChunkMetaData`] from `reader`.
+/// Returns `None` if the column chunk has no bloom filter, and errors if it is encrypted.
+///
+/// The header only supports one algorithm (split block), hash (xxhash64) and
+/// compression (none), which this function thus does not need to inspect.
+pub fn read<R: Read + Seek>(
+    column_metadata: &ColumnChunkMetaData,
+    mut reader: &mut R,
+) -> Result<Option<BloomFilter>> {
+    let offset = if let Some(offset) = bloom_filter_offset(column_metadata)? {
+        offset
+    } else {
+        return Ok(None);
+    };
+    reader.seek(SeekFrom::Start(offset))?;
+
+    // deserialize header
+    let mut prot = TCompactInputProtocol::new(&mut reader);
+    let header = BloomFilterHeader::read_from_in_protocol(&mut prot)?;
+
+    let mut bitset = vec![0; bitset_len(&header)?];
+    reader.read_exact(&mut bitset)?;
+
+    BloomFilter::try_from_bitset(bitset).map(Some)
+}
+
+/// Async version of [`read`].
+#[cfg(feature = "stream")]
+pub async fn read_async<R: futures::AsyncRead + futures::AsyncSeek + Send + Unpin>(
+    column_metadata: &ColumnChunkMetaData,
+    mut reader: &mut R,
+) -> Result<Option<BloomFilter>> {
+    use futures::{AsyncReadExt, AsyncSeekExt};
+    use parquet_format_async_temp::thrift::protocol::TCompactInputStreamProtocol;
+
+    let offset = if let Some(offset) = bloom_filter_offset(column_metadata)? {
+        offset
+    } else {
+        return Ok(None);
+    };
+    reader.seek(SeekFrom::Start(offset)).await?;
+
+    // deserialize header
+    let mut prot = TCompactInputStreamProtocol::new(&mut reader);
+    let header = BloomFilterHeader::stream_from_in_protocol(&mut prot).await?;
+
+    let mut bitset = vec![0; bitset_len(&header)?];
+    reader.read_exact(&mut bitset).await?;
+
+    BloomFilter::try_from_bitset(bitset).map(Some)
+}
@@ -0,0 +1,203 @@
+use std::convert::TryInto;
+
+use crate::error::{ParquetError, Result};
+
+/// magic numbers taken from https://github.com/apache/parquet-format/blob/master/BloomFilter.md
+const SALT: [u32; 8] = [
+    0x47b6137b_u32,
+    0x44974d91_u32,
+    0x8824ad5b_u32,
+    0xa2b7289d_u32,
+    0x705495c7_u32,
+    0x2df1424b_u32,
+    0x9efc4947_u32,
+    0x5c6bfb31_u32,
+];
+
+/// The number of bytes of a block
+const BLOCK_SIZE: usize = 32;
+
+/// The maximum number of bytes of a bloom filter, as used by other implementations
+const MAX_NUM_BYTES: usize = 128 * 1024 * 1024;
+
+/// Each block is 256 bits, broken up into eight contiguous "words", each consisting of 32 bits.
+/// Each word is thought of as an array of bits; each bit is either "set" or "not set".
+type Block = [u32; 8];
+
+/// takes as its argument a single unsigned 32-bit integer and returns a block in which each
+/// word has exactly one bit set.
+fn new_mask(x: u32) -> Block {
+    let mut a = [0u32; 8];
+    for i in 0..8 {
+        let mask = x.wrapping_mul(SALT[i]);
+        let mask = mask >> 27;
+        let mask = 0x1 << mask;
+        a[i] = mask;
+    }
+    a
+}
+
+/// loads a block from the bitset to the stack
+#[inline]
+fn load_block(bitset: &[u8]) -> Block {
+    let mut a = [0u32; 8];
+    let bitset = bitset.chunks_exact(4).take(8);
+    for (a, chunk) in a.iter_mut().zip(bitset) {
+        *a = u32::from_le_bytes(chunk.try_into().unwrap())
+    }
+    a
+}
+
+/// assigns a block from the stack to `bitset`
+#[inline]
+fn unload_block(block: Block, bitset: &mut [u8]) {
+    let bitset = bitset.chunks_exact_mut(4).take(8);
+    for (a, chunk) in block.iter().zip(bitset) {
+        let a = a.to_le_bytes();
+        chunk[0] = a[0];
+        chunk[1] = a[1];
+        chunk[2] = a[2];
+        chunk[3] = a[3];
+    }
+}
+
+/// Returns the number of bytes of a [`BloomFilter`] that holds `ndv` distinct values with
+/// a false positive probability of at most `fpp`.
+///
+/// The result is a power of two between the block size (32 bytes) and 128MiB.
+pub fn optimal_num_of_bytes(ndv: usize, fpp: f64) -> usize {
+    // see https://github.com/apache/parquet-format/blob/master/BloomFilter.md#sizing-an-sbbf
+    let num_bits = -8.0 * ndv as f64 / (1.0 - fpp.powf(1.0 / 8.0)).ln();
+    let num_bytes = (num_bits / 8.0).ceil();
+    let num_bytes = if num_bytes.is_finite() && num_bytes < MAX_NUM_BYTES as f64 {
+        num_bytes as usize
+    } else {
+        MAX_NUM_BYTES
+    };
+    num_bytes
+        .clamp(BLOCK_SIZE, MAX_NUM_BYTES)
+        .next_power_of_two()
+}
+
+/// A split block bloom filter (SBBF), the bloom filter algorithm of parquet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bitset: Vec<u8>,
+}
+
+impl BloomFilter {
+    /// Returns a new, empty, [`BloomFilter`] of `num_bytes` bytes, rounded up to
+    /// a multiple of the block size (32 bytes).
+    pub fn new(num_bytes: usize) -> Self {
+        let num_blocks = (num_bytes.max(1) - 1) / BLOCK_SIZE + 1;
+        Self {
+            bitset: vec![0; num_blocks * BLOCK_SIZE],
+        }
+    }
+
+    /// Returns a [`BloomFilter`] from a bitset, as written in parquet files.
+    /// # Error
+    /// Errors iff the length of the bitset is not a positive multiple of the block size
+    /// (32 bytes).
+    pub fn try_from_bitset(bitset: Vec<u8>) -> Result<Self> {
+        if bitset.is_empty() || bitset.len() % BLOCK_SIZE != 0 {
+            return Err(ParquetError::OutOfSpec(format!(
+                "The bitset of a bloom filter must be a multiple of {} bytes, but it has {}",
+                BLOCK_SIZE,
+                bitset.len()
+            )));
+        }
+        Ok(Self { bitset })
+    }
+
+    /// The bitset of this filter, as written in parquet files.
+    pub fn bitset(&self) -> &[u8] {
+        &self.bitset
+    }
+
+    /// Consumes itself, returning its bitset.
+    pub fn into_bitset(self) -> Vec<u8> {
+        self.bitset
+    }
+
+    /// The offset of the block of `hash` in the bitset
+    #[inline]
+    fn block_offset(&self, hash: u64) -> usize {
+        let num_blocks = (self.bitset.len() / BLOCK_SIZE) as u64;
+        let block_index = ((hash >> 32).wrapping_mul(num_blocks)) >> 32;
+        block_index as usize * BLOCK_SIZE
+    }
+
+    /// Returns whether the `hash` is in the set.
+    /// A `false` means that no value with this hash was inserted;
+    /// a `true` means that a value with this hash was likely inserted.
+    pub fn contains(&self, hash: u64) -> bool {
+        let offset = self.block_offset(hash);
+        let block = load_block(&self.bitset[offset..offset + BLOCK_SIZE]);
+        let mask = new_mask(hash as u32);
+
+        block.iter().zip(mask.iter()).all(|(b, m)| b & m != 0)
+    }
+
+    /// Inserts a new hash to the set
+    pub fn insert(&mut self, hash: u64) {
+        let offset = self.block_offset(hash);
+        let bitset = &mut self.bitset[offset..offset + BLOCK_SIZE];
+        let mut block = load_block(bitset);
+        let mask = new_mask(hash as u32);
+
+        block.iter_mut().zip(mask.iter()).for_each(|(b, m)| *b |= m);
+        unload_block(block, bitset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::hash_native;
+    use super::*;
+
+    #[test]
+    fn basics() {
+        let mut filter = BloomFilter::new(64);
+        assert_eq!(filter.bitset().len(), 64);
+
+        let inserted = (0..100i64).map(hash_native).collect::<Vec<_>>();
+        inserted.iter().for_each(|hash| filter.insert(*hash));
+
+        assert!(inserted.iter().all(|hash| filter.contains(*hash)));
+    }
+
+    #[test]
+    fn false_positive_probability() {
+        let fpp = 0.01;
+        let mut filter = BloomFilter::new(optimal_num_of_bytes(1_000, fpp));
+        (0..1_000i64).for_each(|x| filter.insert(hash_native(x)));
+
+        // the sizing is approximate: allow twice the configured probability
+        let num_absent = 100_000;
+        let false_positives = (1_000..1_000 + num_absent as i64)
+            .filter(|x| filter.contains(hash_native(*x)))
+            .count();
+        assert!(
+            (false_positives as f64) < 2.0 * fpp * num_absent as f64,
+            "{}",
+            false_positives
+        );
+    }
+
+    #[test]
+    fn num_of_bytes() {
+        assert_eq!(optimal_num_of_bytes(0, 0.01), BLOCK_SIZE);
+        assert_eq!(optimal_num_of_bytes(1_000, 0.01), 2048);
+        assert_eq!(optimal_num_of_bytes(usize::MAX, 0.01), MAX_NUM_BYTES);
+        // a lower false positive probability requires more bytes
+        assert!(optimal_num_of_bytes(1_000, 0.001) > optimal_num_of_bytes(1_000, 0.1));
+    }
+
+    #[test]
+    fn invalid_bitset() {
+        assert!(BloomFilter::try_from_bitset(vec![0; 33]).is_err());
+        assert!(BloomFilter::try_from_bitset(vec![]).is_err());
+        assert!(BloomFilter::try_from_bitset(vec![0; 64]).is_ok());
+    }
+}
@@ -2,51 +2,131 @@ pub use super::parquet_bridge::Compression;
 
 use crate::error::{ParquetError, Result};
 
+/// The default compression level of [`CompressionOptions::Gzip`]
+pub const GZIP_DEFAULT_LEVEL: u32 = 6;
+/// The default quality of [`CompressionOptions::Brotli`], between 0 and 11
+pub const BROTLI_DEFAULT_QUALITY: u32 = 1;
+/// The default base-2 logarithm of the window size of [`CompressionOptions::Brotli`], between
+/// 10 and 24 (20-22 are recommended)
+pub const BROTLI_DEFAULT_LG_WINDOW_SIZE: u32 = 22;
+/// The default compression level of [`CompressionOptions::Zstd`], chosen for compression speed
+pub const ZSTD_DEFAULT_LEVEL: i32 = 1;
+
+/// The codec used to compress pages, and its parameters.
+///
+/// Only the codec is written to the file, as its [`Compression`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum CompressionOptions {
+    Uncompressed,
+    Snappy,
+    /// Gzip with a compression level, between 0 and 9
+    Gzip(u32),
+    Lzo,
+    /// Brotli with a quality, between 0 and 11, and the base-2 logarithm of its window size,
+    /// between 10 and 24
+    Brotli(u32, u32),
+    /// LZ4 blocks framed by Hadoop, as written by parquet-mr and Arrow C++. This codec is
+    /// deprecated by the spec: prefer [`CompressionOptions::Lz4Raw`] unless readers need it.
+    Lz4,
+    /// Zstd with a compression level, between 1 and 22 (or negative, for faster compression)
+    Zstd(i32),
+    /// A raw LZ4 block, without framing
+    Lz4Raw,
+}
+
+impl From<Compression> for CompressionOptions {
+    /// The codec `compression` with its default parameters
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::Uncompressed => CompressionOptions::Uncompressed,
+            Compression::Snappy => CompressionOptions::Snappy,
+            Compression::Gzip => CompressionOptions::Gzip(GZIP_DEFAULT_LEVEL),
+            Compression::Lzo => CompressionOptions::Lzo,
+            Compression::Brotli => {
+                CompressionOptions::Brotli(BROTLI_DEFAULT_QUALITY, BROTLI_DEFAULT_LG_WINDOW_SIZE)
+            }
+            Compression::Lz4 => CompressionOptions::Lz4,
+            Compression::Zstd => CompressionOptions::Zstd(ZSTD_DEFAULT_LEVEL),
+            Compression::Lz4Raw => CompressionOptions::Lz4Raw,
+        }
+    }
+}
+
+impl From<CompressionOptions> for Compression {
+    fn from(options: CompressionOptions) -> Self {
+        match options {
+            CompressionOptions::Uncompressed => Compression::Uncompressed,
+            CompressionOptions::Snappy => Compression::Snappy,
+            CompressionOptions::Gzip(_) => Compression::Gzip,
+            CompressionOptions::Lzo => Compression::Lzo,
+            CompressionOptions::Brotli(_, _) => Compression::Brotli,
+            CompressionOptions::Lz4 => Compression::Lz4,
+            CompressionOptions::Zstd(_) => Compression::Zstd,
+            CompressionOptions::Lz4Raw => Compression::Lz4Raw,
+        }
+    }
+}
+
+fn invalid_level(compression: CompressionOptions) -> ParquetError {
+    general_err!(
+        "The parameters of the compression {:?} are out of range",
+        compression
+    )
+}
+
 /// Compresses data stored in slice `input_buf` and writes the compressed result
 /// to `output_buf`.
 /// Note that you'll need to call `clear()` before reusing the same `output_buf`
 /// across different `compress` calls.
+/// # Error
+/// Errors iff the codec is not supported (or its feature not active) or its parameters are
+/// out of range.
 pub fn compress(
-    compression: Compression,
+    compression: CompressionOptions,
     input_buf: &[u8],
     output_buf: &mut Vec<u8>,
 ) -> Result<()> {
     match compression {
         #[cfg(feature = "brotli")]
-        Compression::Brotli => {
+        CompressionOptions::Brotli(quality, lg_window_size) => {
             use std::io::Write;
             const BROTLI_DEFAULT_BUFFER_SIZE: usize = 4096;
-            const BROTLI_DEFAULT_COMPRESSION_QUALITY: u32 = 1; // supported levels 0-9
-            const BROTLI_DEFAULT_LG_WINDOW_SIZE: u32 = 22; // recommended between 20-22
+            if quality > 11 || !(10..=24).contains(&lg_window_size) {
+                return Err(invalid_level(compression));
+            }
 
             let mut encoder = brotli::CompressorWriter::new(
                 output_buf,
                 BROTLI_DEFAULT_BUFFER_SIZE,
-                BROTLI_DEFAULT_COMPRESSION_QUALITY,
-                BROTLI_DEFAULT_LG_WINDOW_SIZE,
+                quality,
+                lg_window_size,
             );
             encoder.write_all(input_buf)?;
             encoder.flush().map_err(|e| e.into())
         }
         #[cfg(not(feature = "brotli"))]
-        Compression::Brotli => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Brotli(_, _) => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Brotli,
             "compress to brotli".to_string(),
         )),
         #[cfg(feature = "gzip")]
-        Compression::Gzip => {
+        CompressionOptions::Gzip(level) => {
             use std::io::Write;
-            let mut encoder = flate2::write::GzEncoder::new(output_buf, Default::default());
+            if level > 9 {
+                return Err(invalid_level(compression));
+            }
+            let mut encoder =
+                flate2::write::GzEncoder::new(output_buf, flate2::Compression::new(level));
             encoder.write_all(input_buf)?;
             encoder.try_finish().map_err(|e| e.into())
         }
         #[cfg(not(feature = "gzip"))]
-        Compression::Gzip => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Gzip(_) => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Gzip,
             "compress to gzip".to_string(),
         )),
         #[cfg(feature = "snappy")]
-        Compression::Snappy => {
+        CompressionOptions::Snappy => {
             use snap::raw::{max_compress_len, Encoder};
 
             let output_buf_len = output_buf.len();
@@ -57,38 +137,44 @@ pub fn compress(
             Ok(())
         }
         #[cfg(not(feature = "snappy"))]
-        Compression::Snappy => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Snappy => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Snappy,
             "compress to snappy".to_string(),
         )),
         #[cfg(feature = "lz4")]
-        Compression::Lz4 => {
-            use std::io::Write;
-            const LZ4_BUFFER_SIZE: usize = 4096;
-            let mut encoder = lz4::EncoderBuilder::new().build(output_buf)?;
-            let mut from = 0;
-            loop {
-                let to = std::cmp::min(from + LZ4_BUFFER_SIZE, input_buf.len());
-                encoder.write_all(&input_buf[from..to])?;
-                from += LZ4_BUFFER_SIZE;
-                if from >= input_buf.len() {
-                    break;
-                }
-            }
-            encoder.finish().1.map_err(|e| e.into())
+        CompressionOptions::Lz4 => compress_hadoop(input_buf, output_buf),
+        #[cfg(not(feature = "lz4"))]
+        CompressionOptions::Lz4 => Err(ParquetError::FeatureNotActive(
+            crate::error::Feature::Lz4,
+            "compress to lz4".to_string(),
+        )),
+        #[cfg(feature = "lz4")]
+        CompressionOptions::Lz4Raw => {
+            let output_buf_len = output_buf.len();
+            let required_len = lz4::block::compress_bound(input_buf.len())?;
+            output_buf.resize(output_buf_len + required_len, 0);
+            let n = lz4::block::compress_to_buffer(
+                input_buf,
+                None,
+                false,
+                &mut output_buf[output_buf_len..],
+            )?;
+            output_buf.truncate(output_buf_len + n);
+            Ok(())
         }
         #[cfg(not(feature = "lz4"))]
-        Compression::Lz4 => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Lz4Raw => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Lz4,
             "compress to lz4".to_string(),
         )),
         #[cfg(feature = "zstd")]
-        Compression::Zstd => {
+        CompressionOptions::Zstd(level) => {
             use std::io::Write;
-            /// Compression level (1-21) for ZSTD. Choose 1 here for better compression speed.
-            const ZSTD_COMPRESSION_LEVEL: i32 = 1;
+            if !zstd::compression_level_range().contains(&level) {
+                return Err(invalid_level(compression));
+            }
 
-            let mut encoder = zstd::Encoder::new(output_buf, ZSTD_COMPRESSION_LEVEL)?;
+            let mut encoder = zstd::Encoder::new(output_buf, level)?;
             encoder.write_all(input_buf)?;
             match encoder.finish() {
                 Ok(_) => Ok(()),
@@ -96,14 +182,14 @@ pub fn compress(
             }
         }
         #[cfg(not(feature = "zstd"))]
-        Compression::Zstd => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Zstd(_) => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Zstd,
             "compress to zstd".to_string(),
         )),
-        Compression::Uncompressed => {
+        CompressionOptions::Uncompressed => {
             Err(general_err!("Compressing without compression is not valid"))
         }
-        _ => Err(general_err!(
+        CompressionOptions::Lzo => Err(general_err!(
             "Compression {:?} is not supported",
             compression
         )),
@@ -156,6 +242,14 @@ pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [
         )),
         #[cfg(feature = "lz4")]
         Compression::Lz4 => {
+            // writers disagree on the format of `LZ4`: try Hadoop's framing (parquet-mr and
+            // Arrow C++), then a raw block and finally LZ4 frames (older versions of this crate)
+            if try_decompress_hadoop(input_buf, output_buf) {
+                return Ok(());
+            }
+            if decompress_lz4_raw(input_buf, output_buf).is_ok() {
+                return Ok(());
+            }
             use std::io::Read;
             let mut decoder = lz4::Decoder::new(input_buf)?;
             decoder.read_exact(output_buf).map_err(|e| e.into())
@@ -165,6 +259,13 @@ pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [
             crate::error::Feature::Lz4,
             "decompress with lz4".to_string(),
         )),
+        #[cfg(feature = "lz4")]
+        Compression::Lz4Raw => decompress_lz4_raw(input_buf, output_buf),
+        #[cfg(not(feature = "lz4"))]
+        Compression::Lz4Raw => Err(ParquetError::FeatureNotActive(
+            crate::error::Feature::Lz4,
+            "decompress with lz4".to_string(),
+        )),
         #[cfg(feature = "zstd")]
         Compression::Zstd => {
             use std::io::Read;
@@ -186,11 +287,77 @@ pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [
     }
 }
 
+/// Decompresses a raw LZ4 block that must fill `output_buf`.
+#[cfg(feature = "lz4")]
+fn decompress_lz4_raw(input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
+    let expected = output_buf.len();
+    let n = lz4::block::decompress_to_buffer(input_buf, Some(expected as i32), output_buf)?;
+    if n != expected {
+        return Err(general_err!(
+            "The decompressed lz4 block has {} bytes, but {} were expected",
+            n,
+            expected
+        ));
+    }
+    Ok(())
+}
+
+/// The maximum decompressed size of the LZ4 blocks framed by Hadoop, Hadoop's default buffer
+/// size.
+#[cfg(feature = "lz4")]
+const HADOOP_BLOCK_SIZE: usize = 256 * 1024;
+
+/// Compresses `input_buf` into LZ4 blocks framed by Hadoop, each prefixed by its big-endian
+/// decompressed and compressed sizes.
+#[cfg(feature = "lz4")]
+fn compress_hadoop(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<()> {
+    let mut blocks = input_buf.chunks(HADOOP_BLOCK_SIZE);
+    // an empty input is a single empty block
+    let empty: &[u8] = &[];
+    let first = blocks.next().unwrap_or(empty);
+    for block in std::iter::once(first).chain(blocks) {
+        let compressed = lz4::block::compress(block, None, false)?;
+        output_buf.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        output_buf.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        output_buf.extend_from_slice(&compressed);
+    }
+    Ok(())
+}
+
+/// Decompresses LZ4 blocks framed by Hadoop, each prefixed by its big-endian decompressed and
+/// compressed sizes, that must fill `output_buf`. Returns whether `input_buf` is such a
+/// sequence of blocks.
+#[cfg(feature = "lz4")]
+fn try_decompress_hadoop(mut input_buf: &[u8], mut output_buf: &mut [u8]) -> bool {
+    use std::convert::TryInto;
+    const PREFIX_LEN: usize = std::mem::size_of::<u32>() * 2;
+    while input_buf.len() >= PREFIX_LEN {
+        let decompressed_len = u32::from_be_bytes(input_buf[..4].try_into().unwrap()) as usize;
+        let compressed_len = u32::from_be_bytes(input_buf[4..8].try_into().unwrap()) as usize;
+        input_buf = &input_buf[PREFIX_LEN..];
+        if compressed_len > input_buf.len() || decompressed_len > output_buf.len() {
+            return false;
+        }
+        let (block, remaining) = output_buf.split_at_mut(decompressed_len);
+        if decompress_lz4_raw(&input_buf[..compressed_len], block).is_err() {
+            return false;
+        }
+        input_buf = &input_buf[compressed_len..];
+        output_buf = remaining;
+        if input_buf.is_empty() {
+            return output_buf.is_empty();
+        }
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
+    use std::convert::TryInto;
+
     use super::*;
 
-    fn test_roundtrip(c: Compression, data: &[u8]) {
+    fn test_roundtrip(c: CompressionOptions, data: &[u8]) {
         let offset = 2;
 
         // Compress to a buffer that already has data is possible
@@ -201,11 +368,12 @@ mod tests {
         assert!(compressed.len() - 2 < data.len());
 
         let mut decompressed = vec![0; data.len()];
-        decompress(c, &compressed[offset..], &mut decompressed).expect("Error when decompressing");
+        decompress(c.into(), &compressed[offset..], &mut decompressed)
+            .expect("Error when decompressing");
         assert_eq!(data, decompressed.as_slice());
     }
 
-    fn test_codec(c: Compression) {
+    fn test_codec(c: CompressionOptions) {
         let sizes = vec![10000, 100000];
         for size in sizes {
             let data = (0..size).map(|x| (x % 255) as u8).collect::<Vec<_>>();
@@ -215,26 +383,112 @@ mod tests {
 
     #[test]
     fn test_codec_snappy() {
-        test_codec(Compression::Snappy);
+        test_codec(Compression::Snappy.into());
     }
 
     #[test]
     fn test_codec_gzip() {
-        test_codec(Compression::Gzip);
+        test_codec(Compression::Gzip.into());
     }
 
     #[test]
     fn test_codec_brotli() {
-        test_codec(Compression::Brotli);
+        test_codec(Compression::Brotli.into());
     }
 
     #[test]
     fn test_codec_lz4() {
-        test_codec(Compression::Lz4);
+        test_codec(Compression::Lz4.into());
+    }
+
+    #[test]
+    fn test_codec_lz4_raw() {
+        test_codec(Compression::Lz4Raw.into());
+    }
+
+    #[test]
+    fn test_lz4_hadoop() {
+        let data = (0..100000).map(|x| (x % 255) as u8).collect::<Vec<_>>();
+
+        // two blocks, each prefixed by its decompressed and compressed sizes
+        let mut compressed = vec![];
+        for chunk in data.chunks(60000) {
+            let block = lz4::block::compress(chunk, None, false).unwrap();
+            compressed.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+            compressed.extend_from_slice(&(block.len() as u32).to_be_bytes());
+            compressed.extend_from_slice(&block);
+        }
+
+        let mut decompressed = vec![0; data.len()];
+        decompress(Compression::Lz4, &compressed, &mut decompressed).unwrap();
+        assert_eq!(data, decompressed);
+    }
+
+    #[test]
+    fn test_lz4_writes_hadoop() {
+        let data = (0..300000).map(|x| (x % 255) as u8).collect::<Vec<_>>();
+        let mut compressed = vec![];
+        compress(CompressionOptions::Lz4, &data, &mut compressed).unwrap();
+
+        // the first of two blocks is prefixed by its decompressed and compressed sizes
+        assert_eq!(compressed[..4], (HADOOP_BLOCK_SIZE as u32).to_be_bytes());
+        let compressed_len = u32::from_be_bytes(compressed[4..8].try_into().unwrap()) as usize;
+        let mut block = vec![0; HADOOP_BLOCK_SIZE];
+        decompress_lz4_raw(&compressed[8..8 + compressed_len], &mut block).unwrap();
+        assert_eq!(block, data[..HADOOP_BLOCK_SIZE]);
+
+        let mut decompressed = vec![0; data.len()];
+        decompress(Compression::Lz4, &compressed, &mut decompressed).unwrap();
+        assert_eq!(data, decompressed);
+
+        // an empty input is a single empty block
+        let mut compressed = vec![];
+        compress(CompressionOptions::Lz4, &[], &mut compressed).unwrap();
+        assert_eq!(compressed[..4], [0; 4]);
+        decompress(Compression::Lz4, &compressed, &mut []).unwrap();
+    }
+
+    #[test]
+    fn test_lz4_fallbacks() {
+        let data = (0..10000).map(|x| (x % 255) as u8).collect::<Vec<_>>();
+
+        // raw blocks and LZ4 frames (written by older versions) are also read as `LZ4`
+        let mut raw = vec![];
+        compress(CompressionOptions::Lz4Raw, &data, &mut raw).unwrap();
+        let mut encoder = lz4::EncoderBuilder::new().build(vec![]).unwrap();
+        std::io::Write::write_all(&mut encoder, &data).unwrap();
+        let (frame, result) = encoder.finish();
+        result.unwrap();
+
+        for compressed in [raw, frame] {
+            let mut decompressed = vec![0; data.len()];
+            decompress(Compression::Lz4, &compressed, &mut decompressed).unwrap();
+            assert_eq!(data, decompressed);
+        }
     }
 
     #[test]
     fn test_codec_zstd() {
-        test_codec(Compression::Zstd);
+        test_codec(Compression::Zstd.into());
+    }
+
+    #[test]
+    fn test_codec_levels() {
+        test_codec(CompressionOptions::Gzip(9));
+        test_codec(CompressionOptions::Brotli(9, 20));
+        test_codec(CompressionOptions::Zstd(19));
+        test_codec(CompressionOptions::Zstd(-1));
+    }
+
+    #[test]
+    fn test_invalid_levels() {
+        for c in [
+            CompressionOptions::Gzip(10),
+            CompressionOptions::Brotli(12, 22),
+            CompressionOptions::Brotli(1, 25),
+            CompressionOptions::Zstd(23),
+        ] {
+            assert!(compress(c, &[1, 2, 3], &mut vec![]).is_err());
+        }
     }
 }
@@ -0,0 +1,175 @@
+use std::borrow::Cow;
+
+use crate::encoding::{delta_byte_array, delta_length_byte_array, plain_byte_array, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::page::{BinaryPageDict, DataPage};
+use crate::schema::types::PhysicalType;
+
+use super::utils::{dict, dict_indices, not_supported, num_values, values};
+
+/// Iterator over `DELTA_LENGTH_BYTE_ARRAY`-encoded values
+#[derive(Debug)]
+struct DeltaLengthValues<'a> {
+    lengths: std::vec::IntoIter<i32>,
+    values: &'a [u8],
+}
+
+impl<'a> DeltaLengthValues<'a> {
+    fn try_new(mut decoder: delta_length_byte_array::Decoder<'a>) -> Result<Self> {
+        let lengths = decoder.by_ref().collect::<Vec<_>>();
+        if lengths.iter().any(|length| *length < 0) {
+            return Err(ParquetError::OutOfSpec(
+                "The lengths of DELTA_LENGTH_BYTE_ARRAY values must be non-negative".to_string(),
+            ));
+        }
+        let values = decoder.into_values();
+        let total_length = lengths
+            .iter()
+            .try_fold(0usize, |total, length| total.checked_add(*length as usize));
+        if !matches!(total_length, Some(total_length) if total_length <= values.len()) {
+            return Err(ParquetError::OutOfSpec(format!(
+                "The lengths of {} DELTA_LENGTH_BYTE_ARRAY values exceed their {} bytes",
+                lengths.len(),
+                values.len()
+            )));
+        }
+        Ok(Self {
+            lengths: lengths.into_iter(),
+            values,
+        })
+    }
+}
+
+impl<'a> Iterator for DeltaLengthValues<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let length = self.lengths.next()? as usize;
+        // the lengths were checked against the values when created
+        let (value, remaining) = self.values.split_at(length);
+        self.values = remaining;
+        Some(value)
+    }
+}
+
+/// Iterator over `DELTA_BYTE_ARRAY`-encoded values, i.e. a prefix of the previous value
+/// followed by a `DELTA_LENGTH_BYTE_ARRAY`-encoded suffix
+#[derive(Debug)]
+pub(super) struct DeltaValues<'a> {
+    prefixes: std::vec::IntoIter<u32>,
+    suffixes: DeltaLengthValues<'a>,
+    last: Vec<u8>,
+}
+
+impl<'a> DeltaValues<'a> {
+    pub fn try_new(values: &'a [u8]) -> Result<Self> {
+        let mut decoder = delta_byte_array::Decoder::try_new(values)?;
+        let prefixes = decoder.by_ref().collect::<Vec<_>>();
+        Ok(Self {
+            prefixes: prefixes.into_iter(),
+            suffixes: DeltaLengthValues::try_new(decoder.try_into_lengths()?)?,
+            last: vec![],
+        })
+    }
+}
+
+impl<'a> Iterator for DeltaValues<'a> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let prefix = self.prefixes.next()? as usize;
+        let suffix = self.suffixes.next()?;
+        // a prefix longer than the previous value is out of spec; it is truncated
+        let mut value = self.last[..prefix.min(self.last.len())].to_vec();
+        value.extend_from_slice(suffix);
+        self.last.clone_from(&value);
+        Some(value)
+    }
+}
+
+#[derive(Debug)]
+enum State<'a> {
+    Plain(plain_byte_array::Decoder<'a>),
+    Dictionary(std::vec::IntoIter<u32>, &'a BinaryPageDict),
+    DeltaLengthByteArray(DeltaLengthValues<'a>),
+    DeltaByteArray(DeltaValues<'a>),
+}
+
+/// Iterator over the (non-null) values of a page of `BYTE_ARRAY`.
+///
+/// Values are borrowed from the page, except for `DELTA_BYTE_ARRAY`-encoded values, that
+/// are assembled from the previous value.
+#[derive(Debug)]
+pub struct BinaryValues<'a> {
+    state: State<'a>,
+    remaining: usize,
+}
+
+impl<'a> BinaryValues<'a> {
+    /// Returns a new [`BinaryValues`] of `page`.
+    /// # Error
+    /// Errors iff the physical type of the page is not `BYTE_ARRAY`, its encoding is not
+    /// supported, or its values are out of spec.
+    pub fn try_new(page: &'a DataPage) -> Result<Self> {
+        let physical_type = page.descriptor().physical_type();
+        if *physical_type != PhysicalType::ByteArray {
+            return Err(general_err!(
+                "Values of the physical type {:?} cannot be decoded as binary",
+                physical_type
+            ));
+        }
+        let values = values(page)?;
+        let num_values = num_values(page)?;
+
+        let state = match page.encoding() {
+            Encoding::Plain => State::Plain(plain_byte_array::Decoder::new(values, num_values)),
+            Encoding::PlainDictionary | Encoding::RleDictionary => {
+                let dict = dict::<BinaryPageDict>(page)?;
+                let dict_len = dict.offsets().len().saturating_sub(1);
+                State::Dictionary(dict_indices(values, num_values, dict_len)?, dict)
+            }
+            Encoding::DeltaLengthByteArray => {
+                let decoder = delta_length_byte_array::Decoder::try_new(values)?;
+                State::DeltaLengthByteArray(DeltaLengthValues::try_new(decoder)?)
+            }
+            Encoding::DeltaByteArray => State::DeltaByteArray(DeltaValues::try_new(values)?),
+            _ => return Err(not_supported(page)),
+        };
+        Ok(Self {
+            state,
+            remaining: num_values,
+        })
+    }
+}
+
+impl<'a> Iterator for BinaryValues<'a> {
+    type Item = Cow<'a, [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = match &mut self.state {
+            State::Plain(decoder) => decoder.next().map(Cow::Borrowed),
+            State::Dictionary(indices, dict) => indices.next().and_then(|index| {
+                let offsets = dict.offsets();
+                let start = *offsets.get(index as usize)? as usize;
+                let end = *offsets.get(index as usize + 1)? as usize;
+                dict.values().get(start..end).map(Cow::Borrowed)
+            }),
+            State::DeltaLengthByteArray(decoder) => decoder.next().map(Cow::Borrowed),
+            State::DeltaByteArray(decoder) => decoder.next().map(Cow::Owned),
+        };
+        if result.is_some() {
+            self.remaining -= 1;
+        } else {
+            // the values are exhausted before `remaining`: the page is out of spec
+            self.remaining = 0;
+        }
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
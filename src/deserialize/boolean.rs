@@ -0,0 +1,91 @@
+use crate::encoding::hybrid_rle::HybridRleDecoder;
+use crate::encoding::{get_length, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::page::DataPage;
+use crate::schema::types::PhysicalType;
+
+use super::utils::{not_supported, num_values, values};
+
+#[derive(Debug)]
+enum State<'a> {
+    // the bitpacked values (LSB first) and the index of the next one
+    Plain(&'a [u8], usize),
+    Rle(HybridRleDecoder<'a>),
+}
+
+/// Iterator over the (non-null) values of a page of `BOOLEAN`.
+#[derive(Debug)]
+pub struct BooleanValues<'a> {
+    state: State<'a>,
+    remaining: usize,
+}
+
+impl<'a> BooleanValues<'a> {
+    /// Returns a new [`BooleanValues`] of `page`.
+    /// # Error
+    /// Errors iff the physical type of the page is not `BOOLEAN`, its encoding is not
+    /// supported, or its values are out of spec.
+    pub fn try_new(page: &'a DataPage) -> Result<Self> {
+        let physical_type = page.descriptor().physical_type();
+        if *physical_type != PhysicalType::Boolean {
+            return Err(general_err!(
+                "Values of the physical type {:?} cannot be decoded as booleans",
+                physical_type
+            ));
+        }
+        let values = values(page)?;
+        let num_values = num_values(page)?;
+
+        let state = match page.encoding() {
+            Encoding::Plain => {
+                if values.len() * 8 < num_values {
+                    return Err(ParquetError::OutOfSpec(format!(
+                        "A page with {} PLAIN-encoded booleans cannot have {} bytes",
+                        num_values,
+                        values.len()
+                    )));
+                }
+                State::Plain(values, 0)
+            }
+            Encoding::Rle => {
+                // SPEC: the RLE-encoded booleans are prefixed by their length (4 bytes)
+                if values.len() < 4 || values.len() < 4 + get_length(values) as usize {
+                    return Err(ParquetError::OutOfSpec(
+                        "The length of RLE-encoded booleans exceeds the page".to_string(),
+                    ));
+                }
+                let length = get_length(values) as usize;
+                State::Rle(HybridRleDecoder::new(&values[4..4 + length], 1, num_values))
+            }
+            _ => return Err(not_supported(page)),
+        };
+        Ok(Self {
+            state,
+            remaining: num_values,
+        })
+    }
+}
+
+impl<'a> Iterator for BooleanValues<'a> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = match &mut self.state {
+            State::Plain(values, index) => {
+                let value = values[*index / 8] & (1 << (*index % 8)) != 0;
+                *index += 1;
+                Some(value)
+            }
+            State::Rle(decoder) => decoder.next().map(|x| x == 1),
+        };
+        self.remaining -= 1;
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
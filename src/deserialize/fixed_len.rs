@@ -0,0 +1,96 @@
+use std::borrow::Cow;
+
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::page::{DataPage, FixedLenByteArrayPageDict};
+use crate::schema::types::PhysicalType;
+
+use super::binary::DeltaValues;
+use super::utils::{dict, dict_indices, not_supported, num_values, values};
+
+#[derive(Debug)]
+enum State<'a> {
+    Plain(std::slice::ChunksExact<'a, u8>),
+    Dictionary(std::vec::IntoIter<u32>, &'a FixedLenByteArrayPageDict),
+    DeltaByteArray(DeltaValues<'a>),
+}
+
+/// Iterator over the (non-null) values of a page of `FIXED_LEN_BYTE_ARRAY`.
+///
+/// Values are borrowed from the page, except for `DELTA_BYTE_ARRAY`-encoded values, that
+/// are assembled from the previous value.
+#[derive(Debug)]
+pub struct FixedLenValues<'a> {
+    state: State<'a>,
+    remaining: usize,
+}
+
+impl<'a> FixedLenValues<'a> {
+    /// Returns a new [`FixedLenValues`] of `page`.
+    /// # Error
+    /// Errors iff the physical type of the page is not `FIXED_LEN_BYTE_ARRAY`, its encoding is
+    /// not supported, or its values are out of spec.
+    pub fn try_new(page: &'a DataPage) -> Result<Self> {
+        let size = match page.descriptor().physical_type() {
+            PhysicalType::FixedLenByteArray(size) if *size > 0 => *size as usize,
+            physical_type => {
+                return Err(general_err!(
+                    "Values of the physical type {:?} cannot be decoded as fixed-length binary",
+                    physical_type
+                ))
+            }
+        };
+        let values = values(page)?;
+        let num_values = num_values(page)?;
+
+        let state = match page.encoding() {
+            Encoding::Plain => {
+                if values.len() < num_values * size {
+                    return Err(ParquetError::OutOfSpec(format!(
+                        "A page with {} PLAIN-encoded values of {} bytes cannot have {} bytes",
+                        num_values,
+                        size,
+                        values.len()
+                    )));
+                }
+                State::Plain(values[..num_values * size].chunks_exact(size))
+            }
+            Encoding::PlainDictionary | Encoding::RleDictionary => {
+                let dict = dict::<FixedLenByteArrayPageDict>(page)?;
+                let dict_len = dict.values().len() / size;
+                State::Dictionary(dict_indices(values, num_values, dict_len)?, dict)
+            }
+            Encoding::DeltaByteArray => State::DeltaByteArray(DeltaValues::try_new(values)?),
+            _ => return Err(not_supported(page)),
+        };
+        Ok(Self {
+            state,
+            remaining: num_values,
+        })
+    }
+}
+
+impl<'a> Iterator for FixedLenValues<'a> {
+    type Item = Cow<'a, [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = match &mut self.state {
+            State::Plain(chunks) => chunks.next().map(Cow::Borrowed),
+            State::Dictionary(indices, dict) => indices.next().and_then(|index| {
+                let size = dict.size();
+                let start = index as usize * size;
+                dict.values().get(start..start + size).map(Cow::Borrowed)
+            }),
+            State::DeltaByteArray(decoder) => decoder.next().map(Cow::Owned),
+        };
+        self.remaining -= 1;
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
@@ -0,0 +1,417 @@
+//! Deserialization of the values of [`DataPage`](crate::page::DataPage)s into iterators of
+//! Rust native types.
+//!
+//! Every physical type has an iterator over the (non-null) values of a page, that supports
+//! the encodings of that type:
+//! * [`NativeValues`]: `INT32`, `INT64`, `INT96`, `FLOAT` and `DOUBLE`, encoded with `PLAIN`,
+//!   dictionary, `BYTE_STREAM_SPLIT` or (integers only) `DELTA_BINARY_PACKED`
+//! * [`BinaryValues`]: `BYTE_ARRAY`, encoded with `PLAIN`, dictionary,
+//!   `DELTA_LENGTH_BYTE_ARRAY` or `DELTA_BYTE_ARRAY`
+//! * [`FixedLenValues`]: `FIXED_LEN_BYTE_ARRAY`, encoded with `PLAIN`, dictionary or
+//!   `DELTA_BYTE_ARRAY`
+//! * [`BooleanValues`]: `BOOLEAN`, encoded with `PLAIN` or `RLE`
+//!
+//! [`OptionalValues`] combines these with the definition levels of the page into an iterator
+//! of `Option`s, where `None` represents a null value.
+//!
+//! [`NestedDecoder`] decodes the repetition and definition levels of the pages of nested
+//! columns into the offsets and validities of every node in their path ([`Nested`]).
+//!
+//! Dictionary-encoded pages require their dictionary page, as attached to them by the readers
+//! of this crate.
+mod binary;
+mod boolean;
+mod fixed_len;
+mod native;
+mod nested;
+mod utils;
+
+pub use binary::BinaryValues;
+pub use boolean::BooleanValues;
+pub use fixed_len::FixedLenValues;
+pub use native::NativeValues;
+pub use nested::{Nested, NestedDecoder};
+pub use utils::OptionalValues;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    use crate::compression::Compression;
+    use crate::encoding::hybrid_rle::encode_u32;
+    use crate::encoding::{
+        byte_stream_split, delta_bitpacked, delta_byte_array, delta_length_byte_array, Encoding,
+    };
+    use crate::error::Result;
+    use crate::metadata::ColumnDescriptor;
+    use crate::page::{
+        read_dict_page, DataPage, DataPageHeader, DataPageHeaderV1, DataPageHeaderV2, EncodedPage,
+    };
+    use crate::tests::{descriptor, options};
+    use crate::write::{
+        BinaryDictEncoder, FixedLenDictEncoder, PrimitiveDictEncoder, Version,
+        DEFAULT_MAX_DICTIONARY_SIZE,
+    };
+
+    /// Returns a v1 page of `descriptor` with `validity` and the encoded `values`
+    fn data_page(
+        descriptor: ColumnDescriptor,
+        validity: &[bool],
+        values: &[u8],
+        encoding: Encoding,
+    ) -> DataPage {
+        let mut buffer = vec![];
+        if descriptor.max_def_level() > 0 {
+            let mut def_levels = vec![];
+            encode_u32(&mut def_levels, validity.iter().map(|x| *x as u32), 1).unwrap();
+            buffer.extend_from_slice(&(def_levels.len() as u32).to_le_bytes());
+            buffer.extend_from_slice(&def_levels);
+        }
+        buffer.extend_from_slice(values);
+        let header = DataPageHeaderV1 {
+            num_values: validity.len() as i32,
+            encoding: encoding.into(),
+            definition_level_encoding: Encoding::Rle.into(),
+            repetition_level_encoding: Encoding::Rle.into(),
+            statistics: None,
+        };
+        DataPage::new(DataPageHeader::V1(header), buffer, None, descriptor)
+    }
+
+    /// Attaches the dictionary page `dict` to the data page `page`, as readers do
+    fn with_dict(page: EncodedPage, dict: Option<EncodedPage>) -> Result<DataPage> {
+        let (page, dict) = match (page, dict) {
+            (EncodedPage::Data(page), Some(EncodedPage::Dict(dict))) => (page, dict),
+            _ => unreachable!(),
+        };
+        let physical_type = *page.descriptor().physical_type();
+        let dict = read_dict_page(&dict, (Compression::Uncompressed, 0), false, &physical_type)?;
+        Ok(DataPage::new(
+            page.header().clone(),
+            page.buffer().to_vec(),
+            Some(Arc::clone(&dict)),
+            page.descriptor().clone(),
+        ))
+    }
+
+    #[test]
+    fn native_plain() -> Result<()> {
+        let values = [1i32, -2, 3]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+        let page = data_page(
+            descriptor("OPTIONAL INT32 a"),
+            &[true, false, true, true],
+            &values,
+            Encoding::Plain,
+        );
+        let result = OptionalValues::try_new(&page, NativeValues::<i32>::try_new(&page)?)?
+            .collect::<Vec<_>>();
+        assert_eq!(result, vec![Some(1), None, Some(-2), Some(3)]);
+
+        assert!(NativeValues::<i64>::try_new(&page).is_err());
+        assert!(BinaryValues::try_new(&page).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn native_delta() -> Result<()> {
+        let expected = vec![i32::MIN, 0, i32::MAX, -1];
+        let mut values = vec![];
+        delta_bitpacked::encode(expected.iter().map(|x| *x as i64), &mut values);
+        let page = data_page(
+            descriptor("REQUIRED INT32 a"),
+            &[true; 4],
+            &values,
+            Encoding::DeltaBinaryPacked,
+        );
+        let result = NativeValues::<i32>::try_new(&page)?.collect::<Vec<_>>();
+        assert_eq!(result, expected);
+
+        // required values are always valid
+        let result = OptionalValues::try_new(&page, NativeValues::<i32>::try_new(&page)?)?
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected.into_iter().map(Some).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn native_dictionary() -> Result<()> {
+        for version in [Version::V1, Version::V2] {
+            let expected = vec![Some(1.5f64), None, Some(-1.0), Some(1.5), None];
+            let mut encoder = PrimitiveDictEncoder::<f64>::try_new(
+                descriptor("OPTIONAL DOUBLE a"),
+                DEFAULT_MAX_DICTIONARY_SIZE,
+            )?;
+            let page = encoder.encode(&expected, &options(version))?;
+            let page = with_dict(page, encoder.dict_page())?;
+
+            let result = OptionalValues::try_new(&page, NativeValues::<f64>::try_new(&page)?)?
+                .collect::<Vec<_>>();
+            assert_eq!(result, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn binary() -> Result<()> {
+        let expected = vec![
+            b"hello".to_vec(),
+            b"help".to_vec(),
+            vec![],
+            b"world".to_vec(),
+        ];
+        let descriptor = descriptor("REQUIRED BYTE_ARRAY a");
+
+        let mut plain = vec![];
+        expected.iter().for_each(|x| {
+            plain.extend_from_slice(&(x.len() as u32).to_le_bytes());
+            plain.extend_from_slice(x);
+        });
+        let mut delta_length = vec![];
+        delta_length_byte_array::encode(expected.iter(), &mut delta_length);
+        let mut delta = vec![];
+        delta_byte_array::encode(expected.iter(), &mut delta);
+
+        for (values, encoding) in [
+            (plain, Encoding::Plain),
+            (delta_length, Encoding::DeltaLengthByteArray),
+            (delta, Encoding::DeltaByteArray),
+        ] {
+            let page = data_page(descriptor.clone(), &[true; 4], &values, encoding);
+            let result = BinaryValues::try_new(&page)?
+                .map(|x| x.into_owned())
+                .collect::<Vec<_>>();
+            assert_eq!(result, expected);
+        }
+
+        let mut encoder = BinaryDictEncoder::try_new(descriptor, DEFAULT_MAX_DICTIONARY_SIZE)?;
+        let expected = expected.into_iter().map(Some).collect::<Vec<_>>();
+        let page = encoder.encode(&expected, &options(Version::V1))?;
+        let page = with_dict(page, encoder.dict_page())?;
+        let result = OptionalValues::try_new(&page, BinaryValues::try_new(&page)?)?
+            .map(|x| x.map(|x| x.into_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_len() -> Result<()> {
+        let page = data_page(
+            descriptor("OPTIONAL FIXED_LEN_BYTE_ARRAY(2) a"),
+            &[false, true, true],
+            b"abcd",
+            Encoding::Plain,
+        );
+        let result = OptionalValues::try_new(&page, FixedLenValues::try_new(&page)?)?
+            .map(|x| x.map(|x| x.into_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            result,
+            vec![None, Some(b"ab".to_vec()), Some(b"cd".to_vec())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn boolean() -> Result<()> {
+        let descriptor = descriptor("OPTIONAL BOOLEAN a");
+        let validity = [true, true, false, true, true, true, true, true, true, true];
+        let expected = [true, false, true, true, false, false, false, true, true];
+
+        let page = data_page(
+            descriptor.clone(),
+            &validity,
+            &[0b10001101, 0b00000001],
+            Encoding::Plain,
+        );
+        let result = BooleanValues::try_new(&page)?.collect::<Vec<_>>();
+        assert_eq!(result, expected);
+
+        let mut rle = vec![];
+        encode_u32(&mut rle, expected.iter().map(|x| *x as u32), 1)?;
+        let mut values = (rle.len() as u32).to_le_bytes().to_vec();
+        values.extend_from_slice(&rle);
+        let page = data_page(descriptor, &validity, &values, Encoding::Rle);
+        let result =
+            OptionalValues::try_new(&page, BooleanValues::try_new(&page)?)?.collect::<Vec<_>>();
+        assert_eq!(result[2], None);
+        assert_eq!(
+            result.into_iter().flatten().collect::<Vec<_>>(),
+            expected.to_vec()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_dictionary() {
+        let page = data_page(
+            descriptor("REQUIRED INT64 a"),
+            &[true],
+            &[0, 0],
+            Encoding::RleDictionary,
+        );
+        assert!(NativeValues::<i64>::try_new(&page).is_err());
+    }
+
+    #[test]
+    fn out_of_bounds_dictionary_index() -> Result<()> {
+        // the indices 0 and 5 into dictionaries of 2 values
+        let mut indices = vec![3];
+        encode_u32(&mut indices, [0u32, 5].iter().copied(), 3)?;
+        let indices_page = |descriptor| {
+            let page = data_page(descriptor, &[true; 2], &indices, Encoding::RleDictionary);
+            EncodedPage::Data(page)
+        };
+        let options = options(Version::V1);
+
+        let int64 = descriptor("REQUIRED INT64 a");
+        let mut encoder =
+            PrimitiveDictEncoder::<i64>::try_new(int64.clone(), DEFAULT_MAX_DICTIONARY_SIZE)?;
+        encoder.encode(&[Some(1), Some(2)], &options)?;
+        let page = with_dict(indices_page(int64), encoder.dict_page())?;
+        assert!(NativeValues::<i64>::try_new(&page).is_err());
+
+        let binary = descriptor("REQUIRED BYTE_ARRAY a");
+        let mut encoder = BinaryDictEncoder::try_new(binary.clone(), DEFAULT_MAX_DICTIONARY_SIZE)?;
+        encoder.encode(&[Some(b"a".to_vec()), Some(b"b".to_vec())], &options)?;
+        let page = with_dict(indices_page(binary), encoder.dict_page())?;
+        assert!(BinaryValues::try_new(&page).is_err());
+
+        let fixed_len = descriptor("REQUIRED FIXED_LEN_BYTE_ARRAY(1) a");
+        let mut encoder =
+            FixedLenDictEncoder::try_new(fixed_len.clone(), DEFAULT_MAX_DICTIONARY_SIZE)?;
+        encoder.encode(&[Some(b"a".to_vec()), Some(b"b".to_vec())], &options)?;
+        let page = with_dict(indices_page(fixed_len), encoder.dict_page())?;
+        assert!(FixedLenValues::try_new(&page).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn delta_length_past_values() {
+        let mut values = vec![];
+        delta_length_byte_array::encode(
+            [b"ab".to_vec(), b"cdefghijkl".to_vec()].iter(),
+            &mut values,
+        );
+        // the last 5 bytes of the second value are missing
+        values.truncate(values.len() - 5);
+        let page = data_page(
+            descriptor("REQUIRED BYTE_ARRAY a"),
+            &[true; 2],
+            &values,
+            Encoding::DeltaLengthByteArray,
+        );
+        assert!(BinaryValues::try_new(&page).is_err());
+    }
+
+    #[test]
+    fn more_nulls_than_values() {
+        let header = DataPageHeaderV2 {
+            num_values: 1,
+            num_nulls: 2,
+            num_rows: 1,
+            encoding: Encoding::Plain.into(),
+            definition_levels_byte_length: 0,
+            repetition_levels_byte_length: 0,
+            is_compressed: None,
+            statistics: None,
+        };
+        let page = DataPage::new(
+            DataPageHeader::V2(header),
+            vec![0; 4],
+            None,
+            descriptor("REQUIRED INT32 a"),
+        );
+        assert!(NativeValues::<i32>::try_new(&page).is_err());
+    }
+
+    #[test]
+    fn levels_past_page_v1() {
+        let header = DataPageHeaderV1 {
+            num_values: 2,
+            encoding: Encoding::Plain.into(),
+            definition_level_encoding: Encoding::Rle.into(),
+            repetition_level_encoding: Encoding::Rle.into(),
+            statistics: None,
+        };
+        // the definition levels declare 100 bytes, and the length itself is truncated
+        for buffer in [vec![100, 0, 0, 0, 0b00000100, 0b00000011], vec![1, 0]] {
+            let page = DataPage::new(
+                DataPageHeader::V1(header.clone()),
+                buffer,
+                None,
+                descriptor("OPTIONAL INT32 a"),
+            );
+            assert!(NativeValues::<i32>::try_new(&page).is_err());
+            assert!(OptionalValues::try_new(&page, std::iter::empty::<i32>()).is_err());
+        }
+    }
+
+    #[test]
+    fn levels_past_page_v2() {
+        for (rep_length, def_length) in [(0, 10), (10, 0), (-1, 0), (i32::MAX, i32::MAX)] {
+            let header = DataPageHeaderV2 {
+                num_values: 1,
+                num_nulls: 0,
+                num_rows: 1,
+                encoding: Encoding::Plain.into(),
+                definition_levels_byte_length: def_length,
+                repetition_levels_byte_length: rep_length,
+                is_compressed: None,
+                statistics: None,
+            };
+            let page = DataPage::new(
+                DataPageHeader::V2(header),
+                vec![0; 4],
+                None,
+                descriptor("OPTIONAL INT32 a"),
+            );
+            assert!(NativeValues::<i32>::try_new(&page).is_err());
+        }
+    }
+
+    #[test]
+    fn truncated_rle_run() -> Result<()> {
+        let header = DataPageHeaderV1 {
+            num_values: 1,
+            encoding: Encoding::Plain.into(),
+            definition_level_encoding: Encoding::Rle.into(),
+            repetition_level_encoding: Encoding::Rle.into(),
+            statistics: None,
+        };
+        // the definition levels are a run of 8 values without the value of the run
+        let page = DataPage::new(
+            DataPageHeader::V1(header),
+            vec![1, 0, 0, 0, 8 << 1, 1, 0, 0, 0],
+            None,
+            descriptor("OPTIONAL INT32 a"),
+        );
+        let result = OptionalValues::try_new(&page, NativeValues::<i32>::try_new(&page)?)?
+            .collect::<Vec<_>>();
+        assert_eq!(result.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn values_past_page() -> Result<()> {
+        // 2 values, for a page of 4
+        let mut values = vec![];
+        byte_stream_split::encode(&[1.0f32, 2.0], &mut values);
+        let page = data_page(
+            descriptor("REQUIRED FLOAT a"),
+            &[true; 4],
+            &values,
+            Encoding::ByteStreamSplit,
+        );
+        let mut iter = NativeValues::<f32>::try_new(&page)?;
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        Ok(())
+    }
+}
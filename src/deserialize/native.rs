@@ -0,0 +1,118 @@
+use std::convert::TryInto;
+
+use crate::encoding::{byte_stream_split, delta_bitpacked, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::page::{DataPage, PrimitivePageDict};
+use crate::schema::types::PhysicalType;
+use crate::types::NativeType;
+
+use super::utils::{dict, dict_indices, not_supported, num_values, values};
+
+#[inline]
+fn from_le_bytes<T: NativeType>(bytes: &[u8]) -> T {
+    // infallible since callers pass `size_of::<T>()` bytes
+    let bytes: T::Bytes = match bytes.try_into() {
+        Ok(bytes) => bytes,
+        Err(_) => unreachable!(),
+    };
+    T::from_le_bytes(bytes)
+}
+
+#[derive(Debug)]
+enum State<'a, T: NativeType> {
+    Plain(std::slice::ChunksExact<'a, u8>),
+    Dictionary(std::vec::IntoIter<u32>, &'a [T]),
+    ByteStreamSplit(byte_stream_split::Decoder<'a, T>),
+    DeltaBinaryPacked(Box<delta_bitpacked::Decoder<'a>>),
+}
+
+/// Iterator over the (non-null) values of a page of `INT32`, `INT64`, `INT96`, `FLOAT` or
+/// `DOUBLE`, represented by `T`.
+#[derive(Debug)]
+pub struct NativeValues<'a, T: NativeType> {
+    state: State<'a, T>,
+    remaining: usize,
+}
+
+impl<'a, T: NativeType> NativeValues<'a, T> {
+    /// Returns a new [`NativeValues`] of `page`.
+    /// # Error
+    /// Errors iff the physical type of the page is not represented by `T`, its encoding is
+    /// not supported, or its values are out of spec.
+    pub fn try_new(page: &'a DataPage) -> Result<Self> {
+        let physical_type = page.descriptor().physical_type();
+        if *physical_type != T::TYPE {
+            return Err(general_err!(
+                "Values of the physical type {:?} cannot be decoded as {:?}",
+                physical_type,
+                T::TYPE
+            ));
+        }
+        let values = values(page)?;
+        let num_values = num_values(page)?;
+
+        let state = match page.encoding() {
+            Encoding::Plain => {
+                let size = std::mem::size_of::<T>();
+                if values.len() < num_values * size {
+                    return Err(ParquetError::OutOfSpec(format!(
+                        "A page with {} PLAIN-encoded values of {} bytes cannot have {} bytes",
+                        num_values,
+                        size,
+                        values.len()
+                    )));
+                }
+                State::Plain(values[..num_values * size].chunks_exact(size))
+            }
+            Encoding::PlainDictionary | Encoding::RleDictionary => {
+                let dict = dict::<PrimitivePageDict<T>>(page)?.values();
+                State::Dictionary(dict_indices(values, num_values, dict.len())?, dict)
+            }
+            Encoding::ByteStreamSplit => {
+                State::ByteStreamSplit(byte_stream_split::Decoder::try_new(values)?)
+            }
+            Encoding::DeltaBinaryPacked
+                if matches!(physical_type, PhysicalType::Int32 | PhysicalType::Int64) =>
+            {
+                State::DeltaBinaryPacked(Box::new(delta_bitpacked::Decoder::try_new(values)?))
+            }
+            _ => return Err(not_supported(page)),
+        };
+        Ok(Self {
+            state,
+            remaining: num_values,
+        })
+    }
+}
+
+impl<'a, T: NativeType> Iterator for NativeValues<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = match &mut self.state {
+            State::Plain(chunks) => chunks.next().map(from_le_bytes),
+            State::Dictionary(indices, dict) => indices
+                .next()
+                .and_then(|index| dict.get(index as usize).copied()),
+            State::ByteStreamSplit(decoder) => decoder.next(),
+            State::DeltaBinaryPacked(decoder) => decoder.next().map(|value| {
+                // the (wrapping) truncation of the `i64` to `T`, i.e. `i32` or `i64`
+                from_le_bytes(&value.to_le_bytes()[..std::mem::size_of::<T>()])
+            }),
+        };
+        if result.is_some() {
+            self.remaining -= 1;
+        } else {
+            // the values are exhausted before `remaining`: the page is out of spec
+            self.remaining = 0;
+        }
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
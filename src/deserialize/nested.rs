@@ -0,0 +1,347 @@
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::DataPage;
+use crate::read::levels;
+use crate::schema::types::ParquetType;
+use crate::schema::Repetition;
+
+/// The structure of a node in the path of a column, from its top-level field to its leaf.
+///
+/// Every node has a number of slots: the records for top-level nodes, the slots of their
+/// parent for required and optional nodes, and the elements of the lists for repeated nodes.
+/// Slots of (or under) null values and empty lists do not have children slots, except for
+/// required and optional children of null values, that have a (null) slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nested {
+    /// A required node, with its number of slots
+    Required(usize),
+    /// An optional node, with the validity of each of its slots
+    Optional(Vec<bool>),
+    /// A repeated node, with the offsets of the list of elements of each slot of its parent
+    /// (or of each record, for top-level nodes)
+    Repeated(Vec<i64>),
+}
+
+impl Nested {
+    /// The number of slots of this node
+    pub fn len(&self) -> usize {
+        match self {
+            Nested::Required(length) => *length,
+            Nested::Optional(validity) => validity.len(),
+            Nested::Repeated(offsets) => offsets.last().copied().unwrap_or(0) as usize,
+        }
+    }
+
+    /// Whether this node has no slots
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A node in the path of a column, with its definition and repetition levels
+#[derive(Debug)]
+struct Node {
+    repetition: Repetition,
+    def_level: u32,
+    rep_level: u32,
+}
+
+/// Returns the nodes in the path of the column `descriptor`.
+fn nodes(descriptor: &ColumnDescriptor) -> Result<Vec<Node>> {
+    let mut type_ = descriptor.base_type();
+    let mut repetitions = vec![*type_.get_basic_info().repetition()];
+    for name in descriptor.path_in_schema().iter().skip(1) {
+        type_ = match type_ {
+            ParquetType::GroupType { fields, .. } => {
+                fields.iter().find(|field| field.name() == name)
+            }
+            ParquetType::PrimitiveType { .. } => None,
+        }
+        .ok_or_else(|| {
+            ParquetError::OutOfSpec(format!(
+                "The path {:?} does not exist in the schema of the column",
+                descriptor.path_in_schema()
+            ))
+        })?;
+        repetitions.push(*type_.get_basic_info().repetition());
+    }
+
+    let mut def_level = 0;
+    let mut rep_level = 0;
+    let nodes = repetitions
+        .into_iter()
+        .map(|repetition| {
+            match repetition {
+                Repetition::Required => {}
+                Repetition::Optional => def_level += 1,
+                Repetition::Repeated => {
+                    def_level += 1;
+                    rep_level += 1;
+                }
+            };
+            Node {
+                repetition,
+                def_level,
+                rep_level,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if def_level != descriptor.max_def_level() as u32
+        || rep_level != descriptor.max_rep_level() as u32
+    {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The maximum levels of the column {:?} do not match its path in the schema",
+            descriptor.path_in_schema()
+        )));
+    }
+    Ok(nodes)
+}
+
+/// Decoder of the repetition and definition levels of the pages of a column chunk into the
+/// [`Nested`] structure of every node in the path of the column.
+///
+/// The decoder keeps its state across pages, so that records split across pages are
+/// assembled correctly.
+/// The values of the column, as returned by e.g. [`super::NativeValues`], are the values of
+/// the valid slots of the leaf node.
+#[derive(Debug)]
+pub struct NestedDecoder {
+    nodes: Vec<Node>,
+    nested: Vec<Nested>,
+    // the number of elements of every repeated node, and of slots of every other node
+    lengths: Vec<i64>,
+    num_records: usize,
+}
+
+impl NestedDecoder {
+    /// Returns a new [`NestedDecoder`] of the column `descriptor`.
+    /// # Error
+    /// Errors iff the path of the column does not match its maximum levels.
+    pub fn try_new(descriptor: &ColumnDescriptor) -> Result<Self> {
+        let nodes = nodes(descriptor)?;
+        let nested = nodes
+            .iter()
+            .map(|node| match node.repetition {
+                Repetition::Required => Nested::Required(0),
+                Repetition::Optional => Nested::Optional(vec![]),
+                Repetition::Repeated => Nested::Repeated(vec![]),
+            })
+            .collect();
+        Ok(Self {
+            lengths: vec![0; nodes.len()],
+            nodes,
+            nested,
+            num_records: 0,
+        })
+    }
+
+    /// The number of records decoded so far
+    pub fn num_records(&self) -> usize {
+        self.num_records
+    }
+
+    /// Decodes the levels of a single value of the leaf.
+    fn push(&mut self, rep_level: u32, def_level: u32) {
+        let mut is_new = rep_level == 0;
+        if is_new {
+            self.num_records += 1;
+        }
+        let mut is_valid = true;
+        for ((node, nested), length) in self
+            .nodes
+            .iter()
+            .zip(self.nested.iter_mut())
+            .zip(self.lengths.iter_mut())
+        {
+            match nested {
+                Nested::Repeated(offsets) => {
+                    if is_new {
+                        // a new slot of the parent starts a new list
+                        offsets.push(*length);
+                    }
+                    let is_element = is_valid
+                        && def_level >= node.def_level
+                        && (is_new || rep_level == node.rep_level);
+                    if is_element {
+                        *length += 1;
+                        is_new = true;
+                    } else if is_new {
+                        // a null or empty list, whose children have no slots
+                        break;
+                    }
+                }
+                Nested::Optional(validity) => {
+                    if is_new {
+                        is_valid &= def_level >= node.def_level;
+                        validity.push(is_valid);
+                        *length += 1;
+                    }
+                }
+                Nested::Required(slots) => {
+                    if is_new {
+                        *slots += 1;
+                        *length += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decodes the repetition and definition levels of consecutive values of the leaf.
+    pub fn extend<R, D>(&mut self, rep_levels: R, def_levels: D)
+    where
+        R: Iterator<Item = u32>,
+        D: Iterator<Item = u32>,
+    {
+        rep_levels
+            .zip(def_levels)
+            .for_each(|(rep_level, def_level)| self.push(rep_level, def_level))
+    }
+
+    /// Decodes the repetition and definition levels of `page`.
+    /// # Error
+    /// Errors iff the levels of the page are not RLE-encoded.
+    pub fn extend_from_page(&mut self, page: &DataPage) -> Result<()> {
+        let (rep_levels, def_levels) = levels::decode(page)?;
+        self.extend(rep_levels, def_levels);
+        Ok(())
+    }
+
+    /// Returns the [`Nested`] structure of every node of the path of the column, from its
+    /// top-level field to its leaf.
+    pub fn finish(mut self) -> Vec<Nested> {
+        self.nested
+            .iter_mut()
+            .zip(self.lengths.iter())
+            .for_each(|(nested, length)| {
+                if let Nested::Repeated(offsets) = nested {
+                    offsets.push(*length);
+                }
+            });
+        self.nested
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metadata::SchemaDescriptor;
+
+    fn decoder(schema: &str) -> NestedDecoder {
+        let schema = SchemaDescriptor::try_from_message(schema).unwrap();
+        NestedDecoder::try_new(&schema.columns()[0]).unwrap()
+    }
+
+    fn extend(decoder: &mut NestedDecoder, rep_levels: &[u32], def_levels: &[u32]) {
+        decoder.extend(rep_levels.iter().copied(), def_levels.iter().copied())
+    }
+
+    fn list() -> NestedDecoder {
+        decoder(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL INT64 element;
+                    }
+                }
+            }",
+        )
+    }
+
+    #[test]
+    fn list_of_optional() {
+        // [[1, None], None, [], [2]]
+        let mut decoder = list();
+        extend(&mut decoder, &[0, 1, 0, 0, 0], &[3, 2, 0, 1, 3]);
+        assert_eq!(decoder.num_records(), 4);
+        assert_eq!(
+            decoder.finish(),
+            vec![
+                Nested::Optional(vec![true, false, true, true]),
+                Nested::Repeated(vec![0, 2, 2, 2, 3]),
+                Nested::Optional(vec![true, false, true]),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_across_pages() {
+        // [[1, 2, 3], [4]], split after the second value, as v1 pages allow
+        let mut decoder = list();
+        extend(&mut decoder, &[0, 1], &[3, 3]);
+        extend(&mut decoder, &[1, 0], &[3, 3]);
+        assert_eq!(decoder.num_records(), 2);
+        assert_eq!(
+            decoder.finish(),
+            vec![
+                Nested::Optional(vec![true, true]),
+                Nested::Repeated(vec![0, 3, 4]),
+                Nested::Optional(vec![true, true, true, true]),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_lists() {
+        // [[[1], []], [[2, 3]]], []
+        let mut decoder = decoder(
+            "message schema {
+                REQUIRED group a {
+                    REPEATED group b {
+                        REPEATED INT32 c;
+                    }
+                }
+            }",
+        );
+        extend(&mut decoder, &[0, 1, 0, 2, 0], &[2, 1, 2, 2, 0]);
+        let nested = decoder.finish();
+        assert_eq!(
+            nested,
+            vec![
+                Nested::Required(3),
+                Nested::Repeated(vec![0, 2, 3, 3]),
+                Nested::Repeated(vec![0, 1, 1, 3]),
+            ]
+        );
+        assert_eq!(nested[2].len(), 3);
+    }
+
+    #[test]
+    fn struct_() {
+        // {a: {b: 1}}, {a: None}, {a: {b: None}}
+        let mut decoder = decoder(
+            "message schema {
+                OPTIONAL group a {
+                    OPTIONAL INT32 b;
+                }
+            }",
+        );
+        extend(&mut decoder, &[0, 0, 0], &[2, 0, 1]);
+        assert_eq!(
+            decoder.finish(),
+            vec![
+                Nested::Optional(vec![true, false, true]),
+                Nested::Optional(vec![true, false, false]),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_levels() {
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema { OPTIONAL group a { REPEATED INT32 b; } }",
+        )
+        .unwrap();
+        let column = &schema.columns()[0];
+        let column = ColumnDescriptor::new(
+            column.type_().clone(),
+            1,
+            1,
+            column.path_in_schema().to_vec(),
+            column.base_type().clone(),
+        );
+        assert!(NestedDecoder::try_new(&column).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use std::convert::TryFrom;
+
+use crate::encoding::hybrid_rle::HybridRleDecoder;
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::page::{split_buffer, DataPage, DataPageHeader, DictPage};
+use crate::read::levels::get_bit_width;
+
+/// Returns the error of decoding a page whose encoding is not supported
+pub(super) fn not_supported(page: &DataPage) -> ParquetError {
+    general_err!(
+        "Decoding {:?}-encoded pages of the physical type {:?} is not supported",
+        page.encoding(),
+        page.descriptor().physical_type()
+    )
+}
+
+/// Returns the encoded values of `page`
+pub(super) fn values(page: &DataPage) -> Result<&[u8]> {
+    Ok(split_buffer(page, page.descriptor())?.2)
+}
+
+/// Returns the decoder of the definition levels of `page`, or `None` if its column is required.
+fn def_levels(page: &DataPage) -> Result<Option<HybridRleDecoder<'_>>> {
+    let max_def_level = page.descriptor().max_def_level();
+    if max_def_level == 0 {
+        return Ok(None);
+    }
+    if page.definition_level_encoding() != Encoding::Rle {
+        return Err(general_err!(
+            "Decoding definition levels encoded with {:?} is not supported",
+            page.definition_level_encoding()
+        ));
+    }
+    let (_, def_levels, _) = split_buffer(page, page.descriptor())?;
+    Ok(Some(HybridRleDecoder::new(
+        def_levels,
+        get_bit_width(max_def_level),
+        page.num_values(),
+    )))
+}
+
+/// Returns the number of (non-null) values encoded in `page`.
+pub(super) fn num_values(page: &DataPage) -> Result<usize> {
+    let max_def_level = page.descriptor().max_def_level() as u32;
+    if let DataPageHeader::V2(header) = page.header() {
+        return header
+            .num_values
+            .checked_sub(header.num_nulls)
+            .and_then(|x| usize::try_from(x).ok())
+            .ok_or_else(|| {
+                ParquetError::OutOfSpec(format!(
+                    "A page with {} values cannot have {} nulls",
+                    header.num_values, header.num_nulls
+                ))
+            });
+    }
+    Ok(match def_levels(page)? {
+        Some(def_levels) => def_levels.filter(|x| *x == max_def_level).count(),
+        None => page.num_values(),
+    })
+}
+
+/// Returns the dictionary of `page`, of type `D`.
+pub(super) fn dict<D: DictPage + 'static>(page: &DataPage) -> Result<&D> {
+    page.dictionary_page()
+        .ok_or_else(|| {
+            ParquetError::OutOfSpec(
+                "A dictionary-encoded page requires a dictionary page".to_string(),
+            )
+        })?
+        .as_any()
+        .downcast_ref::<D>()
+        .ok_or_else(|| {
+            general_err!(
+                "The dictionary page does not match the physical type {:?}",
+                page.descriptor().physical_type()
+            )
+        })
+}
+
+/// Returns the indices of a dictionary-encoded page with `values`, into a dictionary of
+/// `dict_len` values.
+/// # Error
+/// Errors iff the indices are out of spec or out of the bounds of the dictionary.
+pub(super) fn dict_indices(
+    values: &[u8],
+    num_values: usize,
+    dict_len: usize,
+) -> Result<std::vec::IntoIter<u32>> {
+    // SPEC: the bit width is stored in the first byte
+    let (bit_width, values) = match values.split_first() {
+        Some((bit_width, values)) => (*bit_width, values),
+        None if num_values == 0 => (0, values),
+        None => {
+            return Err(ParquetError::OutOfSpec(
+                "The values of a dictionary-encoded page must start with their bit width"
+                    .to_string(),
+            ))
+        }
+    };
+    if bit_width > 32 {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The bit width of dictionary indices must be at most 32, but it is {}",
+            bit_width
+        )));
+    }
+    let indices = HybridRleDecoder::new(values, bit_width as u32, num_values).collect::<Vec<_>>();
+    if let Some(index) = indices.iter().find(|index| **index as usize >= dict_len) {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The index {} is out of the bounds of a dictionary of {} values",
+            index, dict_len
+        )));
+    }
+    Ok(indices.into_iter())
+}
+
+/// An iterator adapter that uses the definition levels of a page to return `None` for every
+/// null value and `Some` for every value of `values`.
+///
+/// Values of required columns are always `Some`.
+#[derive(Debug)]
+pub struct OptionalValues<'a, I: Iterator> {
+    values: I,
+    def_levels: Option<HybridRleDecoder<'a>>,
+    max_def_level: u32,
+}
+
+impl<'a, I: Iterator> OptionalValues<'a, I> {
+    /// Returns a new [`OptionalValues`] of `page`, whose (non-null) values are `values`.
+    /// # Error
+    /// Errors iff the definition levels of the page are not RLE-encoded.
+    pub fn try_new(page: &'a DataPage, values: I) -> Result<Self> {
+        Ok(Self {
+            values,
+            def_levels: def_levels(page)?,
+            max_def_level: page.descriptor().max_def_level() as u32,
+        })
+    }
+}
+
+impl<'a, I: Iterator> Iterator for OptionalValues<'a, I> {
+    type Item = Option<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.def_levels.as_mut() {
+            Some(def_levels) => def_levels.next().map(|def_level| {
+                if def_level == self.max_def_level {
+                    self.values.next()
+                } else {
+                    None
+                }
+            }),
+            None => self.values.next().map(Some),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.def_levels.as_ref() {
+            Some(def_levels) => def_levels.size_hint(),
+            None => self.values.size_hint(),
+        }
+    }
+}
@@ -114,6 +114,79 @@ impl<'a> Iterator for Decoder<'a> {
     }
 }
 
+/// Encodes `u64` values into `compressed` using `num_bits` (up to 64), with the same layout as
+/// [`encode`]. `compressed` must have at least `ceil8(decompressed.len() * num_bits)` bytes,
+/// all set to zero.
+pub fn encode_u64(decompressed: &[u64], num_bits: u8, compressed: &mut [u8]) {
+    let num_bits = num_bits as usize;
+    decompressed.iter().enumerate().for_each(|(i, value)| {
+        let mut value = *value;
+        let mut bit = i * num_bits;
+        let mut remaining = num_bits;
+        while remaining > 0 {
+            let offset = bit % 8;
+            let length = std::cmp::min(8 - offset, remaining);
+            compressed[bit / 8] |= ((value & ((1 << length) - 1)) as u8) << offset;
+            value = value.checked_shr(length as u32).unwrap_or(0);
+            bit += length;
+            remaining -= length;
+        }
+    });
+}
+
+/// Decoder of `u64` values bitpacked with up to 64 bits, as encoded by [`encode_u64`].
+#[derive(Debug, Clone)]
+pub struct DecoderU64<'a> {
+    compressed: &'a [u8],
+    num_bits: usize,
+    index: usize,
+    length: usize,
+}
+
+impl<'a> DecoderU64<'a> {
+    /// Returns a new [`DecoderU64`] of the first `length` values of `num_bits` bits of
+    /// `compressed`.
+    /// # Panics
+    /// Iterating panics if `compressed` has less than `length * num_bits` bits or `num_bits`
+    /// exceeds 64.
+    pub fn new(compressed: &'a [u8], num_bits: u8, length: usize) -> Self {
+        Self {
+            compressed,
+            num_bits: num_bits as usize,
+            index: 0,
+            length,
+        }
+    }
+}
+
+impl<'a> Iterator for DecoderU64<'a> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.length {
+            return None;
+        }
+        let mut result = 0u64;
+        let mut bit = self.index * self.num_bits;
+        let mut read = 0;
+        while read < self.num_bits {
+            let offset = bit % 8;
+            let length = std::cmp::min(8 - offset, self.num_bits - read);
+            let bits = (self.compressed[bit / 8] >> offset) as u64 & ((1 << length) - 1);
+            result |= bits << read;
+            bit += length;
+            read += length;
+        }
+        self.index += 1;
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.length - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -252,4 +325,31 @@ mod tests {
         let decoded = Decoder::new(&data, num_bits, length).collect::<Vec<_>>();
         assert_eq!(decoded, expected);
     }
+
+    #[test]
+    fn u64_roundtrip() {
+        let data = [0, 1, u64::MAX, 1 << 63, 12345678901234567, 7];
+        for num_bits in [1u8, 3, 33, 64] {
+            let mask = if num_bits == 64 {
+                u64::MAX
+            } else {
+                (1 << num_bits) - 1
+            };
+            let data = data.iter().map(|x| x & mask).collect::<Vec<_>>();
+            let mut compressed = vec![0u8; ceil8(data.len() * num_bits as usize)];
+            encode_u64(&data, num_bits, &mut compressed);
+
+            let decoded = DecoderU64::new(&compressed, num_bits, data.len()).collect::<Vec<_>>();
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn u64_same_layout() {
+        let (num_bits, data, expected) = case1();
+        let data = data.into_iter().map(|x| x as u64).collect::<Vec<_>>();
+        let mut compressed = vec![0u8; ceil8(data.len() * num_bits as usize)];
+        encode_u64(&data, num_bits, &mut compressed);
+        assert_eq!(compressed, expected);
+    }
 }
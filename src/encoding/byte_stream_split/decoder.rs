@@ -0,0 +1,85 @@
+use std::convert::TryInto;
+use std::marker::PhantomData;
+
+use crate::error::{ParquetError, Result};
+use crate::types::NativeType;
+
+fn num_elements(values: &[u8], element_size: usize) -> Result<usize> {
+    if element_size == 0 || values.len() % element_size != 0 {
+        return Err(ParquetError::OutOfSpec(format!(
+            "A byte stream split buffer of {} bytes can't contain values of {} bytes",
+            values.len(),
+            element_size
+        )));
+    }
+    Ok(values.len() / element_size)
+}
+
+/// Decodes [byte stream split](https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9)
+/// values of a native type.
+/// # Implementation
+/// This struct does not allocate on the heap.
+#[derive(Debug)]
+pub struct Decoder<'a, T: NativeType> {
+    values: &'a [u8],
+    num_elements: usize,
+    index: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: NativeType> Decoder<'a, T> {
+    /// Returns a new [`Decoder`] of the byte stream split `values`.
+    /// # Error
+    /// Errors iff the length of `values` is not a multiple of the size of `T`.
+    pub fn try_new(values: &'a [u8]) -> Result<Self> {
+        let num_elements = num_elements(values, std::mem::size_of::<T>())?;
+        Ok(Self {
+            values,
+            num_elements,
+            index: 0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a, T: NativeType> Iterator for Decoder<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.num_elements {
+            return None;
+        }
+        // 16 bytes fit every native type
+        let mut bytes = [0u8; 16];
+        let element_size = std::mem::size_of::<T>();
+        for (stream, byte) in bytes[..element_size].iter_mut().enumerate() {
+            *byte = self.values[stream * self.num_elements + self.index];
+        }
+        self.index += 1;
+
+        let bytes: T::Bytes = match bytes[..element_size].try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => unreachable!(),
+        };
+        Some(T::from_le_bytes(bytes))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.num_elements - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Decodes the byte stream split `values` of `element_size` bytes each (e.g. of a
+/// `FIXED_LEN_BYTE_ARRAY`) into `buffer`, as their concatenation.
+/// # Error
+/// Errors iff `element_size` is zero or the length of `values` is not a multiple of it.
+pub fn decode_fixed_len(values: &[u8], element_size: usize, buffer: &mut Vec<u8>) -> Result<()> {
+    let num_elements = num_elements(values, element_size)?;
+    buffer.reserve(values.len());
+    for index in 0..num_elements {
+        buffer.extend((0..element_size).map(|stream| values[stream * num_elements + index]));
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::types::NativeType;
+
+/// Encodes `values` into `buffer` with byte stream split.
+pub fn encode<T: NativeType>(values: &[T], buffer: &mut Vec<u8>) {
+    let start = buffer.len();
+    buffer.resize(start + std::mem::size_of_val(values), 0);
+    let streams = &mut buffer[start..];
+
+    for (index, value) in values.iter().enumerate() {
+        for (stream, byte) in value.to_le_bytes().as_ref().iter().enumerate() {
+            streams[stream * values.len() + index] = *byte;
+        }
+    }
+}
+
+/// Encodes `values`, the concatenation of values of `element_size` bytes each (e.g. the
+/// values of a `FIXED_LEN_BYTE_ARRAY`), into `buffer` with byte stream split.
+/// # Panics
+/// This function panics iff `element_size` is zero or `values.len()` is not a multiple of it.
+pub fn encode_fixed_len(values: &[u8], element_size: usize, buffer: &mut Vec<u8>) {
+    assert_ne!(element_size, 0);
+    assert_eq!(values.len() % element_size, 0);
+    let num_elements = values.len() / element_size;
+    let start = buffer.len();
+    buffer.resize(start + values.len(), 0);
+    let streams = &mut buffer[start..];
+
+    for (index, value) in values.chunks_exact(element_size).enumerate() {
+        for (stream, byte) in value.iter().enumerate() {
+            streams[stream * num_elements + index] = *byte;
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! [Byte stream split](https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9)
+//! encoding: the `K` bytes of each of the `N` values are scattered into `K` streams of `N`
+//! bytes, such that stream `k` contains the `k`-th byte of every value.
+mod decoder;
+mod encoder;
+
+pub use decoder::{decode_fixed_len, Decoder};
+pub use encoder::{encode, encode_fixed_len};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spec() {
+        // the example of the specification
+        let values = [0xDDCCBBAAu32 as i32, 0x33221100, 0xA3A2A1A0u32 as i32];
+
+        let mut buffer = vec![];
+        encode(&values, &mut buffer);
+        assert_eq!(
+            buffer,
+            vec![0xAA, 0x00, 0xA0, 0xBB, 0x11, 0xA1, 0xCC, 0x22, 0xA2, 0xDD, 0x33, 0xA3]
+        );
+
+        let result = Decoder::<i32>::try_new(&buffer)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(result, values);
+    }
+
+    #[test]
+    fn floats() {
+        let values = (0..100).map(|x| x as f64 * 0.5).collect::<Vec<_>>();
+
+        let mut buffer = vec![];
+        encode(&values, &mut buffer);
+        assert_eq!(buffer.len(), 800);
+
+        let decoder = Decoder::<f64>::try_new(&buffer).unwrap();
+        assert_eq!(decoder.size_hint(), (100, Some(100)));
+        assert_eq!(decoder.collect::<Vec<_>>(), values);
+    }
+
+    #[test]
+    fn fixed_len() {
+        let values = b"abcdefghijkl";
+
+        let mut buffer = vec![];
+        encode_fixed_len(values, 3, &mut buffer);
+        assert_eq!(buffer, b"adgjbehkcfil");
+
+        let mut result = vec![];
+        decode_fixed_len(&buffer, 3, &mut result).unwrap();
+        assert_eq!(result, values);
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert!(Decoder::<i32>::try_new(&[0; 7]).is_err());
+        assert!(decode_fixed_len(&[0; 7], 3, &mut vec![]).is_err());
+        assert!(decode_fixed_len(&[0; 7], 0, &mut vec![]).is_err());
+    }
+}
@@ -1,9 +1,40 @@
 use crate::encoding::ceil8;
+use crate::error::{ParquetError, Result};
 
 use super::super::bitpacking;
 use super::super::uleb128;
 use super::super::zigzag_leb128;
 
+/// A bitpacked mini-block, decoded with the 32-bit unpacker whenever its bit width allows it
+#[derive(Debug)]
+enum MiniBlock<'a> {
+    U32(bitpacking::Decoder<'a>),
+    U64(bitpacking::DecoderU64<'a>),
+}
+
+impl<'a> MiniBlock<'a> {
+    fn try_new(values: &'a [u8], num_bits: u8, length: usize) -> Result<Self> {
+        if num_bits > 64 {
+            return Err(ParquetError::OutOfSpec(format!(
+                "The bit width of a DELTA_BINARY_PACKED mini-block must be at most 64; it is {}",
+                num_bits
+            )));
+        }
+        Ok(if num_bits <= 32 {
+            Self::U32(bitpacking::Decoder::new(values, num_bits, length))
+        } else {
+            Self::U64(bitpacking::DecoderU64::new(values, num_bits, length))
+        })
+    }
+
+    fn next(&mut self) -> Option<u64> {
+        match self {
+            Self::U32(decoder) => decoder.next().map(|x| x as u64),
+            Self::U64(decoder) => decoder.next(),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Block<'a> {
     // this is the minimum delta that must be added to every value.
@@ -15,18 +46,18 @@ struct Block<'a> {
     remaining: usize,     // number of elements
     current_index: usize, // invariant: < values_per_mini_block
     // None represents a relative delta of zero, in which case there is no miniblock.
-    current_miniblock: Option<bitpacking::Decoder<'a>>,
+    current_miniblock: Option<MiniBlock<'a>>,
     // number of bytes consumed.
     consumed_bytes: usize,
 }
 
 impl<'a> Block<'a> {
-    pub fn new(
+    pub fn try_new(
         mut values: &'a [u8],
         num_mini_blocks: usize,
         values_per_mini_block: usize,
         length: usize,
-    ) -> Self {
+    ) -> Result<Self> {
         let length = std::cmp::min(length, num_mini_blocks * values_per_mini_block);
 
         let mut consumed_bytes = 0;
@@ -51,12 +82,12 @@ impl<'a> Block<'a> {
         };
 
         // Set up first mini-block
-        block.advance_miniblock();
+        block.advance_miniblock()?;
 
-        block
+        Ok(block)
     }
 
-    fn advance_miniblock(&mut self) {
+    fn advance_miniblock(&mut self) -> Result<()> {
         let num_bits = self.bitwidths[0];
         self.bitwidths = &self.bitwidths[1..];
 
@@ -69,35 +100,39 @@ impl<'a> Block<'a> {
             self.values = remainder;
             self.consumed_bytes += miniblock_length;
 
-            Some(bitpacking::Decoder::new(miniblock, num_bits, length))
+            Some(MiniBlock::try_new(miniblock, num_bits, length)?)
         } else {
             None
         };
         self.current_index = 0;
+        Ok(())
     }
 }
 
 impl<'a> Iterator for Block<'a> {
-    type Item = i64;
+    type Item = Result<i64>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.remaining == 0 {
             return None;
         }
-        let result = self.min_delta
-            + self
-                .current_miniblock
+        if self.current_index == self.values_per_mini_block {
+            if let Err(error) = self.advance_miniblock() {
+                self.remaining = 0;
+                return Some(Err(error));
+            }
+        }
+        // deltas are encoded with wrapping arithmetic
+        let result = self.min_delta.wrapping_add(
+            self.current_miniblock
                 .as_mut()
                 .map(|x| x.next().unwrap())
-                .unwrap_or(0) as i64;
+                .unwrap_or(0) as i64,
+        );
         self.current_index += 1;
         self.remaining -= 1;
 
-        if self.remaining > 0 && self.current_index == self.values_per_mini_block {
-            self.advance_miniblock();
-        }
-
-        Some(result)
+        Some(Ok(result))
     }
 }
 
@@ -117,12 +152,95 @@ pub struct Decoder<'a> {
     consumed_bytes: usize,
 }
 
+/// Checks that the header and the blocks of `values` are within spec, i.e. that decoding
+/// them does not read past `values` nor bit widths above 64.
+fn validate(values: &[u8]) -> Result<()> {
+    let mut values = values;
+    let mut header = [0u64; 4];
+    for item in header.iter_mut() {
+        let (value, consumed) = uleb128::try_decode(values)?;
+        *item = value;
+        values = &values[consumed..];
+    }
+    let [block_size, num_mini_blocks, total_count, _] = header;
+
+    if block_size == 0 || block_size % 128 != 0 {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The block size of DELTA_BINARY_PACKED must be a positive multiple of 128; it is {}",
+            block_size
+        )));
+    }
+    let values_per_mini_block = block_size.checked_div(num_mini_blocks).unwrap_or(0);
+    // SPEC: the number of values of a mini-block is a multiple of 32
+    if values_per_mini_block == 0 || values_per_mini_block % 32 != 0 {
+        return Err(ParquetError::OutOfSpec(format!(
+            "A block of {} DELTA_BINARY_PACKED values cannot have {} mini-blocks",
+            block_size, num_mini_blocks
+        )));
+    }
+    let num_mini_blocks = num_mini_blocks as usize;
+    let values_per_mini_block = values_per_mini_block as usize;
+
+    let mut remaining = (total_count as usize).saturating_sub(1);
+    while remaining > 0 {
+        let (_, consumed) = uleb128::try_decode(values)?;
+        values = &values[consumed..];
+        if values.len() < num_mini_blocks {
+            return Err(ParquetError::OutOfSpec(
+                "The bit widths of a DELTA_BINARY_PACKED block are truncated".to_string(),
+            ));
+        }
+        let (bitwidths, mut miniblocks) = values.split_at(num_mini_blocks);
+        // only the mini-blocks with remaining values are read
+        for num_bits in bitwidths {
+            if remaining == 0 {
+                break;
+            }
+            if *num_bits > 64 {
+                return Err(ParquetError::OutOfSpec(format!(
+                    "The bit width of a DELTA_BINARY_PACKED mini-block must be at most 64; it is {}",
+                    num_bits
+                )));
+            }
+            let length = values_per_mini_block
+                .checked_mul(*num_bits as usize)
+                .map(ceil8)
+                .filter(|length| *length <= miniblocks.len())
+                .ok_or_else(|| {
+                    ParquetError::OutOfSpec(
+                        "A DELTA_BINARY_PACKED mini-block is truncated".to_string(),
+                    )
+                })?;
+            miniblocks = &miniblocks[length..];
+            remaining = remaining.saturating_sub(values_per_mini_block);
+        }
+        values = miniblocks;
+    }
+    Ok(())
+}
+
 impl<'a> Decoder<'a> {
-    pub fn new(mut values: &'a [u8]) -> Self {
+    /// Returns a new [`Decoder`] of `values`.
+    /// # Error
+    /// Errors iff `values` is out of spec, e.g. its header is invalid, a bit width exceeds 64
+    /// or a mini-block is truncated.
+    pub fn try_new(values: &'a [u8]) -> Result<Self> {
+        validate(values)?;
+        Self::decode(values)
+    }
+
+    /// Returns a new [`Decoder`] of `values`.
+    /// # Panics
+    /// This function panics if `values` is out of spec; see [`Decoder::try_new`].
+    pub fn new(values: &'a [u8]) -> Self {
+        Self::try_new(values).unwrap()
+    }
+
+    /// Returns a new [`Decoder`] of `values`, that must have been validated.
+    fn decode(mut values: &'a [u8]) -> Result<Self> {
         let mut consumed_bytes = 0;
         let (block_size, consumed) = uleb128::decode(values);
         consumed_bytes += consumed;
-        assert_eq!(block_size % 128, 0);
         values = &values[consumed..];
         let (num_mini_blocks, consumed) = uleb128::decode(values);
         let num_mini_blocks = num_mini_blocks as usize;
@@ -137,21 +255,20 @@ impl<'a> Decoder<'a> {
         values = &values[consumed..];
 
         let values_per_mini_block = block_size as usize / num_mini_blocks;
-        assert_eq!(values_per_mini_block % 8, 0);
 
         // If we only have one value (first_value), there are no blocks.
         let current_block = if total_count > 1 {
-            Some(Block::new(
+            Some(Block::try_new(
                 values,
                 num_mini_blocks,
                 values_per_mini_block,
                 total_count - 1,
-            ))
+            )?)
         } else {
             None
         };
 
-        Self {
+        Ok(Self {
             block_size,
             num_mini_blocks,
             values_per_mini_block,
@@ -160,7 +277,7 @@ impl<'a> Decoder<'a> {
             values,
             current_block,
             consumed_bytes,
-        }
+        })
     }
 
     /// Returns the total number of bytes consumed up to this point by [`Decoder`].
@@ -193,20 +310,24 @@ impl<'a> Iterator for Decoder<'a> {
             self.values = &self.values[current_block.consumed_bytes..];
             self.consumed_bytes += current_block.consumed_bytes;
 
-            let mut next_block = Block::new(
+            Block::try_new(
                 self.values,
                 self.num_mini_blocks,
                 self.values_per_mini_block,
                 self.values_remaining,
-            );
-
-            let delta = next_block.next().unwrap();
-            self.current_block = Some(next_block);
-
-            delta
+            )
+            .and_then(|mut next_block| {
+                let delta = next_block.next().unwrap();
+                self.current_block = Some(next_block);
+                delta
+            })
         };
 
-        self.next_value += delta;
+        match delta {
+            Ok(delta) => self.next_value = self.next_value.wrapping_add(delta),
+            // the block is out of spec: there is nothing left to decode
+            Err(_) => self.values_remaining = 0,
+        }
         result
     }
 
@@ -334,4 +455,57 @@ mod tests {
         assert_eq!(&expected[..], &r[..]);
         assert_eq!(decoder.consumed_bytes(), data.len() - 3);
     }
+
+    #[test]
+    fn multiple_miniblocks_try_new() {
+        let data = &[
+            128, 1, 4, 65, 100, 7, 3, 4, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x88, 0x88,
+            0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x88, 0x88,
+        ];
+        let decoder = Decoder::try_new(data).unwrap();
+        assert_eq!(decoder.count(), 65);
+    }
+
+    #[test]
+    fn bit_width_above_64() {
+        // header: block size 128, 4 mini-blocks, 6 elements, first value 1
+        // block1: min_delta -4, bit_widths [65, 0, 0, 0]
+        let mut data = vec![128, 1, 4, 6, 2, 7, 65, 0, 0, 0];
+        data.extend_from_slice(&[0; 260]);
+        assert!(matches!(
+            Decoder::try_new(&data),
+            Err(ParquetError::OutOfSpec(_))
+        ));
+        assert!(matches!(
+            Block::try_new(&data[5..], 4, 32, 5),
+            Err(ParquetError::OutOfSpec(_))
+        ));
+    }
+
+    #[test]
+    fn truncated_miniblock() {
+        // the 3-bit mini-block of 32 values requires 12 bytes
+        let data = &[128, 1, 4, 6, 2, 7, 3, 0, 0, 0, 0b01101101, 0b00001011];
+        assert!(matches!(
+            Decoder::try_new(data),
+            Err(ParquetError::OutOfSpec(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_header() {
+        for data in [
+            &[][..],
+            &[100, 4, 6, 2],
+            &[128, 1, 0, 6, 2],
+            &[128, 1, 3, 6, 2],
+            // mini-blocks of 8 values
+            &[128, 1, 16, 6, 2],
+        ] {
+            assert!(matches!(
+                Decoder::try_new(data),
+                Err(ParquetError::OutOfSpec(_))
+            ));
+        }
+    }
 }
@@ -1,74 +1,116 @@
 use crate::encoding::ceil8;
-use crate::read::levels::get_bit_width;
 
 use super::super::bitpacking;
 use super::super::uleb128;
 use super::super::zigzag_leb128;
 
-/// Encodes an iterator of `i32` according to parquet's `DELTA_BINARY_PACKED`.
+/// The default number of values per block
+pub const DEFAULT_BLOCK_SIZE: usize = 128;
+/// The default number of mini-blocks per block
+pub const DEFAULT_NUM_MINI_BLOCKS: usize = 4;
+
+/// Encodes an iterator of `i64` according to parquet's `DELTA_BINARY_PACKED`, with blocks of
+/// [`DEFAULT_BLOCK_SIZE`] values split in [`DEFAULT_NUM_MINI_BLOCKS`] mini-blocks.
+pub fn encode<I: Iterator<Item = i64>>(iterator: I, buffer: &mut Vec<u8>) {
+    encode_with_layout(
+        iterator,
+        buffer,
+        DEFAULT_BLOCK_SIZE,
+        DEFAULT_NUM_MINI_BLOCKS,
+    )
+}
+
+/// Encodes an iterator of `i64` according to parquet's `DELTA_BINARY_PACKED`, with blocks of
+/// `block_size` values split in `num_mini_blocks` mini-blocks.
+///
+/// Deltas are computed with wrapping arithmetic and bitpacked with up to 64 bits, so that
+/// any sequence of `i64` is representable.
+/// # Panics
+/// This function panics iff `block_size` is not a multiple of 128 or the number of values per
+/// mini-block (`block_size / num_mini_blocks`) is not a multiple of 32.
 /// # Implementation
-/// * This function does not allocate on the heap.
-/// * The number of mini-blocks is always 1. This may change in the future.
-pub fn encode<I: Iterator<Item = i64>>(mut iterator: I, buffer: &mut Vec<u8>) {
-    let block_size = 128;
-    let mini_blocks = 1;
+/// This function allocates the buffers of a single block.
+pub fn encode_with_layout<I: Iterator<Item = i64>>(
+    mut iterator: I,
+    buffer: &mut Vec<u8>,
+    block_size: usize,
+    num_mini_blocks: usize,
+) {
+    assert!(block_size > 0 && block_size.checked_rem(128) == Some(0));
+    assert_eq!(block_size.checked_rem(num_mini_blocks), Some(0));
+    let values_per_mini_block = block_size / num_mini_blocks;
+    assert_eq!(values_per_mini_block.checked_rem(32), Some(0));
 
     let mut container = [0u8; 10];
-    let encoded_len = uleb128::encode(block_size, &mut container);
+    let encoded_len = uleb128::encode(block_size as u64, &mut container);
     buffer.extend_from_slice(&container[..encoded_len]);
 
-    let encoded_len = uleb128::encode(mini_blocks, &mut container);
+    let encoded_len = uleb128::encode(num_mini_blocks as u64, &mut container);
     buffer.extend_from_slice(&container[..encoded_len]);
 
     let length = iterator.size_hint().1.unwrap();
     let encoded_len = uleb128::encode(length as u64, &mut container);
     buffer.extend_from_slice(&container[..encoded_len]);
 
-    let mut values = [0i64; 128];
-    let mut deltas = [0u32; 128];
-
-    let first_value = iterator.next().unwrap().into();
+    let first_value = iterator.next().unwrap_or_default();
     let (container, encoded_len) = zigzag_leb128::encode(first_value);
     buffer.extend_from_slice(&container[..encoded_len]);
 
+    let mut deltas = vec![0i64; block_size];
+    let mut mini_block = vec![0u64; values_per_mini_block];
+    let mut mini_block_u32 = vec![0u32; values_per_mini_block];
+
     let mut prev = first_value;
-    let mut length = iterator.size_hint().1.unwrap();
-    while length != 0 {
-        for (i, v) in (0..128).zip(&mut iterator) {
-            let v: i64 = v.into();
-            values[i] = v - prev;
+    loop {
+        let mut consumed = 0;
+        for (delta, v) in deltas.iter_mut().zip(&mut iterator) {
+            *delta = v.wrapping_sub(prev);
             prev = v;
+            consumed += 1;
         }
-        let consumed = std::cmp::min(length - iterator.size_hint().1.unwrap(), 128);
-        let values = &values[..consumed];
-
-        let min_delta = *values.iter().min().unwrap();
-        let max_delta = *values.iter().max().unwrap();
-
-        values.iter().zip(deltas.iter_mut()).for_each(|(v, d)| {
-            *d = (v - min_delta) as u32;
-        });
+        if consumed == 0 {
+            break;
+        }
+        let deltas = &deltas[..consumed];
 
         // <min delta> <list of bitwidths of miniblocks> <miniblocks>
+        let min_delta = *deltas.iter().min().unwrap();
         let (container, encoded_len) = zigzag_leb128::encode(min_delta);
         buffer.extend_from_slice(&container[..encoded_len]);
 
-        let num_bits = get_bit_width((max_delta - min_delta) as i16) as u8;
-        buffer.push(num_bits);
+        // the bitwidths of mini-blocks without values are zero
+        let bitwidths = buffer.len();
+        buffer.resize(bitwidths + num_mini_blocks, 0);
+
+        for (i, chunk) in deltas.chunks(values_per_mini_block).enumerate() {
+            // the last mini-block is padded with zeros
+            mini_block.iter_mut().for_each(|x| *x = 0);
+            chunk
+                .iter()
+                .zip(mini_block.iter_mut())
+                .for_each(|(delta, x)| {
+                    *x = delta.wrapping_sub(min_delta) as u64;
+                });
+
+            let max = mini_block.iter().copied().max().unwrap();
+            let num_bits = (64 - max.leading_zeros()) as u8;
+            buffer[bitwidths + i] = num_bits;
+            if num_bits == 0 {
+                continue;
+            }
 
-        if num_bits > 0 {
             let start = buffer.len();
-
-            // bitpack encode all (deltas.len = 128 which is a multiple of 32)
-            let bytes_needed = start + ceil8(deltas.len() * num_bits as usize);
-            buffer.resize(bytes_needed, 0);
-            bitpacking::encode(deltas.as_ref(), num_bits, &mut buffer[start..]);
-
-            let bytes_needed = start + ceil8(deltas.len() * num_bits as usize);
-            buffer.truncate(bytes_needed);
+            buffer.resize(start + ceil8(values_per_mini_block * num_bits as usize), 0);
+            if num_bits <= 32 {
+                mini_block
+                    .iter()
+                    .zip(mini_block_u32.iter_mut())
+                    .for_each(|(x, y)| *y = *x as u32);
+                bitpacking::encode(&mini_block_u32, num_bits, &mut buffer[start..]);
+            } else {
+                bitpacking::encode_u64(&mini_block, num_bits, &mut buffer[start..]);
+            }
         }
-
-        length = iterator.size_hint().1.unwrap();
     }
 }
 
@@ -90,7 +132,7 @@ mod tests {
         let expected = vec![128u8, 1, 1, 5, 2, 2, 0];
 
         let mut buffer = vec![];
-        encode(data, &mut buffer);
+        encode_with_layout(data, &mut buffer, 128, 1);
         assert_eq!(expected, buffer);
     }
 
@@ -113,8 +155,34 @@ mod tests {
         let mut expected = vec![128u8, 1, 1, 6, 2, 7, 3, 0b01101101, 0b00001011];
         expected.extend(std::iter::repeat(0).take(128 * 3 / 8 - 2)); // 128 values, 3 bits, 2 already used
 
+        let mut buffer = vec![];
+        encode_with_layout(data.into_iter(), &mut buffer, 128, 1);
+        assert_eq!(expected, buffer);
+    }
+
+    #[test]
+    fn default_layout() {
+        let data = vec![1, 2, 3, 4, 5, 1];
+        // header: [128, 1, 4, 6, 2]
+        // block1: [7, 3, 0, 0, 0] (only the first of the 4 mini-blocks is used)
+        //  values: [5, 5, 5, 5, 0] in 32 values of 3 bits
+        let mut expected = vec![128u8, 1, 4, 6, 2, 7, 3, 0, 0, 0, 0b01101101, 0b00001011];
+        expected.extend_from_slice(&[0; 32 * 3 / 8 - 2]);
+
         let mut buffer = vec![];
         encode(data.into_iter(), &mut buffer);
         assert_eq!(expected, buffer);
     }
+
+    #[test]
+    fn wide_deltas() {
+        // the deltas are i64::MIN and i64::MAX (wrapped from -1 - i64::MIN), whose difference
+        // requires 64 bits
+        let data = vec![0, i64::MIN, -1];
+        let mut buffer = vec![];
+        encode(data.into_iter(), &mut buffer);
+        // header (5 bytes), min delta (10 bytes), bitwidths (4 bytes) and 32 values of 64 bits
+        assert_eq!(buffer.len(), 5 + 10 + 4 + 32 * 8);
+        assert_eq!(&buffer[15..19], &[64, 0, 0, 0]);
+    }
 }
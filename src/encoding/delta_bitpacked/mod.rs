@@ -2,7 +2,7 @@ mod decoder;
 mod encoder;
 
 pub use decoder::Decoder;
-pub use encoder::encode;
+pub use encoder::{encode, encode_with_layout, DEFAULT_BLOCK_SIZE, DEFAULT_NUM_MINI_BLOCKS};
 
 #[cfg(test)]
 mod tests {
@@ -61,4 +61,50 @@ mod tests {
 
         assert_eq!(iter.consumed_bytes(), len);
     }
+
+    #[test]
+    fn extreme_values() {
+        let data = vec![
+            i64::MIN,
+            i64::MAX,
+            0,
+            i64::MIN,
+            -1,
+            1_600_000_000_000_000_000, // nanoseconds since epoch
+            -1_600_000_000_000_000_000,
+            i64::MAX,
+        ];
+
+        let mut buffer = vec![];
+        encode(data.clone().into_iter(), &mut buffer);
+        let len = buffer.len();
+        let mut iter = Decoder::new(&buffer);
+
+        let result = iter.by_ref().collect::<Vec<_>>();
+        assert_eq!(result, data);
+        assert_eq!(iter.consumed_bytes(), len);
+    }
+
+    #[test]
+    fn layouts() {
+        let data = (0..1000i64)
+            .map(|x| x * x * (x % 7 - 3) + x * 1_000_000_000_000)
+            .collect::<Vec<_>>();
+
+        for (block_size, num_mini_blocks) in [(128, 1), (128, 4), (256, 2), (256, 8), (512, 4)] {
+            let mut buffer = vec![];
+            encode_with_layout(
+                data.clone().into_iter(),
+                &mut buffer,
+                block_size,
+                num_mini_blocks,
+            );
+            let len = buffer.len();
+            let mut iter = Decoder::new(&buffer);
+
+            let result = iter.by_ref().collect::<Vec<_>>();
+            assert_eq!(result, data);
+            assert_eq!(iter.consumed_bytes(), len);
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use crate::error::Result;
+
 use super::super::delta_bitpacked;
 use super::super::delta_length_byte_array;
 
@@ -12,6 +14,21 @@ pub struct Decoder<'a> {
 }
 
 impl<'a> Decoder<'a> {
+    /// Returns a new [`Decoder`] of `values`.
+    /// # Error
+    /// Errors iff the `DELTA_BINARY_PACKED`-encoded prefix lengths of `values` are out of spec.
+    pub fn try_new(values: &'a [u8]) -> Result<Self> {
+        let prefix_lengths = delta_bitpacked::Decoder::try_new(values)?;
+        Ok(Self {
+            values,
+            prefix_lengths,
+        })
+    }
+
+    /// Returns a new [`Decoder`] of `values`.
+    /// # Panics
+    /// This function panics if the prefix lengths of `values` are out of spec; see
+    /// [`Decoder::try_new`].
     pub fn new(values: &'a [u8]) -> Self {
         let prefix_lengths = delta_bitpacked::Decoder::new(values);
         Self {
@@ -24,6 +41,18 @@ impl<'a> Decoder<'a> {
         assert_eq!(self.prefix_lengths.size_hint().0, 0);
         delta_length_byte_array::Decoder::new(&self.values[self.prefix_lengths.consumed_bytes()..])
     }
+
+    /// Consumes this decoder and returns the decoder of the suffixes.
+    /// # Error
+    /// Errors iff the `DELTA_BINARY_PACKED`-encoded lengths of the suffixes are out of spec.
+    /// # Panics
+    /// This function panics if this iterator has not been fully consumed.
+    pub fn try_into_lengths(self) -> Result<delta_length_byte_array::Decoder<'a>> {
+        assert_eq!(self.prefix_lengths.size_hint().0, 0);
+        delta_length_byte_array::Decoder::try_new(
+            &self.values[self.prefix_lengths.consumed_bytes()..],
+        )
+    }
 }
 
 impl<'a> Iterator for Decoder<'a> {
@@ -0,0 +1,49 @@
+use crate::encoding::{delta_bitpacked, delta_length_byte_array};
+
+/// The suffix of `value` after its first `prefix` bytes
+struct Suffix<A: AsRef<[u8]>> {
+    value: A,
+    prefix: usize,
+}
+
+impl<A: AsRef<[u8]>> AsRef<[u8]> for Suffix<A> {
+    fn as_ref(&self) -> &[u8] {
+        &self.value.as_ref()[self.prefix..]
+    }
+}
+
+/// Encodes a clonable iterator of `&[u8]` into `buffer` according to
+/// [Delta strings](https://github.com/apache/parquet-format/blob/master/Encodings.md#delta-strings-delta_byte_array--7).
+/// # Implementation
+/// This encoding is equivalent to call [`delta_bitpacked::encode`] on the lengths of the prefixes
+/// shared by each item with its previous item, followed by [`delta_length_byte_array::encode`]
+/// on the remaining suffixes.
+pub fn encode<A: AsRef<[u8]>, I: Iterator<Item = A> + Clone>(iterator: I, buffer: &mut Vec<u8>) {
+    let mut previous: Option<A> = None;
+    let prefixes = iterator
+        .clone()
+        .map(|value| {
+            let prefix = previous
+                .as_ref()
+                .map(|previous| {
+                    previous
+                        .as_ref()
+                        .iter()
+                        .zip(value.as_ref().iter())
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                })
+                .unwrap_or(0);
+            previous = Some(value);
+            prefix
+        })
+        .collect::<Vec<_>>();
+
+    delta_bitpacked::encode(prefixes.iter().map(|prefix| *prefix as i64), buffer);
+
+    let suffixes = iterator.zip(prefixes.iter()).map(|(value, prefix)| Suffix {
+        value,
+        prefix: *prefix,
+    });
+    delta_length_byte_array::encode(suffixes, buffer);
+}
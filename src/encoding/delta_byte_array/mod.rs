@@ -1,3 +1,73 @@
 mod decoder;
+mod encoder;
 
 pub use decoder::Decoder;
+pub use encoder::encode;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(buffer: &[u8]) -> Vec<Vec<u8>> {
+        let mut decoder = Decoder::new(buffer);
+        let prefixes = decoder.by_ref().collect::<Vec<_>>();
+
+        let mut decoder = decoder.into_lengths();
+        let lengths = decoder.by_ref().collect::<Vec<_>>();
+        let mut suffixes = decoder.into_values();
+
+        let mut previous: Vec<u8> = vec![];
+        prefixes
+            .iter()
+            .zip(lengths.iter())
+            .map(|(prefix, length)| {
+                let (suffix, remaining) = suffixes.split_at(*length as usize);
+                suffixes = remaining;
+                let mut value = previous[..*prefix as usize].to_vec();
+                value.extend_from_slice(suffix);
+                previous = value.clone();
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn basic() {
+        let data = ["Hello", "Help", "Helping", "World", "", "W"];
+
+        let mut buffer = vec![];
+        encode(data.iter().map(|x| x.as_bytes()), &mut buffer);
+
+        let mut decoder = Decoder::new(&buffer);
+        let prefixes = decoder.by_ref().collect::<Vec<_>>();
+        assert_eq!(prefixes, vec![0, 3, 4, 0, 0, 0]);
+
+        let mut decoder = decoder.into_lengths();
+        let lengths = decoder.by_ref().collect::<Vec<_>>();
+        assert_eq!(lengths, vec![5, 1, 3, 5, 0, 1]);
+        assert_eq!(decoder.into_values(), b"HellopingWorldW");
+
+        let expected = data
+            .iter()
+            .map(|x| x.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(decode(&buffer), expected);
+    }
+
+    #[test]
+    fn many_paths() {
+        let data = (0..300)
+            .map(|i| format!("/data/year={}/part-{}.parquet", 2000 + i / 100, i))
+            .collect::<Vec<_>>();
+
+        let mut buffer = vec![];
+        encode(data.iter(), &mut buffer);
+        assert!(buffer.len() < data.iter().map(|x| x.len()).sum::<usize>() / 2);
+
+        let expected = data
+            .iter()
+            .map(|x| x.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(decode(&buffer), expected);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::Result;
+
 use super::super::delta_bitpacked;
 
 /// Decodes [Delta-length byte array](https://github.com/apache/parquet-format/blob/master/Encodings.md#delta-length-byte-array-delta_length_byte_array--6)
@@ -34,6 +36,22 @@ pub struct Decoder<'a> {
 }
 
 impl<'a> Decoder<'a> {
+    /// Returns a new [`Decoder`] of `values`.
+    /// # Error
+    /// Errors iff the `DELTA_BINARY_PACKED`-encoded lengths of `values` are out of spec.
+    pub fn try_new(values: &'a [u8]) -> Result<Self> {
+        let lengths = delta_bitpacked::Decoder::try_new(values)?;
+        Ok(Self {
+            values,
+            lengths,
+            total_length: 0,
+        })
+    }
+
+    /// Returns a new [`Decoder`] of `values`.
+    /// # Panics
+    /// This function panics if the lengths of `values` are out of spec; see
+    /// [`Decoder::try_new`].
     pub fn new(values: &'a [u8]) -> Self {
         let lengths = delta_bitpacked::Decoder::new(values);
         Self {
@@ -43,13 +61,17 @@ impl<'a> Decoder<'a> {
         }
     }
 
-    /// Consumes this decoder and returns the slice of concatenated values.
+    /// Consumes this decoder and returns the slice of concatenated values, truncated when
+    /// `values` is shorter than the sum of the lengths (i.e. out of spec).
     /// # Panics
     /// This function panics if this iterator has not been fully consumed.
     pub fn into_values(self) -> &'a [u8] {
         assert_eq!(self.lengths.size_hint().0, 0);
-        let start = self.lengths.consumed_bytes();
-        &self.values[start..start + self.total_length as usize]
+        let start = self.lengths.consumed_bytes().min(self.values.len());
+        let end = start
+            .saturating_add(self.total_length as usize)
+            .min(self.values.len());
+        &self.values[start..end]
     }
 }
 
@@ -59,7 +81,7 @@ impl<'a> Iterator for Decoder<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         let result = self.lengths.next();
         if let Some(result) = result {
-            self.total_length += result as u32
+            self.total_length = self.total_length.saturating_add(result as u32)
         }
         result.map(|x| x as i32)
     }
@@ -41,6 +41,11 @@ impl<'a> Iterator for Decoder<'a> {
             let run_length = indicator as usize >> 1;
             // repeated-value := value that is repeated, using a fixed-width of round-up-to-next-byte(bit-width)
             let rle_bytes = ceil8(self.num_bits as usize);
+            if self.values.len() < rle_bytes {
+                // a truncated run is out of spec: there is nothing left to decode
+                self.values = &[];
+                return None;
+            }
             let result = Some(HybridEncoded::Rle(&self.values[..rle_bytes], run_length));
             self.values = &self.values[rle_bytes..];
             result
@@ -122,4 +127,15 @@ mod tests {
             panic!()
         };
     }
+
+    #[test]
+    fn truncated_rle() {
+        // a run of 4 values of bit width 9 (2 bytes) with a single byte
+        let values = vec![4 << 1, 0b00000001];
+
+        let mut decoder = Decoder::new(&values, 9);
+
+        assert_eq!(decoder.next(), None);
+        assert_eq!(decoder.next(), None);
+    }
 }
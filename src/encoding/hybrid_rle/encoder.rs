@@ -5,26 +5,70 @@ use std::io::Write;
 
 use super::bitpacked_encode;
 
-/// RLE-hybrid encoding of `u32`. This currently only yields bitpacked values.
+/// The minimum number of repeated values encoded as a RLE run.
+/// Shorter runs are bitpacked, as a RLE run costs at least 2 bytes.
+const MIN_RLE_RUN: usize = 8;
+
+/// RLE-hybrid encoding of `u32`. Runs of at least 8 repeated values are RLE-encoded;
+/// the remaining values are bitpacked in groups of 8.
 pub fn encode_u32<W: Write, I: Iterator<Item = u32>>(
     writer: &mut W,
     iterator: I,
     num_bits: u8,
 ) -> std::io::Result<()> {
-    // the length of the iterator.
-    let length = iterator.size_hint().1.unwrap();
+    let values = iterator.collect::<Vec<_>>();
+
+    // the start of the values not yet written, to be bitpacked
+    let mut start = 0;
+    let mut index = 0;
+    while index < values.len() {
+        let value = values[index];
+        let run_length = values[index..].iter().take_while(|x| **x == value).count();
+
+        // SPEC: bitpacked runs must contain a multiple of 8 values, which the first values of
+        // this run complete
+        let to_pack = (8 - (index - start) % 8) % 8;
+        if run_length >= to_pack + MIN_RLE_RUN {
+            write_bitpacked(writer, &values[start..index + to_pack], num_bits)?;
+            write_rle(writer, value, run_length - to_pack, num_bits)?;
+            start = index + run_length;
+        }
+        index += run_length;
+    }
+    write_bitpacked(writer, &values[start..], num_bits)
+}
 
+/// Writes `values` as a single bitpacked run.
+fn write_bitpacked<W: Write>(writer: &mut W, values: &[u32], num_bits: u8) -> std::io::Result<()> {
+    if values.is_empty() {
+        return Ok(());
+    }
     // write the length + indicator
-    let mut header = ceil8(length as usize) as u64;
+    let mut header = ceil8(values.len()) as u64;
     header <<= 1;
     header |= 1; // it is bitpacked => first bit is set
     let mut container = [0; 10];
     let used = uleb128::encode(header, &mut container);
     writer.write_all(&container[..used])?;
 
-    bitpacked_encode_u32(writer, iterator, num_bits)?;
+    bitpacked_encode_u32(writer, values.iter().copied(), num_bits)
+}
 
-    Ok(())
+/// Writes a RLE run of `value` repeated `run_length` times.
+fn write_rle<W: Write>(
+    writer: &mut W,
+    value: u32,
+    run_length: usize,
+    num_bits: u8,
+) -> std::io::Result<()> {
+    // write the length + indicator (it is RLE => first bit is not set)
+    let header = (run_length as u64) << 1;
+    let mut container = [0; 10];
+    let used = uleb128::encode(header, &mut container);
+    writer.write_all(&container[..used])?;
+
+    // SPEC: the value is written in little endian, in ceil8(num_bits) bytes
+    writer.write_all(&value.to_le_bytes()[..ceil8(num_bits as usize)])
 }
 
 fn bitpacked_encode_u32<W: Write, I: Iterator<Item = u32>>(
@@ -158,4 +202,40 @@ mod tests {
         assert_eq!(expected, vec);
         Ok(())
     }
+
+    #[test]
+    fn test_u32_rle() -> std::io::Result<()> {
+        let values = [3; 100].iter().copied();
+
+        let mut vec = vec![];
+        encode_u32(&mut vec, values, 2)?;
+
+        // a single run: uleb128(100 << 1) followed by the value
+        assert_eq!(vec, vec![200, 1, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_u32_mixed() -> std::io::Result<()> {
+        // 3 distinct values, a run of 15 values (whose first 5 complete the bitpacked group),
+        // and 2 distinct values
+        let values = vec![0, 1, 2].into_iter().chain([1; 15]).chain(vec![2, 0]);
+
+        let mut vec = vec![];
+        encode_u32(&mut vec, values, 2)?;
+
+        assert_eq!(
+            vec,
+            vec![
+                (1 << 1 | 1),
+                0b01_10_01_00,
+                0b01_01_01_01,
+                (10 << 1),
+                1,
+                (1 << 1 | 1),
+                0b00_00_00_10
+            ]
+        );
+        Ok(())
+    }
 }
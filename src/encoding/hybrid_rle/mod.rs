@@ -117,6 +117,25 @@ mod tests {
         assert_eq!(result, data);
     }
 
+    #[test]
+    fn roundtrip_runs() {
+        let mut buffer = vec![];
+        let num_bits = 3;
+
+        // runs of every length, starting at every offset of a bitpacked group
+        let data = (0..40u32)
+            .flat_map(|x| vec![x % 8; x as usize % 13])
+            .collect::<Vec<_>>();
+
+        encode_u32(&mut buffer, data.iter().cloned(), num_bits).unwrap();
+
+        let decoder = HybridRleDecoder::new(&buffer, num_bits as u32, data.len());
+
+        let result = decoder.collect::<Vec<_>>();
+
+        assert_eq!(result, data);
+    }
+
     #[test]
     fn pyarrow_integration() {
         // data encoded from pyarrow representing (0..1000)
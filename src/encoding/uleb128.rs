@@ -1,4 +1,18 @@
+use crate::error::{ParquetError, Result};
+
+/// Decodes a ULEB128-encoded value from the start of `values`, returning it and the number
+/// of bytes it was encoded with.
+/// # Panics
+/// This function panics if the value overflows 64 bits; see [`try_decode`].
 pub fn decode(values: &[u8]) -> (u64, usize) {
+    try_decode(values).unwrap()
+}
+
+/// Decodes a ULEB128-encoded value from the start of `values`, returning it and the number
+/// of bytes it was encoded with.
+/// # Error
+/// Errors iff the value overflows 64 bits.
+pub fn try_decode(values: &[u8]) -> Result<(u64, usize)> {
     let mut result = 0;
     let mut shift = 0;
 
@@ -6,7 +20,9 @@ pub fn decode(values: &[u8]) -> (u64, usize) {
     for byte in values {
         consumed += 1;
         if shift == 63 && *byte > 1 {
-            panic!()
+            return Err(ParquetError::OutOfSpec(
+                "A ULEB128-encoded value cannot overflow 64 bits".to_string(),
+            ));
         };
 
         result |= u64::from(byte & 0b01111111) << shift;
@@ -17,7 +33,7 @@ pub fn decode(values: &[u8]) -> (u64, usize) {
 
         shift += 7;
     }
-    (result, consumed)
+    Ok((result, consumed))
 }
 
 /// Encodes `value` in ULEB128 into `container`. The exact number of bytes written
@@ -0,0 +1,336 @@
+//! The AES ciphers of parquet modular encryption.
+//!
+//! Every encrypted module is written as `length | nonce | ciphertext [| tag]`, where `length`
+//! is the (4 bytes, little endian) length of the rest of the module and `tag` is only present
+//! for AES GCM.
+use std::convert::TryInto;
+
+use crate::error::{ParquetError, Result};
+
+/// The number of bytes of the length of a module
+pub(crate) const SIZE_LEN: usize = 4;
+/// The number of bytes of a nonce
+pub(crate) const NONCE_LEN: usize = 12;
+/// The number of bytes of the authentication tag of AES GCM
+pub(crate) const TAG_LEN: usize = 16;
+
+/// Splits a module into its nonce and its ciphertext (followed by the tag in AES GCM),
+/// validating its length.
+fn split_module(module: &[u8], tag_len: usize) -> Result<(&[u8], &[u8])> {
+    if module.len() < SIZE_LEN + NONCE_LEN + tag_len {
+        return Err(ParquetError::OutOfSpec(format!(
+            "An encrypted module must have at least {} bytes, but it has {}",
+            SIZE_LEN + NONCE_LEN + tag_len,
+            module.len()
+        )));
+    }
+    let length = u32::from_le_bytes(module[..SIZE_LEN].try_into().unwrap()) as usize;
+    if length != module.len() - SIZE_LEN {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The length of an encrypted module ({}) does not match its declared length ({})",
+            module.len() - SIZE_LEN,
+            length
+        )));
+    }
+    let module = &module[SIZE_LEN..];
+    Ok((&module[..NONCE_LEN], &module[NONCE_LEN..]))
+}
+
+/// Decrypts a module (`length | nonce | ciphertext | tag`) encrypted with AES GCM.
+pub(crate) fn gcm_decrypt(key: &[u8], aad: &[u8], module: &[u8]) -> Result<Vec<u8>> {
+    let (nonce, ciphertext) = split_module(module, TAG_LEN)?;
+    imp::gcm_decrypt(key, nonce, aad, ciphertext)
+}
+
+/// Decrypts a module (`length | nonce | ciphertext`) encrypted with AES CTR.
+pub(crate) fn ctr_decrypt(key: &[u8], module: &[u8]) -> Result<Vec<u8>> {
+    let (nonce, ciphertext) = split_module(module, 0)?;
+    let mut plaintext = ciphertext.to_vec();
+    imp::ctr_apply(key, nonce, &mut plaintext)?;
+    Ok(plaintext)
+}
+
+/// Assembles the module `length | nonce | ciphertext`.
+fn to_module(nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let length: u32 = (nonce.len() + ciphertext.len()).try_into().map_err(|_| {
+        ParquetError::OutOfSpec(format!(
+            "An encrypted module can only contain u32::MAX bytes. This one contains {}",
+            nonce.len() + ciphertext.len()
+        ))
+    })?;
+    let mut module = Vec::with_capacity(SIZE_LEN + length as usize);
+    module.extend_from_slice(&length.to_le_bytes());
+    module.extend_from_slice(nonce);
+    module.extend_from_slice(ciphertext);
+    Ok(module)
+}
+
+/// Encrypts `plaintext` with AES GCM and a random nonce into a module
+/// (`length | nonce | ciphertext | tag`).
+pub(crate) fn gcm_encrypt(key: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = random_bytes(NONCE_LEN)?;
+    to_module(&nonce, &imp::gcm_encrypt(key, &nonce, aad, plaintext)?)
+}
+
+/// Encrypts `plaintext` with AES CTR and a random nonce into a module
+/// (`length | nonce | ciphertext`).
+pub(crate) fn ctr_encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = random_bytes(NONCE_LEN)?;
+    let mut ciphertext = plaintext.to_vec();
+    imp::ctr_apply(key, &nonce, &mut ciphertext)?;
+    to_module(&nonce, &ciphertext)
+}
+
+/// Returns `length` cryptographically secure random bytes.
+pub(crate) fn random_bytes(length: usize) -> Result<Vec<u8>> {
+    let mut bytes = vec![0; length];
+    imp::fill_random(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Returns whether the tags `a` and `b` are equal, in constant time.
+pub(crate) fn tags_eq(a: &[u8], b: &[u8]) -> bool {
+    imp::constant_time_eq(a, b)
+}
+
+/// Returns the ciphertext followed by the tag of `plaintext` encrypted with AES GCM.
+pub(crate) fn gcm_encrypt_raw(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    imp::gcm_encrypt(key, nonce, aad, plaintext)
+}
+
+#[cfg(feature = "encryption")]
+mod imp {
+    use aes_gcm::aead::consts::U12;
+    use aes_gcm::aead::generic_array::GenericArray;
+    use aes_gcm::aead::rand_core::RngCore;
+    use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+    use aes_gcm::aes::{Aes128, Aes192, Aes256};
+    use aes_gcm::AesGcm;
+    use ctr::cipher::consts::U16;
+    use ctr::cipher::{BlockCipher, BlockEncrypt, BlockSizeUser, KeyIvInit, StreamCipher};
+    use ctr::Ctr32BE;
+    use subtle::ConstantTimeEq;
+
+    use crate::error::{ParquetError, Result};
+
+    fn invalid_key_length(length: usize) -> ParquetError {
+        general_err!(
+            "The key of AES must have 16, 24 or 32 bytes, but it has {}",
+            length
+        )
+    }
+
+    fn gcm_cipher<C: KeyInit>(key: &[u8]) -> Result<C> {
+        C::new_from_slice(key).map_err(|_| invalid_key_length(key.len()))
+    }
+
+    fn gcm_decrypt_with<C: KeyInit + Aead>(
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        gcm_cipher::<C>(key)?
+            .decrypt(
+                GenericArray::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| {
+                general_err!(
+                    "Failed to decrypt a module with AES GCM: either the key is wrong or the module was tampered with"
+                )
+            })
+    }
+
+    fn gcm_encrypt_with<C: KeyInit + Aead>(
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        gcm_cipher::<C>(key)?
+            .encrypt(
+                GenericArray::from_slice(nonce),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| general_err!("Failed to encrypt a module with AES GCM"))
+    }
+
+    pub fn gcm_decrypt(key: &[u8], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        match key.len() {
+            16 => gcm_decrypt_with::<AesGcm<Aes128, U12>>(key, nonce, aad, ciphertext),
+            24 => gcm_decrypt_with::<AesGcm<Aes192, U12>>(key, nonce, aad, ciphertext),
+            32 => gcm_decrypt_with::<AesGcm<Aes256, U12>>(key, nonce, aad, ciphertext),
+            other => Err(invalid_key_length(other)),
+        }
+    }
+
+    pub fn gcm_encrypt(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        match key.len() {
+            16 => gcm_encrypt_with::<AesGcm<Aes128, U12>>(key, nonce, aad, plaintext),
+            24 => gcm_encrypt_with::<AesGcm<Aes192, U12>>(key, nonce, aad, plaintext),
+            32 => gcm_encrypt_with::<AesGcm<Aes256, U12>>(key, nonce, aad, plaintext),
+            other => Err(invalid_key_length(other)),
+        }
+    }
+
+    fn ctr_apply_with<C: BlockCipher + BlockEncrypt + BlockSizeUser<BlockSize = U16> + KeyInit>(
+        key: &[u8],
+        nonce: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<()> {
+        // SPEC: the IV is the nonce followed by a 4 bytes (big endian) counter starting at 1
+        let mut iv = [0u8; 16];
+        iv[..super::NONCE_LEN].copy_from_slice(nonce);
+        iv[15] = 1;
+        let mut cipher =
+            Ctr32BE::<C>::new_from_slices(key, &iv).map_err(|_| invalid_key_length(key.len()))?;
+        cipher.apply_keystream(buffer);
+        Ok(())
+    }
+
+    pub fn ctr_apply(key: &[u8], nonce: &[u8], buffer: &mut [u8]) -> Result<()> {
+        match key.len() {
+            16 => ctr_apply_with::<Aes128>(key, nonce, buffer),
+            24 => ctr_apply_with::<Aes192>(key, nonce, buffer),
+            32 => ctr_apply_with::<Aes256>(key, nonce, buffer),
+            other => Err(invalid_key_length(other)),
+        }
+    }
+
+    pub fn fill_random(buffer: &mut [u8]) -> Result<()> {
+        OsRng
+            .try_fill_bytes(buffer)
+            .map_err(|e| general_err!("Failed to generate random bytes: {}", e))
+    }
+
+    pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        a.ct_eq(b).into()
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+mod imp {
+    use crate::error::{Feature, ParquetError, Result};
+
+    pub fn gcm_decrypt(_: &[u8], _: &[u8], _: &[u8], _: &[u8]) -> Result<Vec<u8>> {
+        Err(ParquetError::FeatureNotActive(
+            Feature::Encryption,
+            "decrypt with AES GCM".to_string(),
+        ))
+    }
+
+    pub fn gcm_encrypt(_: &[u8], _: &[u8], _: &[u8], _: &[u8]) -> Result<Vec<u8>> {
+        Err(ParquetError::FeatureNotActive(
+            Feature::Encryption,
+            "encrypt with AES GCM".to_string(),
+        ))
+    }
+
+    pub fn ctr_apply(_: &[u8], _: &[u8], _: &mut [u8]) -> Result<()> {
+        Err(ParquetError::FeatureNotActive(
+            Feature::Encryption,
+            "apply AES CTR".to_string(),
+        ))
+    }
+
+    pub fn fill_random(_: &mut [u8]) -> Result<()> {
+        Err(ParquetError::FeatureNotActive(
+            Feature::Encryption,
+            "generate random nonces".to_string(),
+        ))
+    }
+
+    pub fn constant_time_eq(_: &[u8], _: &[u8]) -> bool {
+        // tags are only computed with the feature `encryption`
+        false
+    }
+}
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcm_known_answer() -> Result<()> {
+        // test case 2 of the GCM specification
+        let key = [0u8; 16];
+        let nonce = [0u8; 12];
+        let plaintext = [0u8; 16];
+
+        let ciphertext = gcm_encrypt_raw(&key, &nonce, &[], &plaintext)?;
+        let expected = [
+            0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92, 0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2,
+            0xfe, 0x78, // ciphertext
+            0xab, 0x6e, 0x47, 0xd4, 0x2c, 0xec, 0x13, 0xbd, 0xf5, 0x3a, 0x67, 0xb2, 0x12, 0x57,
+            0xbd, 0xdf, // tag
+        ];
+        assert_eq!(ciphertext, expected);
+
+        let module = to_module(&nonce, &ciphertext)?;
+        assert_eq!(gcm_decrypt(&key, &[], &module)?, plaintext);
+        Ok(())
+    }
+
+    #[test]
+    fn gcm_authenticates() -> Result<()> {
+        let key = b"0123456789012345";
+        let nonce = [1u8; 12];
+
+        let ciphertext = gcm_encrypt_raw(key, &nonce, b"aad", b"hello")?;
+        let module = to_module(&nonce, &ciphertext)?;
+        assert_eq!(gcm_decrypt(key, b"aad", &module)?, b"hello");
+
+        // wrong aad, wrong key and tampered modules are rejected
+        assert!(gcm_decrypt(key, b"other", &module).is_err());
+        assert!(gcm_decrypt(b"1234567890123450", b"aad", &module).is_err());
+        let mut tampered = module.clone();
+        tampered[SIZE_LEN + NONCE_LEN] ^= 1;
+        assert!(gcm_decrypt(key, b"aad", &tampered).is_err());
+        // invalid lengths
+        assert!(gcm_decrypt(key, b"aad", &module[..module.len() - 1]).is_err());
+        assert!(gcm_decrypt(b"short", b"aad", &module).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ctr_round_trip() -> Result<()> {
+        let key = [2u8; 32];
+        let nonce = [3u8; 12];
+        let plaintext = (0..100u8).collect::<Vec<_>>();
+
+        let mut ciphertext = plaintext.clone();
+        imp::ctr_apply(&key, &nonce, &mut ciphertext)?;
+        assert_ne!(ciphertext, plaintext);
+
+        let module = to_module(&nonce, &ciphertext)?;
+        assert_eq!(ctr_decrypt(&key, &module)?, plaintext);
+        Ok(())
+    }
+
+    #[test]
+    fn random_nonces() -> Result<()> {
+        let key = [4u8; 24];
+        let a = gcm_encrypt(&key, b"aad", b"hello")?;
+        let b = gcm_encrypt(&key, b"aad", b"hello")?;
+        assert_ne!(a, b);
+        assert_eq!(gcm_decrypt(&key, b"aad", &a)?, b"hello");
+        assert_eq!(gcm_decrypt(&key, b"aad", &b)?, b"hello");
+
+        let module = ctr_encrypt(&key, b"hello")?;
+        assert_eq!(module.len(), SIZE_LEN + NONCE_LEN + 5);
+        assert_eq!(ctr_decrypt(&key, &module)?, b"hello");
+        Ok(())
+    }
+}
@@ -0,0 +1,563 @@
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+
+use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
+use parquet_format_async_temp::{
+    ColumnCryptoMetaData, ColumnMetaData, FileCryptoMetaData, FileMetaData as TFileMetaData,
+};
+
+use crate::error::{ParquetError, Result};
+
+use super::ciphers::{
+    ctr_decrypt, gcm_decrypt, gcm_encrypt_raw, tags_eq, NONCE_LEN, SIZE_LEN, TAG_LEN,
+};
+use super::{
+    footer_aad, module_aad, AlgorithmParameters, EncryptionAlgorithm, FileDecryptionProperties,
+    ModuleType,
+};
+
+/// Decrypts the modules of an encrypted column chunk.
+#[derive(Clone)]
+pub(crate) struct ColumnDecryptor {
+    key: Vec<u8>,
+    file_aad: Arc<Vec<u8>>,
+    algorithm: EncryptionAlgorithm,
+    row_group: usize,
+    column: usize,
+}
+
+impl std::fmt::Debug for ColumnDecryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the key is never printed
+        f.debug_struct("ColumnDecryptor")
+            .field("algorithm", &self.algorithm)
+            .field("row_group", &self.row_group)
+            .field("column", &self.column)
+            .finish()
+    }
+}
+
+impl ColumnDecryptor {
+    fn decrypt_gcm(
+        &self,
+        module_type: ModuleType,
+        page: Option<usize>,
+        module: &[u8],
+    ) -> Result<Vec<u8>> {
+        let aad = module_aad(
+            &self.file_aad,
+            module_type,
+            self.row_group,
+            self.column,
+            page,
+        )?;
+        gcm_decrypt(&self.key, &aad, module)
+    }
+
+    /// Decrypts a data or dictionary page, whose cipher depends on the algorithm.
+    fn decrypt_page(
+        &self,
+        module_type: ModuleType,
+        page: Option<usize>,
+        module: &[u8],
+    ) -> Result<Vec<u8>> {
+        match self.algorithm {
+            EncryptionAlgorithm::AesGcmV1 => self.decrypt_gcm(module_type, page, module),
+            EncryptionAlgorithm::AesGcmCtrV1 => ctr_decrypt(&self.key, module),
+        }
+    }
+}
+
+/// Decrypts the sequence of page headers and pages of an encrypted column chunk.
+#[derive(Debug, Clone)]
+pub(crate) struct PageDecryptor {
+    column: Arc<ColumnDecryptor>,
+    // whether the next page is the dictionary page
+    dictionary: bool,
+    // the ordinal of the next data page
+    page: usize,
+}
+
+impl PageDecryptor {
+    /// Returns a new [`PageDecryptor`]. `has_dictionary` declares whether the column chunk
+    /// starts with a dictionary page.
+    pub fn new(column: Arc<ColumnDecryptor>, has_dictionary: bool) -> Self {
+        Self {
+            column,
+            dictionary: has_dictionary,
+            page: 0,
+        }
+    }
+
+    /// Decrypts the header of the next page.
+    pub fn decrypt_header(&self, module: &[u8]) -> Result<Vec<u8>> {
+        if self.dictionary {
+            self.column
+                .decrypt_gcm(ModuleType::DictionaryPageHeader, None, module)
+        } else {
+            self.column
+                .decrypt_gcm(ModuleType::DataPageHeader, Some(self.page), module)
+        }
+    }
+
+    /// Decrypts the next page.
+    pub fn decrypt_page(&self, module: &[u8]) -> Result<Vec<u8>> {
+        if self.dictionary {
+            self.column
+                .decrypt_page(ModuleType::DictionaryPage, None, module)
+        } else {
+            self.column
+                .decrypt_page(ModuleType::DataPage, Some(self.page), module)
+        }
+    }
+
+    /// Moves to the next page
+    pub fn advance(&mut self) {
+        if self.dictionary {
+            self.dictionary = false
+        } else {
+            self.page += 1
+        }
+    }
+}
+
+/// Reads an encrypted module (`length | nonce | ciphertext [| tag]`) from `reader`.
+pub(crate) fn read_module<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut length = [0u8; SIZE_LEN];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length) as usize;
+
+    let mut module = Vec::with_capacity(SIZE_LEN + length);
+    module.extend_from_slice(&(length as u32).to_le_bytes());
+    module.resize(SIZE_LEN + length, 0);
+    reader.read_exact(&mut module[SIZE_LEN..])?;
+    Ok(module)
+}
+
+fn missing_key(what: &str) -> ParquetError {
+    general_err!(
+        "The key of {} was neither declared nor retrievable from its key metadata",
+        what
+    )
+}
+
+/// Returns the key declared in `properties` or retrieved from `key_metadata`.
+fn resolve_key(
+    declared: Option<&Vec<u8>>,
+    key_metadata: Option<&[u8]>,
+    properties: &FileDecryptionProperties,
+) -> Result<Option<Vec<u8>>> {
+    if let Some(key) = declared {
+        return Ok(Some(key.clone()));
+    }
+    match (&properties.key_retriever, key_metadata) {
+        (Some(retriever), Some(key_metadata)) => retriever.retrieve_key(key_metadata).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn file_aad(
+    parameters: &AlgorithmParameters,
+    properties: &FileDecryptionProperties,
+) -> Result<Vec<u8>> {
+    let aad_prefix = match (properties.aad_prefix.as_deref(), parameters.aad_prefix) {
+        (Some(prefix), Some(stored)) if prefix != stored => {
+            return Err(general_err!(
+                "The AAD prefix declared in the decryption properties differs from the one stored in the file"
+            ))
+        }
+        (Some(prefix), _) => prefix,
+        (None, Some(prefix)) => prefix,
+        (None, None) if parameters.supply_aad_prefix => {
+            return Err(general_err!(
+            "This file requires an AAD prefix that was not declared in the decryption properties"
+        ))
+        }
+        (None, None) => &[],
+    };
+    let mut aad = aad_prefix.to_vec();
+    aad.extend_from_slice(parameters.aad_file_unique);
+    Ok(aad)
+}
+
+fn deserialize_file_metadata(buffer: &[u8]) -> Result<TFileMetaData> {
+    let mut prot = TCompactInputProtocol::new(buffer);
+    TFileMetaData::read_from_in_protocol(&mut prot)
+        .map_err(|e| ParquetError::General(format!("Could not parse metadata: {}", e)))
+}
+
+/// Verifies the signature (`nonce | tag`) of a plaintext footer
+fn verify_signature(footer: &[u8], signature: &[u8], key: &[u8], file_aad: &[u8]) -> Result<()> {
+    if signature.len() != NONCE_LEN + TAG_LEN {
+        return Err(ParquetError::OutOfSpec(
+            "The plaintext footer of an encrypted file must be followed by its signature"
+                .to_string(),
+        ));
+    }
+    let (nonce, tag) = signature.split_at(NONCE_LEN);
+    let encrypted = gcm_encrypt_raw(key, nonce, &footer_aad(file_aad), footer)?;
+    if !tags_eq(&encrypted[encrypted.len() - TAG_LEN..], tag) {
+        return Err(general_err!(
+            "The signature of the plaintext footer does not match its content: either the footer key is wrong or the footer was tampered with"
+        ));
+    }
+    Ok(())
+}
+
+/// Deserializes the metadata of a parquet file from its footer `buffer` (without its length and
+/// magic), decrypting it and the metadata of its encrypted columns with `properties`.
+/// `encrypted_footer` declares whether the footer is encrypted (magic `PARE`) or plaintext.
+///
+/// Returns the metadata and, for every column chunk of every row group, its decryptor,
+/// if the column chunk is encrypted. The decryptor of a column chunk whose key is neither
+/// declared nor retrievable is `None`: its metadata is kept as is (i.e. without statistics in
+/// plaintext footers and absent in encrypted footers), and reading its pages errors.
+/// # Error
+/// Errors iff the footer cannot be decrypted or verified, the AAD prefix is missing or
+/// differs from the one stored in the file, or the [`KeyRetriever`] errors.
+///
+/// [`KeyRetriever`]: super::KeyRetriever
+#[allow(clippy::type_complexity)]
+pub(crate) fn decrypt_metadata(
+    buffer: &[u8],
+    encrypted_footer: bool,
+    properties: &FileDecryptionProperties,
+) -> Result<(TFileMetaData, Vec<Vec<Option<Arc<ColumnDecryptor>>>>)> {
+    let mut reader = Cursor::new(buffer);
+
+    let (mut metadata, algorithm, footer_key, file_aad) = if encrypted_footer {
+        let mut prot = TCompactInputProtocol::new(&mut reader);
+        let crypto_metadata = FileCryptoMetaData::read_from_in_protocol(&mut prot)?;
+        let module = &buffer[reader.position() as usize..];
+
+        let parameters = AlgorithmParameters::from(&crypto_metadata.encryption_algorithm);
+        let file_aad = file_aad(&parameters, properties)?;
+        let footer_key = resolve_key(
+            properties.footer_key.as_ref(),
+            crypto_metadata.key_metadata.as_deref(),
+            properties,
+        )?
+        .ok_or_else(|| missing_key("the footer"))?;
+
+        let footer = gcm_decrypt(&footer_key, &footer_aad(&file_aad), module)?;
+        let metadata = deserialize_file_metadata(&footer)?;
+        (
+            metadata,
+            crypto_metadata.encryption_algorithm,
+            Some(footer_key),
+            file_aad,
+        )
+    } else {
+        let metadata = {
+            let mut prot = TCompactInputProtocol::new(&mut reader);
+            TFileMetaData::read_from_in_protocol(&mut prot)
+                .map_err(|e| ParquetError::General(format!("Could not parse metadata: {}", e)))?
+        };
+        let algorithm = if let Some(algorithm) = metadata.encryption_algorithm.clone() {
+            algorithm
+        } else {
+            // not encrypted
+            let decryptors = metadata
+                .row_groups
+                .iter()
+                .map(|row_group| vec![None; row_group.columns.len()])
+                .collect();
+            return Ok((metadata, decryptors));
+        };
+
+        let parameters = AlgorithmParameters::from(&algorithm);
+        let file_aad = file_aad(&parameters, properties)?;
+        let footer_key = resolve_key(
+            properties.footer_key.as_ref(),
+            metadata.footer_signing_key_metadata.as_deref(),
+            properties,
+        )?;
+
+        if properties.verify_plaintext_footer {
+            let footer_key = footer_key
+                .as_ref()
+                .ok_or_else(|| missing_key("the footer"))?;
+            let (footer, signature) = buffer.split_at(reader.position() as usize);
+            verify_signature(footer, signature, footer_key, &file_aad)?;
+        }
+        (metadata, algorithm, footer_key, file_aad)
+    };
+
+    let algorithm = AlgorithmParameters::from(&algorithm).algorithm;
+    let file_aad = Arc::new(file_aad);
+
+    let decryptors = metadata
+        .row_groups
+        .iter_mut()
+        .enumerate()
+        .map(|(row_group_index, row_group)| {
+            let row_group_ordinal = row_group
+                .ordinal
+                .map(|x| x as usize)
+                .unwrap_or(row_group_index);
+            row_group
+                .columns
+                .iter_mut()
+                .enumerate()
+                .map(|(column, column_chunk)| {
+                    let key = match &column_chunk.crypto_metadata {
+                        None => return Ok(None),
+                        Some(ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_)) => {
+                            footer_key.clone()
+                        }
+                        Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(crypto)) => resolve_key(
+                            properties.column_keys.get(&crypto.path_in_schema.join(".")),
+                            crypto.key_metadata.as_deref(),
+                            properties,
+                        )?,
+                    };
+                    // a missing key only makes the column unreadable: reading its pages errors
+                    let key = match key {
+                        Some(key) => key,
+                        None => return Ok(None),
+                    };
+                    let decryptor = ColumnDecryptor {
+                        key,
+                        file_aad: file_aad.clone(),
+                        algorithm,
+                        row_group: row_group_ordinal,
+                        column,
+                    };
+
+                    if let Some(encrypted) = column_chunk.encrypted_column_metadata.as_ref() {
+                        let metadata =
+                            decryptor.decrypt_gcm(ModuleType::ColumnMetaData, None, encrypted)?;
+                        let mut prot = TCompactInputProtocol::new(metadata.as_slice());
+                        column_chunk.meta_data =
+                            Some(ColumnMetaData::read_from_in_protocol(&mut prot)?);
+                    }
+                    Ok(Some(Arc::new(decryptor)))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((metadata, decryptors))
+}
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use parquet_format_async_temp::thrift::protocol::{TCompactOutputProtocol, TOutputProtocol};
+    use parquet_format_async_temp::{
+        AesGcmV1, ColumnChunk, EncryptionAlgorithm as TEncryptionAlgorithm,
+        EncryptionWithColumnKey, RowGroup,
+    };
+
+    use super::*;
+    use crate::metadata::SchemaDescriptor;
+
+    const FOOTER_KEY: &[u8] = b"0123456789012345";
+    const COLUMN_KEY: &[u8] = b"1234567890123450";
+
+    fn serialize<F: FnOnce(&mut TCompactOutputProtocol<&mut Vec<u8>>) -> usize>(f: F) -> Vec<u8> {
+        let mut buffer = vec![];
+        let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+        f(&mut protocol);
+        protocol.flush().unwrap();
+        buffer
+    }
+
+    fn encrypt(key: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let nonce = [7u8; NONCE_LEN];
+        let ciphertext = gcm_encrypt_raw(key, &nonce, aad, plaintext).unwrap();
+        let mut module = ((NONCE_LEN + ciphertext.len()) as u32)
+            .to_le_bytes()
+            .to_vec();
+        module.extend_from_slice(&nonce);
+        module.extend_from_slice(&ciphertext);
+        module
+    }
+
+    fn algorithm() -> TEncryptionAlgorithm {
+        TEncryptionAlgorithm::AESGCMV1(AesGcmV1::new(None, b"unique".to_vec(), None))
+    }
+
+    /// metadata of a file with one row group of one column encrypted with `COLUMN_KEY`
+    fn metadata(plaintext_footer: bool) -> TFileMetaData {
+        let schema = SchemaDescriptor::try_from_message("message schema { REQUIRED INT32 a; }")
+            .unwrap()
+            .into_thrift()
+            .unwrap();
+
+        let column_metadata = ColumnMetaData::new(
+            parquet_format_async_temp::Type::INT32,
+            vec![],
+            vec!["a".to_string()],
+            parquet_format_async_temp::CompressionCodec::UNCOMPRESSED,
+            1,
+            10,
+            10,
+            None,
+            4,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let aad = module_aad(b"unique", ModuleType::ColumnMetaData, 0, 0, None).unwrap();
+        let encrypted = encrypt(
+            COLUMN_KEY,
+            &aad,
+            &serialize(|p| column_metadata.write_to_out_protocol(p).unwrap()),
+        );
+
+        let column_chunk = ColumnChunk {
+            file_path: None,
+            file_offset: 14,
+            meta_data: None,
+            offset_index_offset: None,
+            offset_index_length: None,
+            column_index_offset: None,
+            column_index_length: None,
+            crypto_metadata: Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(
+                EncryptionWithColumnKey::new(vec!["a".to_string()], b"column".to_vec()),
+            )),
+            encrypted_column_metadata: Some(encrypted),
+        };
+        let row_group = RowGroup::new(vec![column_chunk], 10, 1, None, None, None, None);
+
+        let (algorithm, key_metadata) = if plaintext_footer {
+            (Some(algorithm()), Some(b"footer".to_vec()))
+        } else {
+            (None, None)
+        };
+        TFileMetaData::new(
+            1,
+            schema,
+            1,
+            vec![row_group],
+            None,
+            None,
+            None,
+            algorithm,
+            key_metadata,
+        )
+    }
+
+    struct Retriever;
+
+    impl crate::encryption::KeyRetriever for Retriever {
+        fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+            match key_metadata {
+                b"footer" => Ok(FOOTER_KEY.to_vec()),
+                b"column" => Ok(COLUMN_KEY.to_vec()),
+                _ => Err(general_err!("unknown key")),
+            }
+        }
+    }
+
+    /// A retriever that is unable to retrieve any key
+    struct Failing;
+
+    impl crate::encryption::KeyRetriever for Failing {
+        fn retrieve_key(&self, _: &[u8]) -> Result<Vec<u8>> {
+            Err(general_err!("the KMS is unavailable"))
+        }
+    }
+
+    fn check(metadata: &TFileMetaData, decryptors: &[Vec<Option<Arc<ColumnDecryptor>>>]) {
+        let column = &metadata.row_groups[0].columns[0];
+        assert_eq!(column.meta_data.as_ref().unwrap().total_compressed_size, 10);
+        let decryptor = decryptors[0][0].as_ref().unwrap();
+        assert_eq!(decryptor.key, COLUMN_KEY);
+        assert_eq!(decryptor.algorithm, EncryptionAlgorithm::AesGcmV1);
+    }
+
+    #[test]
+    fn encrypted_footer() -> Result<()> {
+        let footer = serialize(|p| metadata(false).write_to_out_protocol(p).unwrap());
+        let footer = encrypt(FOOTER_KEY, &footer_aad(b"unique"), &footer);
+
+        let mut buffer = serialize(|p| {
+            FileCryptoMetaData::new(algorithm(), b"footer".to_vec())
+                .write_to_out_protocol(p)
+                .unwrap()
+        });
+        buffer.extend_from_slice(&footer);
+
+        // with a key retriever
+        let properties = FileDecryptionProperties::with_key_retriever(Arc::new(Retriever));
+        let (metadata, decryptors) = decrypt_metadata(&buffer, true, &properties)?;
+        check(&metadata, &decryptors);
+
+        // with explicit keys
+        let mut properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        properties
+            .column_keys
+            .insert("a".to_string(), COLUMN_KEY.to_vec());
+        let (metadata, decryptors) = decrypt_metadata(&buffer, true, &properties)?;
+        check(&metadata, &decryptors);
+
+        // a wrong footer key
+        let properties = FileDecryptionProperties::new(COLUMN_KEY.to_vec());
+        assert!(decrypt_metadata(&buffer, true, &properties).is_err());
+
+        // a column whose key is missing has no decryptor nor metadata
+        let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        let (metadata, decryptors) = decrypt_metadata(&buffer, true, &properties)?;
+        assert!(metadata.row_groups[0].columns[0].meta_data.is_none());
+        assert!(decryptors[0][0].is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn aad_prefix_mismatch() -> Result<()> {
+        let algorithm = TEncryptionAlgorithm::AESGCMV1(AesGcmV1::new(
+            b"prefix".to_vec(),
+            b"unique".to_vec(),
+            None,
+        ));
+        let parameters = AlgorithmParameters::from(&algorithm);
+
+        let mut properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        assert_eq!(file_aad(&parameters, &properties)?, b"prefixunique");
+        properties.aad_prefix = Some(b"prefix".to_vec());
+        assert_eq!(file_aad(&parameters, &properties)?, b"prefixunique");
+        properties.aad_prefix = Some(b"other".to_vec());
+        assert!(file_aad(&parameters, &properties).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn plaintext_footer() -> Result<()> {
+        let footer = serialize(|p| metadata(true).write_to_out_protocol(p).unwrap());
+
+        let nonce = [9u8; NONCE_LEN];
+        let signed = gcm_encrypt_raw(FOOTER_KEY, &nonce, &footer_aad(b"unique"), &footer)?;
+        let mut buffer = footer.clone();
+        buffer.extend_from_slice(&nonce);
+        buffer.extend_from_slice(&signed[signed.len() - TAG_LEN..]);
+
+        let properties = FileDecryptionProperties::with_key_retriever(Arc::new(Retriever));
+        let (metadata, decryptors) = decrypt_metadata(&buffer, false, &properties)?;
+        check(&metadata, &decryptors);
+
+        // a column whose key is unknown has no decryptor
+        let no_column_key = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        let (_, decryptors) = decrypt_metadata(&buffer, false, &no_column_key)?;
+        assert!(decryptors[0][0].is_none());
+
+        // but errors retrieving it are not ignored
+        let mut failing = FileDecryptionProperties::with_key_retriever(Arc::new(Failing));
+        failing.footer_key = Some(FOOTER_KEY.to_vec());
+        assert!(decrypt_metadata(&buffer, false, &failing).is_err());
+
+        // a tampered footer fails verification...
+        let mut tampered = buffer.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(decrypt_metadata(&tampered, false, &properties).is_err());
+
+        // ...unless verification is disabled
+        let mut properties = properties;
+        properties.verify_plaintext_footer = false;
+        assert!(decrypt_metadata(&tampered, false, &properties).is_ok());
+        Ok(())
+    }
+}
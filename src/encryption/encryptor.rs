@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use parquet_format_async_temp::thrift::protocol::{TCompactOutputProtocol, TOutputProtocol};
+use parquet_format_async_temp::{
+    AesGcmCtrV1, AesGcmV1, ColumnChunk, ColumnCryptoMetaData,
+    EncryptionAlgorithm as TEncryptionAlgorithm, EncryptionWithColumnKey, EncryptionWithFooterKey,
+    FileCryptoMetaData, FileMetaData as TFileMetaData,
+};
+
+use crate::error::{ParquetError, Result};
+use crate::metadata::{ColumnDescriptor, SchemaDescriptor};
+use crate::{PARQUET_MAGIC, PARQUET_MAGIC_ENCRYPTED};
+
+use super::ciphers::{ctr_encrypt, gcm_encrypt, gcm_encrypt_raw, random_bytes, NONCE_LEN, TAG_LEN};
+use super::{footer_aad, module_aad, EncryptionAlgorithm, FileEncryptionProperties, ModuleType};
+
+/// The number of random bytes of the AAD that is unique to every file
+const AAD_FILE_UNIQUE_LEN: usize = 8;
+
+fn validate_key(key: &[u8], what: &str) -> Result<()> {
+    if ![16, 24, 32].contains(&key.len()) {
+        return Err(general_err!(
+            "The key of {} must have 16, 24 or 32 bytes, but it has {}",
+            what,
+            key.len()
+        ));
+    }
+    Ok(())
+}
+
+fn serialize<F>(f: F) -> Result<Vec<u8>>
+where
+    F: FnOnce(&mut TCompactOutputProtocol<&mut Vec<u8>>) -> Result<usize>,
+{
+    let mut buffer = vec![];
+    let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+    f(&mut protocol)?;
+    protocol.flush()?;
+    Ok(buffer)
+}
+
+/// Encrypts the modules of a parquet file, as declared by its [`FileEncryptionProperties`].
+#[derive(Debug)]
+pub(crate) struct FileEncryptor {
+    properties: FileEncryptionProperties,
+    algorithm: TEncryptionAlgorithm,
+    file_aad: Arc<Vec<u8>>,
+}
+
+impl FileEncryptor {
+    /// Returns a new [`FileEncryptor`] of a file with `schema`.
+    /// # Error
+    /// Errors iff a key is invalid or a column key is declared for a column not in `schema`.
+    pub fn try_new(
+        properties: FileEncryptionProperties,
+        schema: &SchemaDescriptor,
+    ) -> Result<Self> {
+        validate_key(&properties.footer_key, "the footer")?;
+        let paths = schema
+            .columns()
+            .iter()
+            .map(|column| column.path_in_schema().join("."))
+            .collect::<HashSet<_>>();
+        for (path, key) in properties.column_keys.iter() {
+            if !paths.contains(path) {
+                return Err(general_err!(
+                    "The column \"{}\" declared in the encryption properties does not exist",
+                    path
+                ));
+            }
+            validate_key(&key.key, &format!("the column \"{}\"", path))?;
+        }
+
+        let aad_file_unique = random_bytes(AAD_FILE_UNIQUE_LEN)?;
+        let mut file_aad = properties.aad_prefix.clone().unwrap_or_default();
+        file_aad.extend_from_slice(&aad_file_unique);
+
+        let aad_prefix = properties
+            .aad_prefix
+            .clone()
+            .filter(|_| properties.store_aad_prefix);
+        let supply_aad_prefix = if properties.aad_prefix.is_some() && !properties.store_aad_prefix {
+            Some(true)
+        } else {
+            None
+        };
+        let algorithm = match properties.algorithm {
+            EncryptionAlgorithm::AesGcmV1 => TEncryptionAlgorithm::AESGCMV1(AesGcmV1::new(
+                aad_prefix,
+                aad_file_unique,
+                supply_aad_prefix,
+            )),
+            EncryptionAlgorithm::AesGcmCtrV1 => TEncryptionAlgorithm::AESGCMCTRV1(
+                AesGcmCtrV1::new(aad_prefix, aad_file_unique, supply_aad_prefix),
+            ),
+        };
+
+        Ok(Self {
+            properties,
+            algorithm,
+            file_aad: Arc::new(file_aad),
+        })
+    }
+
+    /// The magic of the file: `PARE` when the footer is encrypted, `PAR1` otherwise
+    pub fn magic(&self) -> [u8; 4] {
+        if self.properties.plaintext_footer {
+            PARQUET_MAGIC
+        } else {
+            PARQUET_MAGIC_ENCRYPTED
+        }
+    }
+
+    /// Returns the [`ColumnEncryptor`] of `column` (the column at `index`) in the row group
+    /// `row_group`, or `None` if the column is not encrypted.
+    pub fn column(
+        &self,
+        row_group: usize,
+        index: usize,
+        column: &ColumnDescriptor,
+    ) -> Result<Option<ColumnEncryptor>> {
+        let (key, crypto_metadata) = if self.properties.column_keys.is_empty() {
+            (
+                self.properties.footer_key.clone(),
+                ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(EncryptionWithFooterKey {}),
+            )
+        } else {
+            let path = column.path_in_schema();
+            match self.properties.column_keys.get(&path.join(".")) {
+                Some(key) => (
+                    key.key.clone(),
+                    ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(EncryptionWithColumnKey::new(
+                        path.to_vec(),
+                        key.key_metadata.clone(),
+                    )),
+                ),
+                None => return Ok(None),
+            }
+        };
+        // validate the ordinals, that are part of every AAD
+        module_aad(
+            &self.file_aad,
+            ModuleType::ColumnMetaData,
+            row_group,
+            index,
+            None,
+        )?;
+
+        Ok(Some(ColumnEncryptor {
+            key,
+            file_aad: self.file_aad.clone(),
+            algorithm: self.properties.algorithm,
+            row_group,
+            column: index,
+            crypto_metadata,
+            plaintext_footer: self.properties.plaintext_footer,
+            page: 0,
+        }))
+    }
+
+    /// Returns the footer of the file, i.e. everything between the last row group and the
+    /// footer length.
+    ///
+    /// The footer is either `FileCryptoMetaData` followed by the encrypted `metadata`, or
+    /// the plaintext `metadata` followed by its signature (`nonce | tag`).
+    pub fn footer(&self, mut metadata: TFileMetaData) -> Result<Vec<u8>> {
+        let key = &self.properties.footer_key;
+        let aad = footer_aad(&self.file_aad);
+        if self.properties.plaintext_footer {
+            metadata.encryption_algorithm = Some(self.algorithm.clone());
+            metadata.footer_signing_key_metadata = self.properties.footer_key_metadata.clone();
+            let mut footer = serialize(|p| Ok(metadata.write_to_out_protocol(p)?))?;
+
+            // SPEC: the signature is the nonce and the tag of the footer encrypted with AES GCM
+            let nonce = random_bytes(NONCE_LEN)?;
+            let encrypted = gcm_encrypt_raw(key, &nonce, &aad, &footer)?;
+            footer.extend_from_slice(&nonce);
+            footer.extend_from_slice(&encrypted[encrypted.len() - TAG_LEN..]);
+            Ok(footer)
+        } else {
+            let crypto_metadata = FileCryptoMetaData::new(
+                self.algorithm.clone(),
+                self.properties.footer_key_metadata.clone(),
+            );
+            let mut footer = serialize(|p| Ok(crypto_metadata.write_to_out_protocol(p)?))?;
+            let metadata = serialize(|p| Ok(metadata.write_to_out_protocol(p)?))?;
+            footer.extend_from_slice(&gcm_encrypt(key, &aad, &metadata)?);
+            Ok(footer)
+        }
+    }
+}
+
+/// Encrypts the modules of an encrypted column chunk.
+pub(crate) struct ColumnEncryptor {
+    key: Vec<u8>,
+    file_aad: Arc<Vec<u8>>,
+    algorithm: EncryptionAlgorithm,
+    row_group: usize,
+    column: usize,
+    crypto_metadata: ColumnCryptoMetaData,
+    plaintext_footer: bool,
+    // the ordinal of the next data page
+    page: usize,
+}
+
+impl std::fmt::Debug for ColumnEncryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the key is never printed
+        f.debug_struct("ColumnEncryptor")
+            .field("algorithm", &self.algorithm)
+            .field("row_group", &self.row_group)
+            .field("column", &self.column)
+            .field("page", &self.page)
+            .finish()
+    }
+}
+
+impl ColumnEncryptor {
+    fn encrypt_gcm(
+        &self,
+        module_type: ModuleType,
+        page: Option<usize>,
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        let aad = module_aad(
+            &self.file_aad,
+            module_type,
+            self.row_group,
+            self.column,
+            page,
+        )?;
+        gcm_encrypt(&self.key, &aad, plaintext)
+    }
+
+    /// Encrypts the next page, which is a dictionary page iff `dictionary`.
+    pub fn encrypt_page(&self, dictionary: bool, page: &[u8]) -> Result<Vec<u8>> {
+        match (self.algorithm, dictionary) {
+            (EncryptionAlgorithm::AesGcmCtrV1, _) => ctr_encrypt(&self.key, page),
+            (EncryptionAlgorithm::AesGcmV1, true) => {
+                self.encrypt_gcm(ModuleType::DictionaryPage, None, page)
+            }
+            (EncryptionAlgorithm::AesGcmV1, false) => {
+                self.encrypt_gcm(ModuleType::DataPage, Some(self.page), page)
+            }
+        }
+    }
+
+    /// Encrypts the (serialized) header of the next page, which is a dictionary page
+    /// iff `dictionary`.
+    pub fn encrypt_page_header(&self, dictionary: bool, header: &[u8]) -> Result<Vec<u8>> {
+        if dictionary {
+            self.encrypt_gcm(ModuleType::DictionaryPageHeader, None, header)
+        } else {
+            self.encrypt_gcm(ModuleType::DataPageHeader, Some(self.page), header)
+        }
+    }
+
+    /// Moves past the current page, which is a dictionary page iff `dictionary`.
+    pub fn advance(&mut self, dictionary: bool) {
+        if !dictionary {
+            self.page += 1
+        }
+    }
+
+    /// Encrypts the metadata of `column_chunk`, declaring how it is encrypted.
+    ///
+    /// The metadata is kept in plaintext in columns encrypted with the footer key of files
+    /// with an encrypted footer, since the footer itself is encrypted.
+    /// In plaintext footers, the metadata is kept without its statistics, so that readers
+    /// without keys can still locate the column chunk.
+    pub fn encrypt_metadata(&self, column_chunk: &mut ColumnChunk) -> Result<()> {
+        column_chunk.crypto_metadata = Some(self.crypto_metadata.clone());
+        let footer_key = matches!(
+            self.crypto_metadata,
+            ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_)
+        );
+        if footer_key && !self.plaintext_footer {
+            return Ok(());
+        }
+
+        let metadata = column_chunk.meta_data.as_mut().ok_or_else(|| {
+            ParquetError::OutOfSpec("A column chunk must have metadata".to_string())
+        })?;
+        let encrypted = serialize(|p| Ok(metadata.write_to_out_protocol(p)?))?;
+        column_chunk.encrypted_column_metadata =
+            Some(self.encrypt_gcm(ModuleType::ColumnMetaData, None, &encrypted)?);
+
+        if self.plaintext_footer {
+            metadata.statistics = None;
+        } else {
+            column_chunk.meta_data = None;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! Parquet modular encryption.
+//!
+//! Encrypted parquet files encrypt their footer (or sign it, in plaintext footer mode), and
+//! the metadata, page headers and pages of encrypted columns, each with the footer key or with
+//! a column-specific key. See
+//! [the spec](https://github.com/apache/parquet-format/blob/master/Encryption.md).
+//!
+//! Files are decrypted by reading their metadata with
+//! [`crate::read::read_metadata_with_decryption`]; the pages of the resulting column chunks
+//! are then decrypted transparently when read.
+//!
+//! Files are encrypted by declaring [`FileEncryptionProperties`] in
+//! [`crate::write::FileWriter::set_encryption`].
+//!
+//! The ciphers require the feature `encryption`, which is not active by default.
+//!
+//! Columns whose key is unavailable can still be located, but reading their pages, page
+//! indexes or bloom filters errors. The page indexes and bloom filters of encrypted columns are
+//! encrypted, and reading them is not supported.
+mod ciphers;
+mod decryptor;
+mod encryptor;
+mod properties;
+
+use std::convert::TryInto;
+
+use parquet_format_async_temp::EncryptionAlgorithm as TEncryptionAlgorithm;
+
+use crate::error::{ParquetError, Result};
+
+pub(crate) use decryptor::{decrypt_metadata, read_module, ColumnDecryptor, PageDecryptor};
+pub(crate) use encryptor::{ColumnEncryptor, FileEncryptor};
+pub use properties::{
+    ColumnEncryptionKey, FileDecryptionProperties, FileEncryptionProperties, KeyRetriever,
+};
+
+/// The encryption algorithms of parquet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncryptionAlgorithm {
+    /// Every module is encrypted with AES GCM
+    AesGcmV1,
+    /// Pages are encrypted with AES CTR and every other module with AES GCM
+    AesGcmCtrV1,
+}
+
+/// The parameters of the encryption algorithm of a file, as declared in it
+struct AlgorithmParameters<'a> {
+    algorithm: EncryptionAlgorithm,
+    aad_prefix: Option<&'a [u8]>,
+    aad_file_unique: &'a [u8],
+    supply_aad_prefix: bool,
+}
+
+impl<'a> From<&'a TEncryptionAlgorithm> for AlgorithmParameters<'a> {
+    fn from(algorithm: &'a TEncryptionAlgorithm) -> Self {
+        let (algorithm, aad_prefix, aad_file_unique, supply_aad_prefix) = match algorithm {
+            TEncryptionAlgorithm::AESGCMV1(a) => (
+                EncryptionAlgorithm::AesGcmV1,
+                &a.aad_prefix,
+                &a.aad_file_unique,
+                a.supply_aad_prefix,
+            ),
+            TEncryptionAlgorithm::AESGCMCTRV1(a) => (
+                EncryptionAlgorithm::AesGcmCtrV1,
+                &a.aad_prefix,
+                &a.aad_file_unique,
+                a.supply_aad_prefix,
+            ),
+        };
+        Self {
+            algorithm,
+            aad_prefix: aad_prefix.as_deref(),
+            aad_file_unique: aad_file_unique.as_deref().unwrap_or(&[]),
+            supply_aad_prefix: supply_aad_prefix.unwrap_or(false),
+        }
+    }
+}
+
+/// The modules of a parquet file, whose type is part of their AAD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ModuleType {
+    Footer = 0,
+    ColumnMetaData = 1,
+    DataPage = 2,
+    DictionaryPage = 3,
+    DataPageHeader = 4,
+    DictionaryPageHeader = 5,
+}
+
+fn ordinal(ordinal: usize, name: &str) -> Result<[u8; 2]> {
+    let ordinal: i16 = ordinal.try_into().map_err(|_| {
+        ParquetError::OutOfSpec(format!(
+            "Encrypted files support at most {} {}, but this one requires {}",
+            i16::MAX as usize + 1,
+            name,
+            ordinal + 1
+        ))
+    })?;
+    Ok(ordinal.to_le_bytes())
+}
+
+/// Returns the AAD of the footer
+pub(crate) fn footer_aad(file_aad: &[u8]) -> Vec<u8> {
+    let mut aad = file_aad.to_vec();
+    aad.push(ModuleType::Footer as u8);
+    aad
+}
+
+/// Returns the AAD of a module of the column chunk of `column` in `row_group`.
+/// `page` is the ordinal of the (data) page within the column chunk, and
+/// is only used by data pages and their headers.
+pub(crate) fn module_aad(
+    file_aad: &[u8],
+    module_type: ModuleType,
+    row_group: usize,
+    column: usize,
+    page: Option<usize>,
+) -> Result<Vec<u8>> {
+    let mut aad = file_aad.to_vec();
+    aad.push(module_type as u8);
+    aad.extend_from_slice(&ordinal(row_group, "row groups")?);
+    aad.extend_from_slice(&ordinal(column, "columns")?);
+    if let Some(page) = page {
+        aad.extend_from_slice(&ordinal(page, "pages per column chunk")?);
+    }
+    Ok(aad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aad() -> Result<()> {
+        assert_eq!(footer_aad(b"file"), b"file\x00");
+        assert_eq!(
+            module_aad(b"file", ModuleType::ColumnMetaData, 1, 2, None)?,
+            b"file\x01\x01\x00\x02\x00"
+        );
+        assert_eq!(
+            module_aad(b"file", ModuleType::DataPage, 1, 2, Some(258))?,
+            b"file\x02\x01\x00\x02\x00\x02\x01"
+        );
+        assert!(module_aad(b"file", ModuleType::DataPage, 0, 0, Some(1 << 15)).is_err());
+        Ok(())
+    }
+}
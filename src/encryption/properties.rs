@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::Result;
+
+use super::EncryptionAlgorithm;
+
+/// A trait to retrieve keys from the key metadata stored in parquet files (e.g. from a KMS).
+pub trait KeyRetriever: Send + Sync {
+    /// Returns the key identified by `key_metadata`.
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// The properties used to decrypt a parquet file.
+///
+/// Keys are resolved in order: the explicit key (`footer_key` or `column_keys`), and then the
+/// [`KeyRetriever`] applied to the key metadata stored in the file.
+#[derive(Clone)]
+pub struct FileDecryptionProperties {
+    /// The key of the footer, also used to decrypt columns encrypted with the footer key
+    pub footer_key: Option<Vec<u8>>,
+    /// The keys of columns encrypted with their own key, by dot-separated column path
+    /// (e.g. `"a.b"`)
+    pub column_keys: HashMap<String, Vec<u8>>,
+    /// Retriever of keys that are not declared explicitly
+    pub key_retriever: Option<Arc<dyn KeyRetriever>>,
+    /// The AAD prefix, required when the file was written without storing it
+    pub aad_prefix: Option<Vec<u8>>,
+    /// Whether to verify the signature of plaintext footers
+    pub verify_plaintext_footer: bool,
+}
+
+impl FileDecryptionProperties {
+    /// Returns new [`FileDecryptionProperties`] with the footer key `footer_key`.
+    pub fn new(footer_key: Vec<u8>) -> Self {
+        Self {
+            footer_key: Some(footer_key),
+            ..Default::default()
+        }
+    }
+
+    /// Returns new [`FileDecryptionProperties`] whose keys are retrieved by `key_retriever`.
+    pub fn with_key_retriever(key_retriever: Arc<dyn KeyRetriever>) -> Self {
+        Self {
+            key_retriever: Some(key_retriever),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for FileDecryptionProperties {
+    fn default() -> Self {
+        Self {
+            footer_key: None,
+            column_keys: HashMap::new(),
+            key_retriever: None,
+            aad_prefix: None,
+            verify_plaintext_footer: true,
+        }
+    }
+}
+
+impl std::fmt::Debug for FileDecryptionProperties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // keys are never printed
+        f.debug_struct("FileDecryptionProperties")
+            .field("column_keys", &self.column_keys.keys().collect::<Vec<_>>())
+            .field("key_retriever", &self.key_retriever.is_some())
+            .field("aad_prefix", &self.aad_prefix)
+            .field("verify_plaintext_footer", &self.verify_plaintext_footer)
+            .finish()
+    }
+}
+
+/// The key of a column encrypted with its own key.
+#[derive(Clone)]
+pub struct ColumnEncryptionKey {
+    /// The key (of 16, 24 or 32 bytes)
+    pub key: Vec<u8>,
+    /// The metadata of the key, stored in the file so that readers can retrieve it
+    pub key_metadata: Option<Vec<u8>>,
+}
+
+impl ColumnEncryptionKey {
+    /// Returns a new [`ColumnEncryptionKey`] without key metadata.
+    pub fn new(key: Vec<u8>) -> Self {
+        Self {
+            key,
+            key_metadata: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for ColumnEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the key is never printed
+        f.debug_struct("ColumnEncryptionKey")
+            .field("key_metadata", &self.key_metadata)
+            .finish()
+    }
+}
+
+/// The properties used to encrypt a parquet file.
+///
+/// When `column_keys` is empty, every column is encrypted with the footer key. Otherwise,
+/// only the declared columns are encrypted, each with its own key, and the remaining columns
+/// are written in plaintext.
+#[derive(Clone)]
+pub struct FileEncryptionProperties {
+    /// The algorithm used to encrypt the file
+    pub algorithm: EncryptionAlgorithm,
+    /// The key of the footer (of 16, 24 or 32 bytes)
+    pub footer_key: Vec<u8>,
+    /// The metadata of the footer key, stored in the file so that readers can retrieve it
+    pub footer_key_metadata: Option<Vec<u8>>,
+    /// The keys of columns encrypted with their own key, by dot-separated column path
+    /// (e.g. `"a.b"`)
+    pub column_keys: HashMap<String, ColumnEncryptionKey>,
+    /// The AAD prefix, binding the file to e.g. its name or location
+    pub aad_prefix: Option<Vec<u8>>,
+    /// Whether to store the AAD prefix in the file. When `false`, readers must declare it.
+    pub store_aad_prefix: bool,
+    /// Whether to write the footer in plaintext (signed with the footer key), so that
+    /// readers without keys can read the plaintext columns
+    pub plaintext_footer: bool,
+}
+
+impl FileEncryptionProperties {
+    /// Returns new [`FileEncryptionProperties`] encrypting every column and the footer with
+    /// `footer_key` and [`EncryptionAlgorithm::AesGcmV1`].
+    pub fn new(footer_key: Vec<u8>) -> Self {
+        Self {
+            algorithm: EncryptionAlgorithm::AesGcmV1,
+            footer_key,
+            footer_key_metadata: None,
+            column_keys: HashMap::new(),
+            aad_prefix: None,
+            store_aad_prefix: true,
+            plaintext_footer: false,
+        }
+    }
+}
+
+impl std::fmt::Debug for FileEncryptionProperties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // keys are never printed
+        f.debug_struct("FileEncryptionProperties")
+            .field("algorithm", &self.algorithm)
+            .field("footer_key_metadata", &self.footer_key_metadata)
+            .field("column_keys", &self.column_keys)
+            .field("aad_prefix", &self.aad_prefix)
+            .field("store_aad_prefix", &self.store_aad_prefix)
+            .field("plaintext_footer", &self.plaintext_footer)
+            .finish()
+    }
+}
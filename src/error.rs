@@ -10,6 +10,7 @@ pub enum Feature {
     Gzip,
     Lz4,
     Zstd,
+    Encryption,
 }
 
 /// Errors generated by this crate
@@ -22,6 +23,9 @@ pub enum ParquetError {
     FeatureNotActive(Feature, String),
     /// When the parquet file is known to be out of spec.
     OutOfSpec(String),
+    /// When the CRC32 checksum of a page does not match its content, with the path of its
+    /// column and the offset of the page in the file.
+    InvalidChecksum(String, u64),
     // An error originating from a consumer or dependency
     External(String, Arc<dyn std::error::Error + Send + Sync>),
 }
@@ -44,6 +48,13 @@ impl std::fmt::Display for ParquetError {
             ParquetError::OutOfSpec(message) => {
                 write!(fmt, "{}", message)
             }
+            ParquetError::InvalidChecksum(column, offset) => {
+                write!(
+                    fmt,
+                    "The CRC32 checksum of the page at offset {} of column \"{}\" does not match its content",
+                    offset, column
+                )
+            }
             ParquetError::External(message, err) => {
                 write!(fmt, "{}: {}", message, err)
             }
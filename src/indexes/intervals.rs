@@ -0,0 +1,240 @@
+use std::convert::TryFrom;
+
+use parquet_format_async_temp::PageLocation;
+
+use crate::error::{ParquetError, Result};
+
+/// An interval of rows: `[start, start + length)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    /// Its start
+    pub start: usize,
+    /// Its length
+    pub length: usize,
+}
+
+impl Interval {
+    /// Create a new interval
+    pub fn new(start: usize, length: usize) -> Self {
+        Self { start, length }
+    }
+}
+
+/// A page selected for reading, with its location in the file and the rows
+/// of interest in it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FilteredPage {
+    /// Location of the page in the file
+    pub start: u64,
+    /// Length of the page in the file, including its header
+    pub length: usize,
+    /// Index of the first row of this page in the row group
+    pub first_row_index: usize,
+    /// Number of rows in this page
+    pub num_rows: usize,
+    /// The intervals of rows selected in this page, relative to its first row
+    pub selected_rows: Vec<Interval>,
+}
+
+/// Returns the first row index and the number of rows of each page described by `locations`
+/// # Error
+/// Errors iff the first row indexes of `locations` are negative, decreasing or past `num_rows`.
+fn page_rows(locations: &[PageLocation], num_rows: usize) -> Result<Vec<(usize, usize)>> {
+    let first_row_indexes = locations
+        .iter()
+        .map(|location| {
+            usize::try_from(location.first_row_index).map_err(|_| {
+                ParquetError::OutOfSpec(format!(
+                    "The first row index of a page location cannot be {}",
+                    location.first_row_index
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    first_row_indexes
+        .iter()
+        .zip(
+            first_row_indexes
+                .iter()
+                .skip(1)
+                .chain(std::iter::once(&num_rows)),
+        )
+        .map(|(start, end)| {
+            end.checked_sub(*start)
+                .map(|length| (*start, length))
+                .ok_or_else(|| {
+                    ParquetError::OutOfSpec(
+                        "The first row indexes of page locations must increase within the row group"
+                            .to_string(),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Returns the set of [`Interval`]s of rows covered by the pages for which `selected` is true,
+/// merging contiguous pages into a single interval.
+/// `num_rows` is the number of rows of the row group.
+/// # Error
+/// Errors iff `selected` and `locations` have different lengths or `locations` is out of spec.
+pub fn compute_rows(
+    selected: &[bool],
+    locations: &[PageLocation],
+    num_rows: usize,
+) -> Result<Vec<Interval>> {
+    if selected.len() != locations.len() {
+        return Err(general_err!(
+            "There must be one selection per page location ({} != {})",
+            selected.len(),
+            locations.len()
+        ));
+    }
+    let rows = page_rows(locations, num_rows)?;
+
+    let mut intervals: Vec<Interval> = vec![];
+    for (is_selected, (start, length)) in selected.iter().zip(rows) {
+        if !is_selected || length == 0 {
+            continue;
+        }
+        match intervals.last_mut() {
+            Some(last) if last.start + last.length == start => last.length += length,
+            _ => intervals.push(Interval::new(start, length)),
+        }
+    }
+    Ok(intervals)
+}
+
+/// Returns the pages of a column chunk that contain rows from `intervals`, together with the
+/// rows selected in each of them.
+/// `intervals` are rows of the row group and must be sorted and non-overlapping,
+/// `locations` is the offset index of the column chunk (see [`crate::read::read_pages_locations`])
+/// and `num_rows` is the number of rows of the row group.
+///
+/// Since pages of a column chunk always start at a new row, this works for
+/// any column, including nested ones.
+pub fn select_pages(
+    intervals: &[Interval],
+    locations: &[PageLocation],
+    num_rows: usize,
+) -> Result<Vec<FilteredPage>> {
+    let rows = page_rows(locations, num_rows)?;
+
+    let mut pages = vec![];
+    for (location, (first_row_index, length)) in locations.iter().zip(rows) {
+        let page_end = first_row_index + length;
+
+        let selected_rows = intervals
+            .iter()
+            .filter_map(|interval| {
+                let start = interval.start.max(first_row_index);
+                let end = interval.start.saturating_add(interval.length).min(page_end);
+                (start < end).then(|| Interval::new(start - first_row_index, end - start))
+            })
+            .collect::<Vec<_>>();
+
+        if selected_rows.is_empty() {
+            continue;
+        }
+
+        if location.offset < 0 || location.compressed_page_size < 0 {
+            return Err(ParquetError::OutOfSpec(
+                "The offset and size of a page location must be positive".to_string(),
+            ));
+        }
+
+        pages.push(FilteredPage {
+            start: location.offset as u64,
+            length: location.compressed_page_size as usize,
+            first_row_index,
+            num_rows: length,
+            selected_rows,
+        });
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locations() -> Vec<PageLocation> {
+        vec![
+            PageLocation::new(100, 10, 0),
+            PageLocation::new(110, 20, 5),
+            PageLocation::new(130, 30, 10),
+        ]
+    }
+
+    #[test]
+    fn rows() -> Result<()> {
+        let intervals = compute_rows(&[true, true, false], &locations(), 20)?;
+        assert_eq!(intervals, vec![Interval::new(0, 10)]);
+
+        let intervals = compute_rows(&[true, false, true], &locations(), 20)?;
+        assert_eq!(intervals, vec![Interval::new(0, 5), Interval::new(10, 10)]);
+        Ok(())
+    }
+
+    #[test]
+    fn pages() -> Result<()> {
+        let pages = select_pages(
+            &[Interval::new(2, 2), Interval::new(8, 4)],
+            &locations(),
+            20,
+        )?;
+        assert_eq!(
+            pages,
+            vec![
+                FilteredPage {
+                    start: 100,
+                    length: 10,
+                    first_row_index: 0,
+                    num_rows: 5,
+                    selected_rows: vec![Interval::new(2, 2)],
+                },
+                FilteredPage {
+                    start: 110,
+                    length: 20,
+                    first_row_index: 5,
+                    num_rows: 5,
+                    selected_rows: vec![Interval::new(3, 2)],
+                },
+                FilteredPage {
+                    start: 130,
+                    length: 30,
+                    first_row_index: 10,
+                    num_rows: 10,
+                    selected_rows: vec![Interval::new(0, 2)],
+                }
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn skip_pages() -> Result<()> {
+        let pages = select_pages(&[Interval::new(15, 10)], &locations(), 20)?;
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].start, 130);
+        assert_eq!(pages[0].selected_rows, vec![Interval::new(5, 5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_spec_locations() {
+        for locations in [
+            vec![PageLocation::new(100, 10, -1)],
+            vec![PageLocation::new(100, 10, i64::MIN)],
+            vec![PageLocation::new(100, 10, 5), PageLocation::new(110, 10, 0)],
+            vec![
+                PageLocation::new(100, 10, 0),
+                PageLocation::new(110, 10, 30),
+            ],
+        ] {
+            let selected = vec![true; locations.len()];
+            assert!(compute_rows(&selected, &locations, 20).is_err());
+            assert!(select_pages(&[Interval::new(0, 20)], &locations, 20).is_err());
+        }
+    }
+}
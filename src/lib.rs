@@ -3,8 +3,12 @@
 
 #[macro_use]
 pub mod error;
+pub mod bloom_filter;
 pub mod compression;
+pub mod deserialize;
 pub mod encoding;
+pub mod encryption;
+pub mod indexes;
 pub mod metadata;
 pub mod page;
 mod parquet_bridge;
@@ -19,6 +23,8 @@ pub use streaming_decompression::FallibleStreamingIterator;
 
 const FOOTER_SIZE: u64 = 8;
 const PARQUET_MAGIC: [u8; 4] = [b'P', b'A', b'R', b'1'];
+/// The magic of parquet files with an encrypted footer
+const PARQUET_MAGIC_ENCRYPTED: [u8; 4] = [b'P', b'A', b'R', b'E'];
 
 /// The number of bytes read at the end of the parquet file on first read
 const DEFAULT_FOOTER_READ_SIZE: u64 = 64 * 1024;
@@ -27,9 +33,29 @@ const DEFAULT_FOOTER_READ_SIZE: u64 = 64 * 1024;
 mod tests {
     use std::path::PathBuf;
 
+    use crate::metadata::{ColumnDescriptor, SchemaDescriptor};
+    use crate::write::{Version, WriteOptions};
+
     pub fn get_path() -> PathBuf {
         let dir = env!("CARGO_MANIFEST_DIR");
 
         PathBuf::from(dir).join("testing/parquet-testing/data")
     }
+
+    /// Returns the descriptor of a schema with a single `column`, e.g. `"OPTIONAL INT32 a"`
+    pub fn descriptor(column: &str) -> ColumnDescriptor {
+        SchemaDescriptor::try_from_message(&format!("message schema {{ {}; }}", column))
+            .unwrap()
+            .columns()[0]
+            .clone()
+    }
+
+    /// Returns uncompressed write options of `version` without statistics
+    pub fn options(version: Version) -> WriteOptions {
+        WriteOptions {
+            write_statistics: false,
+            version,
+            ..Default::default()
+        }
+    }
 }
@@ -4,18 +4,25 @@ use std::sync::Arc;
 use parquet_format_async_temp::{ColumnChunk, ColumnMetaData, Encoding};
 
 use super::column_descriptor::ColumnDescriptor;
+use crate::encryption::ColumnDecryptor;
 use crate::error::Result;
 use crate::schema::types::{ParquetType, PhysicalType};
 use crate::statistics::{deserialize_statistics, Statistics};
 use crate::{compression::Compression, schema::types::Type};
 
 /// Metadata for a column chunk.
+///
+/// The metadata of a column encrypted with a key that was unavailable when reading an
+/// encrypted footer cannot be decrypted (see [`ColumnChunkMetaData::has_metadata`]): the
+/// accessors of its metadata panic, except [`ColumnChunkMetaData::statistics`], and reading
+/// its pages errors.
 // This contains the `ColumnDescriptor` associated with the chunk so that deserializers have
 // access to the descriptor (e.g. physical, converted, logical).
 #[derive(Debug, Clone)]
 pub struct ColumnChunkMetaData {
     column_chunk: ColumnChunk,
     column_descr: ColumnDescriptor,
+    decryptor: Option<Arc<ColumnDecryptor>>,
 }
 
 /// Represents common operations for a column chunk.
@@ -25,6 +32,7 @@ impl ColumnChunkMetaData {
         Self {
             column_chunk,
             column_descr,
+            decryptor: None,
         }
     }
 
@@ -42,7 +50,16 @@ impl ColumnChunkMetaData {
     }
 
     fn column_metadata(&self) -> &ColumnMetaData {
-        self.column_chunk.meta_data.as_ref().unwrap()
+        self.column_chunk
+            .meta_data
+            .as_ref()
+            .expect("the metadata of an encrypted column can only be read with its key")
+    }
+
+    /// Returns whether the metadata of this column chunk is available, i.e. whether it is not
+    /// encrypted with a key that was unavailable when reading the (encrypted) footer.
+    pub fn has_metadata(&self) -> bool {
+        self.column_chunk.meta_data.is_some()
     }
 
     /// Type of this column. Must be primitive.
@@ -65,9 +82,12 @@ impl ColumnChunkMetaData {
         }
     }
 
-    /// Decodes the raw statistics into a statistics
+    /// Decodes the raw statistics into a statistics, or `None` if the column chunk has no
+    /// statistics or its metadata is not available.
     pub fn statistics(&self) -> Option<Result<Arc<dyn Statistics>>> {
-        self.column_metadata()
+        self.column_chunk
+            .meta_data
+            .as_ref()?
             .statistics
             .as_ref()
             .map(|x| deserialize_statistics(x, self.descriptor().clone()))
@@ -118,6 +138,45 @@ impl ColumnChunkMetaData {
         &self.column_metadata().encodings
     }
 
+    /// Returns the offset of the bloom filter of this column chunk, if any.
+    pub fn bloom_filter_offset(&self) -> Option<i64> {
+        self.column_metadata().bloom_filter_offset
+    }
+
+    /// Returns the offset of the column index of this column chunk, if any.
+    pub fn column_index_offset(&self) -> Option<i64> {
+        self.column_chunk.column_index_offset
+    }
+
+    /// Returns the length in bytes of the column index of this column chunk, if any.
+    pub fn column_index_length(&self) -> Option<i32> {
+        self.column_chunk.column_index_length
+    }
+
+    /// Returns the offset of the offset index of this column chunk, if any.
+    pub fn offset_index_offset(&self) -> Option<i64> {
+        self.column_chunk.offset_index_offset
+    }
+
+    /// Returns the length in bytes of the offset index of this column chunk, if any.
+    pub fn offset_index_length(&self) -> Option<i32> {
+        self.column_chunk.offset_index_length
+    }
+
+    /// Returns whether this column chunk is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.column_chunk.crypto_metadata.is_some()
+    }
+
+    /// The decryptor of this column chunk, set when the metadata was read with decryption
+    pub(crate) fn decryptor(&self) -> Option<&Arc<ColumnDecryptor>> {
+        self.decryptor.as_ref()
+    }
+
+    pub(crate) fn set_decryptor(&mut self, decryptor: Option<Arc<ColumnDecryptor>>) {
+        self.decryptor = decryptor;
+    }
+
     /// Returns the offset and length in bytes of the column chunk within the file
     pub fn byte_range(&self) -> (u64, u64) {
         let col_start = if let Some(dict_page_offset) = self.dictionary_page_offset() {
@@ -141,6 +200,7 @@ impl ColumnChunkMetaData {
         Ok(Self {
             column_chunk,
             column_descr,
+            decryptor: None,
         })
     }
 
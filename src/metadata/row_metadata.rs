@@ -40,6 +40,11 @@ impl RowGroupMetaData {
         &self.columns
     }
 
+    /// Mutable slice of column chunk metadata.
+    pub(crate) fn columns_mut(&mut self) -> &mut [ColumnChunkMetaData] {
+        &mut self.columns
+    }
+
     /// Number of rows in this row group.
     pub fn num_rows(&self) -> i64 {
         self.num_rows
@@ -50,9 +55,14 @@ impl RowGroupMetaData {
         self.total_byte_size
     }
 
-    /// Total size of all compressed column data in this row group.
+    /// Total size of all compressed column data in this row group, of the columns whose
+    /// metadata is available.
     pub fn compressed_size(&self) -> i64 {
-        self.columns.iter().map(|c| c.compressed_size()).sum()
+        self.columns
+            .iter()
+            .filter(|c| c.has_metadata())
+            .map(|c| c.compressed_size())
+            .sum()
     }
 
     /// Method to convert from Thrift.
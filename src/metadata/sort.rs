@@ -1,6 +1,11 @@
+use std::cmp::Ordering;
+
 use parquet_format_async_temp::LogicalType;
 
-use crate::schema::types::{PhysicalType, PrimitiveConvertedType};
+use crate::schema::types::{ParquetType, PhysicalType, PrimitiveConvertedType};
+use crate::types::int96_cmp;
+
+use super::ColumnDescriptor;
 
 /// Sort order for page and column statistics.
 ///
@@ -35,6 +40,19 @@ pub fn get_sort_order(
     get_physical_sort_order(physical_type)
 }
 
+/// Returns the sort order of the values of the column `descriptor`.
+pub fn get_column_sort_order(descriptor: &ColumnDescriptor) -> SortOrder {
+    match descriptor.type_() {
+        ParquetType::PrimitiveType {
+            logical_type,
+            converted_type,
+            physical_type,
+            ..
+        } => get_sort_order(logical_type, converted_type, physical_type),
+        ParquetType::GroupType { .. } => SortOrder::Undefined,
+    }
+}
+
 fn get_logical_sort_order(logical_type: &LogicalType) -> SortOrder {
     // TODO: Should this take converted and logical type, for compatibility?
     use LogicalType::*;
@@ -87,3 +105,202 @@ fn get_physical_sort_order(physical_type: &PhysicalType) -> SortOrder {
         ByteArray | FixedLenByteArray(_) => SortOrder::Unsigned,
     }
 }
+
+/// Returns the bytes of `x` preceded by `sign` up to `length` bytes.
+fn sign_extend(x: &[u8], sign: u8, length: usize) -> impl Iterator<Item = u8> + '_ {
+    let padding = length - x.len();
+    (0..length).map(move |i| if i < padding { sign } else { x[i - padding] })
+}
+
+/// Compares two big-endian two's complement integers, of possibly different lengths.
+fn cmp_signed_bytes(a: &[u8], b: &[u8]) -> Ordering {
+    let is_negative = |x: &[u8]| x.first().map(|x| x & 0x80 != 0).unwrap_or(false);
+    match (is_negative(a), is_negative(b)) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (is_negative, _) => {
+            // sign-extend the shorter one
+            let sign = if is_negative { 0xFF } else { 0 };
+            let length = a.len().max(b.len());
+            sign_extend(a, sign, length).cmp(sign_extend(b, sign, length))
+        }
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for bool {}
+    impl Sealed for i32 {}
+    impl Sealed for i64 {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+    impl Sealed for [u32; 3] {}
+    impl Sealed for [u8] {}
+    impl Sealed for Vec<u8> {}
+}
+
+/// Values that can be compared with a [`SortOrder`].
+///
+/// This trait is sealed: it is only implemented for the types of the values of physical types.
+pub trait SortOrd: private::Sealed {
+    /// Compares `self` with `other` with the sort order `order`, or returns `None` if they
+    /// are not comparable, e.g. because the order is undefined or one of them is NaN.
+    fn sort_cmp(&self, other: &Self, order: SortOrder) -> Option<Ordering>;
+
+    /// Returns this value as the minimum of statistics. Floats return `-0.0` for both zeros,
+    /// since they compare equal and the minimum must not exclude `-0.0`.
+    fn min_bound(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Returns this value as the maximum of statistics. Floats return `+0.0` for both zeros,
+    /// since they compare equal and the maximum must not exclude `+0.0`.
+    fn max_bound(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+impl SortOrd for bool {
+    fn sort_cmp(&self, other: &Self, order: SortOrder) -> Option<Ordering> {
+        match order {
+            SortOrder::Undefined => None,
+            _ => Some(self.cmp(other)),
+        }
+    }
+}
+
+macro_rules! integer_sort_ord {
+    ($type:ty, $unsigned:ty) => {
+        impl SortOrd for $type {
+            fn sort_cmp(&self, other: &Self, order: SortOrder) -> Option<Ordering> {
+                match order {
+                    SortOrder::Signed => Some(self.cmp(other)),
+                    SortOrder::Unsigned => Some((*self as $unsigned).cmp(&(*other as $unsigned))),
+                    SortOrder::Undefined => None,
+                }
+            }
+        }
+    };
+}
+
+integer_sort_ord!(i32, u32);
+integer_sort_ord!(i64, u64);
+
+macro_rules! float_sort_ord {
+    ($type:ty) => {
+        impl SortOrd for $type {
+            fn sort_cmp(&self, other: &Self, order: SortOrder) -> Option<Ordering> {
+                match order {
+                    SortOrder::Undefined => None,
+                    _ => self.partial_cmp(other),
+                }
+            }
+
+            fn min_bound(self) -> Self {
+                if self == 0.0 {
+                    -0.0
+                } else {
+                    self
+                }
+            }
+
+            fn max_bound(self) -> Self {
+                if self == 0.0 {
+                    0.0
+                } else {
+                    self
+                }
+            }
+        }
+    };
+}
+
+float_sort_ord!(f32);
+float_sort_ord!(f64);
+
+impl SortOrd for [u32; 3] {
+    /// The order of INT96 is undefined by the spec (see [`get_sort_order`]); with any other
+    /// order, legacy timestamps are ordered by the instant they represent.
+    fn sort_cmp(&self, other: &Self, order: SortOrder) -> Option<Ordering> {
+        match order {
+            SortOrder::Signed | SortOrder::Unsigned => Some(int96_cmp(*self, *other)),
+            SortOrder::Undefined => None,
+        }
+    }
+}
+
+impl SortOrd for [u8] {
+    fn sort_cmp(&self, other: &Self, order: SortOrder) -> Option<Ordering> {
+        match order {
+            SortOrder::Unsigned => Some(self.cmp(other)),
+            // e.g. decimals
+            SortOrder::Signed => Some(cmp_signed_bytes(self, other)),
+            SortOrder::Undefined => None,
+        }
+    }
+}
+
+impl SortOrd for Vec<u8> {
+    fn sort_cmp(&self, other: &Self, order: SortOrder) -> Option<Ordering> {
+        self.as_slice().sort_cmp(other.as_slice(), order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_cmp() {
+        assert_eq!(
+            (-1i32).sort_cmp(&1, SortOrder::Signed),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            (-1i32).sort_cmp(&1, SortOrder::Unsigned),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            (-1i64).sort_cmp(&1, SortOrder::Unsigned),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(1i32.sort_cmp(&1, SortOrder::Undefined), None);
+        assert_eq!(f32::NAN.sort_cmp(&1.0, SortOrder::Signed), None);
+        assert_eq!(
+            [0, 0, 2_440_589].sort_cmp(&[1, 0, 2_440_588], SortOrder::Signed),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            [0, 0, 2_440_589].sort_cmp(&[1, 0, 2_440_588], SortOrder::Undefined),
+            None
+        );
+        // 9999-12-31 and 0001-01-01 are out of the range of i64 nanoseconds
+        assert_eq!(
+            [0, 0, 5_373_484].sort_cmp(&[0, 0, 2_440_588], SortOrder::Signed),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            [0, 0, 1_721_426].sort_cmp(&[0, 0, 2_440_588], SortOrder::Signed),
+            Some(Ordering::Less)
+        );
+
+        assert!(0.0f32.min_bound().is_sign_negative());
+        assert!((-0.0f64).max_bound().is_sign_positive());
+        assert_eq!((-1.0f32).max_bound(), -1.0);
+        assert_eq!(1i32.min_bound(), 1);
+
+        let a: &[u8] = &[0xFF];
+        let b: &[u8] = &[0x00, 0x01];
+        assert_eq!(a.sort_cmp(b, SortOrder::Unsigned), Some(Ordering::Greater));
+        assert_eq!(a.sort_cmp(b, SortOrder::Signed), Some(Ordering::Less));
+        let c: &[u8] = &[0xFF, 0xFE];
+        assert_eq!(a.sort_cmp(c, SortOrder::Signed), Some(Ordering::Greater));
+    }
+}
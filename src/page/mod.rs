@@ -1,6 +1,7 @@
 mod page_dict;
 pub use page_dict::*;
 
+use std::convert::TryFrom;
 use std::sync::Arc;
 
 pub use parquet_format_async_temp::{
@@ -10,11 +11,13 @@ pub use parquet_format_async_temp::{
 pub use crate::parquet_bridge::{DataPageHeaderExt, PageType};
 
 use crate::compression::Compression;
-use crate::encoding::{get_length, Encoding};
-use crate::error::Result;
+use crate::encoding::{get_length, hybrid_rle::HybridRleDecoder, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::indexes::Interval;
 use crate::metadata::ColumnDescriptor;
+use crate::read::levels::get_bit_width;
 
-use crate::statistics::{deserialize_statistics, Statistics};
+use crate::statistics::{deserialize_statistics, HyperLogLog, Statistics};
 
 /// A [`CompressedDataPage`] is compressed, encoded representation of a Parquet data page.
 /// It holds actual data and thus cloning it is expensive.
@@ -26,6 +29,10 @@ pub struct CompressedDataPage {
     uncompressed_page_size: usize,
     pub(crate) dictionary_page: Option<Arc<dyn DictPage>>,
     pub(crate) descriptor: ColumnDescriptor,
+    pub(crate) num_rows: Option<usize>,
+    // The rows to be selected from this page
+    pub(crate) selected_rows: Option<Vec<Interval>>,
+    pub(crate) sketch: Option<HyperLogLog>,
 }
 
 impl CompressedDataPage {
@@ -37,6 +44,7 @@ impl CompressedDataPage {
         dictionary_page: Option<Arc<dyn DictPage>>,
         descriptor: ColumnDescriptor,
     ) -> Self {
+        let num_rows = header_num_rows(&header, &descriptor);
         Self {
             header,
             buffer,
@@ -44,6 +52,9 @@ impl CompressedDataPage {
             uncompressed_page_size,
             dictionary_page,
             descriptor,
+            num_rows,
+            selected_rows: None,
+            sketch: None,
         }
     }
 
@@ -67,6 +78,33 @@ impl CompressedDataPage {
         self.header.num_values()
     }
 
+    /// The number of rows in this page, if known.
+    /// For v1 pages of repeated columns this is only known when the page was
+    /// compressed from a [`DataPage`] (e.g. via [`crate::write::Compressor`]).
+    pub fn num_rows(&self) -> Option<usize> {
+        self.num_rows
+    }
+
+    /// The intervals of rows selected from this page, relative to its first row.
+    /// Only set for pages read through an offset index
+    /// (see [`crate::read::IndexedPageReader`]), where `None` means all rows.
+    pub fn selected_rows(&self) -> Option<&[Interval]> {
+        self.selected_rows.as_deref()
+    }
+
+    /// The sketch of the distinct values of this page, if any, as set in the [`DataPage`] it
+    /// was compressed from.
+    pub fn sketch(&self) -> Option<&HyperLogLog> {
+        self.sketch.as_ref()
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        match &self.header {
+            DataPageHeader::V1(d) => d.encoding(),
+            DataPageHeader::V2(d) => d.encoding(),
+        }
+    }
+
     /// Decodes the raw statistics into a statistics
     pub fn statistics(&self) -> Option<Result<Arc<dyn Statistics>>> {
         match &self.header {
@@ -86,6 +124,43 @@ impl CompressedDataPage {
     }
 }
 
+/// Returns the number of rows of a page that can be derived from its header alone
+fn header_num_rows(header: &DataPageHeader, descriptor: &ColumnDescriptor) -> Option<usize> {
+    match header {
+        DataPageHeader::V1(header) => {
+            if descriptor.max_rep_level() == 0 {
+                Some(header.num_values as usize)
+            } else {
+                None
+            }
+        }
+        DataPageHeader::V2(header) => Some(header.num_rows as usize),
+    }
+}
+
+/// Returns the number of rows of a page from its header and, for v1 pages of repeated columns,
+/// from its uncompressed `buffer`, by counting the repetition levels equal to zero.
+fn num_rows(
+    header: &DataPageHeader,
+    buffer: &[u8],
+    descriptor: &ColumnDescriptor,
+) -> Option<usize> {
+    match header {
+        DataPageHeader::V1(header)
+            if descriptor.max_rep_level() > 0
+                && header.repetition_level_encoding() == Encoding::Rle =>
+        {
+            let (rep_levels, _, _) =
+                split_buffer_v1(buffer, true, descriptor.max_def_level() > 0).ok()?;
+            let num_bits = get_bit_width(descriptor.max_rep_level());
+            let rep_levels =
+                HybridRleDecoder::new(rep_levels, num_bits, header.num_values as usize);
+            Some(rep_levels.filter(|x| *x == 0).count())
+        }
+        _ => header_num_rows(header, descriptor),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DataPageHeader {
     V1(DataPageHeaderV1),
@@ -109,6 +184,8 @@ pub struct DataPage {
     pub(super) buffer: Vec<u8>,
     pub(super) dictionary_page: Option<Arc<dyn DictPage>>,
     pub(super) descriptor: ColumnDescriptor,
+    pub(crate) selected_rows: Option<Vec<Interval>>,
+    pub(crate) sketch: Option<HyperLogLog>,
 }
 
 impl DataPage {
@@ -123,9 +200,23 @@ impl DataPage {
             buffer,
             dictionary_page,
             descriptor,
+            selected_rows: None,
+            sketch: None,
         }
     }
 
+    /// Sets the sketch of the distinct values of this page, that writers merge into the
+    /// `distinct_count` of the statistics of its column chunk.
+    pub fn with_sketch(mut self, sketch: HyperLogLog) -> Self {
+        self.sketch = Some(sketch);
+        self
+    }
+
+    /// The sketch of the distinct values of this page, if any
+    pub fn sketch(&self) -> Option<&HyperLogLog> {
+        self.sketch.as_ref()
+    }
+
     pub fn header(&self) -> &DataPageHeader {
         &self.header
     }
@@ -148,6 +239,18 @@ impl DataPage {
         self.header.num_values()
     }
 
+    /// The number of rows in this page, if known.
+    /// For v1 pages of repeated columns this is computed from the repetition levels.
+    pub fn num_rows(&self) -> Option<usize> {
+        num_rows(&self.header, &self.buffer, &self.descriptor)
+    }
+
+    /// The intervals of rows selected from this page, relative to its first row.
+    /// `None` means all rows.
+    pub fn selected_rows(&self) -> Option<&[Interval]> {
+        self.selected_rows.as_deref()
+    }
+
     pub fn encoding(&self) -> Encoding {
         match &self.header {
             DataPageHeader::V1(d) => d.encoding(),
@@ -221,50 +324,90 @@ impl CompressedPage {
     }
 }
 
+/// Returns the CRC32 checksum of the (compressed and possibly encrypted) buffer of a page,
+/// as declared in its header.
+pub(crate) fn page_crc(buffer: &[u8]) -> i32 {
+    // SPEC: the CRC32 of the page as written to disk, stored as an i32
+    crc32fast::hash(buffer) as i32
+}
+
+/// Splits `buffer` into the levels of the length-prefixed (v1) format, whose length is stored
+/// in its first 4 bytes, and the remaining bytes.
+fn split_levels_v1(buffer: &[u8]) -> Result<(&[u8], &[u8])> {
+    if buffer.len() < 4 {
+        return Err(ParquetError::OutOfSpec(
+            "The levels of a v1 page must start with their length".to_string(),
+        ));
+    }
+    let length = get_length(buffer) as usize;
+    let buffer = &buffer[4..];
+    if length > buffer.len() {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The levels of a v1 page have {} bytes, but the page only has {} remaining",
+            length,
+            buffer.len()
+        )));
+    }
+    Ok(buffer.split_at(length))
+}
+
 /// Splits the page buffer into 3 slices corresponding to (encoded rep levels, encoded def levels, encoded values) for v1 pages.
+/// # Error
+/// Errors iff the lengths of the levels are out of the bounds of `buffer`.
 #[inline]
-pub fn split_buffer_v1(buffer: &[u8], has_rep: bool, has_def: bool) -> (&[u8], &[u8], &[u8]) {
+pub fn split_buffer_v1(
+    buffer: &[u8],
+    has_rep: bool,
+    has_def: bool,
+) -> Result<(&[u8], &[u8], &[u8])> {
     let (rep, buffer) = if has_rep {
-        let level_buffer_length = get_length(buffer) as usize;
-        (
-            &buffer[4..4 + level_buffer_length],
-            &buffer[4 + level_buffer_length..],
-        )
+        split_levels_v1(buffer)?
     } else {
         (&[] as &[u8], buffer)
     };
 
     let (def, buffer) = if has_def {
-        let level_buffer_length = get_length(buffer) as usize;
-        (
-            &buffer[4..4 + level_buffer_length],
-            &buffer[4 + level_buffer_length..],
-        )
+        split_levels_v1(buffer)?
     } else {
         (&[] as &[u8], buffer)
     };
 
-    (rep, def, buffer)
+    Ok((rep, def, buffer))
 }
 
 /// Splits the page buffer into 3 slices corresponding to (encoded rep levels, encoded def levels, encoded values) for v2 pages.
+/// # Error
+/// Errors iff the lengths of the levels are out of the bounds of `buffer`.
 pub fn split_buffer_v2(
     buffer: &[u8],
     rep_level_buffer_length: usize,
     def_level_buffer_length: usize,
-) -> (&[u8], &[u8], &[u8]) {
-    (
+) -> Result<(&[u8], &[u8], &[u8])> {
+    let levels_length = rep_level_buffer_length
+        .checked_add(def_level_buffer_length)
+        .filter(|length| *length <= buffer.len())
+        .ok_or_else(|| {
+            ParquetError::OutOfSpec(format!(
+                "The levels of a v2 page have {} and {} bytes, but the page only has {}",
+                rep_level_buffer_length,
+                def_level_buffer_length,
+                buffer.len()
+            ))
+        })?;
+    Ok((
         &buffer[..rep_level_buffer_length],
-        &buffer[rep_level_buffer_length..rep_level_buffer_length + def_level_buffer_length],
-        &buffer[rep_level_buffer_length + def_level_buffer_length..],
-    )
+        &buffer[rep_level_buffer_length..levels_length],
+        &buffer[levels_length..],
+    ))
 }
 
 /// Splits the page buffer into 3 slices corresponding to (encoded rep levels, encoded def levels, encoded values).
+/// # Error
+/// Errors iff the lengths of the levels are out of the bounds of the buffer of `page`.
 pub fn split_buffer<'a>(
     page: &'a DataPage,
     descriptor: &ColumnDescriptor,
-) -> (&'a [u8], &'a [u8], &'a [u8]) {
+) -> Result<(&'a [u8], &'a [u8], &'a [u8])> {
     match page.header() {
         DataPageHeader::V1(_) => split_buffer_v1(
             page.buffer(),
@@ -272,13 +415,18 @@ pub fn split_buffer<'a>(
             descriptor.max_def_level() > 0,
         ),
         DataPageHeader::V2(header) => {
-            let def_level_buffer_length = header.definition_levels_byte_length as usize;
-            let rep_level_buffer_length = header.repetition_levels_byte_length as usize;
-            split_buffer_v2(
-                page.buffer(),
-                rep_level_buffer_length,
-                def_level_buffer_length,
-            )
+            let def_level_buffer_length = usize::try_from(header.definition_levels_byte_length);
+            let rep_level_buffer_length = usize::try_from(header.repetition_levels_byte_length);
+            match (rep_level_buffer_length, def_level_buffer_length) {
+                (Ok(rep_level_buffer_length), Ok(def_level_buffer_length)) => split_buffer_v2(
+                    page.buffer(),
+                    rep_level_buffer_length,
+                    def_level_buffer_length,
+                ),
+                _ => Err(ParquetError::OutOfSpec(
+                    "The lengths of the levels of a v2 page cannot be negative".to_string(),
+                )),
+            }
         }
     }
 }
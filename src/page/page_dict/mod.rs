@@ -36,18 +36,42 @@ impl EncodedDictPage {
 #[derive(Debug)]
 pub struct CompressedDictPage {
     pub(crate) buffer: Vec<u8>,
+    pub(crate) compression: Option<Compression>,
     pub(crate) num_values: usize,
     pub(crate) uncompressed_page_size: usize,
 }
 
 impl CompressedDictPage {
+    /// Returns a new [`CompressedDictPage`] compressed with the codec of the options its column
+    /// is written with.
     pub fn new(buffer: Vec<u8>, uncompressed_page_size: usize, num_values: usize) -> Self {
         Self {
             buffer,
+            compression: None,
             uncompressed_page_size,
             num_values,
         }
     }
+
+    /// Returns a new [`CompressedDictPage`] compressed with `compression`, that writers check
+    /// against the codec of the other pages of its column chunk.
+    pub fn new_with_compression(
+        buffer: Vec<u8>,
+        compression: Compression,
+        uncompressed_page_size: usize,
+        num_values: usize,
+    ) -> Self {
+        Self {
+            compression: Some(compression),
+            ..Self::new(buffer, uncompressed_page_size, num_values)
+        }
+    }
+
+    /// The codec this page was compressed with, or `None` when it is the codec of the options
+    /// its column is written with.
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
 }
 
 pub fn read_dict_page(
@@ -3,6 +3,7 @@ use std::convert::TryFrom;
 use std::convert::TryInto;
 
 use crate::error::ParquetError;
+use parquet_format_async_temp::BoundaryOrder as ParquetBoundaryOrder;
 use parquet_format_async_temp::CompressionCodec;
 use parquet_format_async_temp::DataPageHeader;
 use parquet_format_async_temp::DataPageHeaderV2;
@@ -49,6 +50,7 @@ pub enum Compression {
     Brotli,
     Lz4,
     Zstd,
+    Lz4Raw,
 }
 
 impl TryFrom<CompressionCodec> for Compression {
@@ -63,6 +65,7 @@ impl TryFrom<CompressionCodec> for Compression {
             CompressionCodec::BROTLI => Compression::Brotli,
             CompressionCodec::LZ4 => Compression::Lz4,
             CompressionCodec::ZSTD => Compression::Zstd,
+            CompressionCodec(7) => Compression::Lz4Raw,
             _ => return Err(ParquetError::OutOfSpec("Thrift out of range".to_string())),
         })
     }
@@ -78,6 +81,8 @@ impl From<Compression> for CompressionCodec {
             Compression::Brotli => CompressionCodec::BROTLI,
             Compression::Lz4 => CompressionCodec::LZ4,
             Compression::Zstd => CompressionCodec::ZSTD,
+            // LZ4_RAW, not yet a constant of `parquet_format`
+            Compression::Lz4Raw => CompressionCodec(7),
         }
     }
 }
@@ -192,6 +197,38 @@ impl From<Encoding> for ParquetEncoding {
     }
 }
 
+/// Enum to annotate whether lists of min/max elements inside ColumnIndex
+/// are ordered and if so, in which direction.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum BoundaryOrder {
+    Unordered,
+    Ascending,
+    Descending,
+}
+
+impl TryFrom<ParquetBoundaryOrder> for BoundaryOrder {
+    type Error = ParquetError;
+
+    fn try_from(parquet: ParquetBoundaryOrder) -> Result<Self, Self::Error> {
+        Ok(match parquet {
+            ParquetBoundaryOrder::UNORDERED => BoundaryOrder::Unordered,
+            ParquetBoundaryOrder::ASCENDING => BoundaryOrder::Ascending,
+            ParquetBoundaryOrder::DESCENDING => BoundaryOrder::Descending,
+            _ => return Err(ParquetError::OutOfSpec("Thrift out of range".to_string())),
+        })
+    }
+}
+
+impl From<BoundaryOrder> for ParquetBoundaryOrder {
+    fn from(order: BoundaryOrder) -> Self {
+        match order {
+            BoundaryOrder::Unordered => ParquetBoundaryOrder::UNORDERED,
+            BoundaryOrder::Ascending => ParquetBoundaryOrder::ASCENDING,
+            BoundaryOrder::Descending => ParquetBoundaryOrder::DESCENDING,
+        }
+    }
+}
+
 pub trait DataPageHeaderExt {
     fn encoding(&self) -> Encoding;
     fn repetition_level_encoding(&self) -> Encoding;
@@ -85,12 +85,14 @@ pub fn decompress(
     buffer: &mut Vec<u8>,
 ) -> Result<DataPage> {
     decompress_buffer(&mut compressed_page, buffer)?;
-    Ok(DataPage::new(
+    let mut page = DataPage::new(
         compressed_page.header,
         std::mem::take(buffer),
         compressed_page.dictionary_page,
         compressed_page.descriptor,
-    ))
+    );
+    page.selected_rows = compressed_page.selected_rows;
+    Ok(page)
 }
 
 fn decompress_reuse<R: std::io::Read>(
@@ -100,12 +102,13 @@ fn decompress_reuse<R: std::io::Read>(
 ) -> Result<(DataPage, bool)> {
     let was_decompressed = decompress_buffer(&mut compressed_page, buffer)?;
 
-    let new_page = DataPage::new(
+    let mut new_page = DataPage::new(
         compressed_page.header,
         std::mem::take(buffer),
         compressed_page.dictionary_page,
         compressed_page.descriptor,
     );
+    new_page.selected_rows = compressed_page.selected_rows;
 
     if was_decompressed {
         iterator.reuse_buffer(compressed_page.buffer)
@@ -0,0 +1,173 @@
+use std::collections::VecDeque;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
+
+use crate::compression::Compression;
+use crate::error::{ParquetError, Result};
+use crate::indexes::FilteredPage;
+use crate::metadata::{ColumnChunkMetaData, ColumnDescriptor};
+use crate::page::{CompressedDataPage, DictPage, ParquetPageHeader};
+
+use super::page_iterator::{finish_page, has_dictionary, verify_crc, FinishedPage};
+
+/// An iterator of [`CompressedDataPage`]s of a column chunk that only reads the pages
+/// selected from its offset index (see [`crate::indexes::select_pages`]), seeking directly
+/// to each of them.
+///
+/// Each page yielded has [`CompressedDataPage::selected_rows`] set to the rows selected from it,
+/// and pages are yielded in the same order as the [`FilteredPage`]s this iterator was
+/// created from.
+///
+/// Encrypted column chunks are not supported: the first page of an encrypted column chunk
+/// errors, and no pages are yielded after it.
+pub struct IndexedPageReader<R: Read + Seek> {
+    // The source
+    reader: R,
+
+    compression: Compression,
+
+    descriptor: ColumnDescriptor,
+
+    // The offset of the dictionary page, if this column chunk has one
+    dictionary_offset: Option<u64>,
+
+    // Arc: it will be shared between multiple pages and pages should be Send + Sync.
+    current_dictionary: Option<Arc<dyn DictPage>>,
+
+    pages: VecDeque<FilteredPage>,
+
+    // Whether the column chunk is encrypted, which this reader does not support
+    is_encrypted: bool,
+
+    // Whether to verify the CRC32 checksum of pages
+    verify_crc: bool,
+
+    // The currently allocated buffer.
+    buffer: Vec<u8>,
+}
+
+impl<R: Read + Seek> IndexedPageReader<R> {
+    /// Returns a new [`IndexedPageReader`] that reads `pages` of `column` from `reader`.
+    ///
+    /// When `column` is encrypted, the iterator errors on its first page.
+    pub fn new(
+        reader: R,
+        column: &ColumnChunkMetaData,
+        pages: Vec<FilteredPage>,
+        buffer: Vec<u8>,
+    ) -> Self {
+        // the pages of encrypted columns are not read, and their metadata may be unavailable
+        let is_encrypted = column.is_encrypted();
+        let dictionary_offset = if !is_encrypted && has_dictionary(column) {
+            Some(column.byte_range().0)
+        } else {
+            None
+        };
+        let compression = if is_encrypted {
+            Compression::Uncompressed
+        } else {
+            column.compression()
+        };
+        Self {
+            reader,
+            compression,
+            descriptor: column.descriptor().clone(),
+            dictionary_offset,
+            current_dictionary: None,
+            pages: pages.into(),
+            is_encrypted,
+            verify_crc: false,
+            buffer,
+        }
+    }
+
+    /// Sets whether to verify the CRC32 checksum of the pages that declare one, erroring with
+    /// [`ParquetError::InvalidChecksum`] when a page does not match it.
+    pub fn set_verify_crc(&mut self, verify_crc: bool) {
+        self.verify_crc = verify_crc;
+    }
+
+    /// Reads Page header from Thrift.
+    fn read_page_header(&mut self) -> Result<ParquetPageHeader> {
+        let mut prot = TCompactInputProtocol::new(&mut self.reader);
+        let page_header = ParquetPageHeader::read_from_in_protocol(&mut prot)?;
+        Ok(page_header)
+    }
+
+    fn read_page(&mut self, offset: u64) -> Result<FinishedPage> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let page_header = self.read_page_header()?;
+
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let read_size = page_header.compressed_page_size as usize;
+        buffer.clear();
+        buffer.resize(read_size, 0);
+        self.reader.read_exact(&mut buffer)?;
+        if self.verify_crc {
+            verify_crc(&page_header, &buffer, &self.descriptor, offset)?;
+        }
+
+        let result = finish_page(
+            page_header,
+            &mut buffer,
+            self.compression,
+            &self.current_dictionary,
+            &self.descriptor,
+        );
+        self.buffer = buffer;
+        result
+    }
+
+    fn read_dictionary(&mut self) -> Result<()> {
+        if let Some(offset) = self.dictionary_offset.take() {
+            if let FinishedPage::Dict(dict) = self.read_page(offset)? {
+                self.current_dictionary = Some(dict);
+            }
+        }
+        Ok(())
+    }
+
+    fn next_page(&mut self) -> Result<Option<CompressedDataPage>> {
+        let page = if let Some(page) = self.pages.pop_front() {
+            page
+        } else {
+            return Ok(None);
+        };
+        if self.is_encrypted {
+            self.pages.clear();
+            return Err(general_err!(
+                "Reading pages of encrypted column chunks via their offset index is not supported"
+            ));
+        }
+        self.read_dictionary()?;
+
+        match self.read_page(page.start)? {
+            FinishedPage::Data(mut data) => {
+                data.selected_rows = Some(page.selected_rows);
+                Ok(Some(data))
+            }
+            _ => Err(ParquetError::OutOfSpec(format!(
+                "The page at offset {} of the offset index is not a data page",
+                page.start
+            ))),
+        }
+    }
+
+    pub fn reuse_buffer(&mut self, buffer: Vec<u8>) {
+        self.buffer = buffer;
+    }
+
+    pub fn into_inner(self) -> (R, Vec<u8>) {
+        (self.reader, self.buffer)
+    }
+}
+
+impl<R: Read + Seek> Iterator for IndexedPageReader<R> {
+    type Item = Result<CompressedDataPage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_page().transpose()
+    }
+}
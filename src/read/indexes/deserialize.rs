@@ -0,0 +1,152 @@
+use std::ops::Range;
+
+use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
+use parquet_format_async_temp::{ColumnIndex, OffsetIndex, PageLocation};
+
+use crate::error::{ParquetError, Result};
+use crate::indexes::{BooleanIndex, ByteIndex, FixedLenByteIndex, Index, NativeIndex};
+use crate::metadata::{ColumnChunkMetaData, ColumnDescriptor};
+use crate::schema::types::PhysicalType;
+
+/// The position of the indexes of a set of column chunks in the file:
+/// the offset and length of the region containing all of them, and the range of each
+/// index within that region (`None` when a column chunk has no index).
+pub(super) type IndexRanges = (u64, usize, Vec<Option<Range<usize>>>);
+
+/// Returns the [`IndexRanges`] of the indexes declared by `get_range` over `chunks`.
+pub(super) fn prepare_read<F: Fn(&ColumnChunkMetaData) -> Option<(i64, i32)>>(
+    chunks: &[ColumnChunkMetaData],
+    get_range: F,
+) -> Result<IndexRanges> {
+    let ranges = chunks
+        .iter()
+        .map(|chunk| {
+            get_range(chunk)
+                .map(|(offset, length)| {
+                    if chunk.is_encrypted() {
+                        return Err(general_err!(
+                            "The page indexes of the encrypted column \"{}\" are encrypted, which is not supported",
+                            chunk.descriptor().path_in_schema().join(".")
+                        ));
+                    }
+                    if offset < 0 || length < 0 {
+                        return Err(ParquetError::OutOfSpec(
+                            "The offset and length of a page index must be positive".to_string(),
+                        ));
+                    }
+                    Ok(offset as u64..offset as u64 + length as u64)
+                })
+                .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let start = ranges.iter().flatten().map(|x| x.start).min();
+    let end = ranges.iter().flatten().map(|x| x.end).max();
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return Ok((0, 0, ranges.into_iter().map(|_| None).collect())),
+    };
+
+    let ranges = ranges
+        .into_iter()
+        .map(|range| range.map(|x| (x.start - start) as usize..(x.end - start) as usize))
+        .collect();
+    Ok((start, (end - start) as usize, ranges))
+}
+
+/// Errors iff the region of `length` bytes at `offset` is not within a file of `file_size` bytes,
+/// so that lengths declared by the metadata are checked before allocating them.
+pub(super) fn check_range(offset: u64, length: usize, file_size: u64) -> Result<()> {
+    match offset.checked_add(length as u64) {
+        Some(end) if end <= file_size => Ok(()),
+        _ => Err(ParquetError::OutOfSpec(format!(
+            "The page indexes ({} bytes at offset {}) must be within the file ({} bytes)",
+            length, offset, file_size
+        ))),
+    }
+}
+
+/// Deserializes a [`ColumnIndex`] from `data` into an [`Index`] of the
+/// physical type of `descriptor`.
+pub(super) fn deserialize_column_index(
+    data: &[u8],
+    descriptor: ColumnDescriptor,
+) -> Result<Box<dyn Index>> {
+    let mut prot = TCompactInputProtocol::new(data);
+    let index = ColumnIndex::read_from_in_protocol(&mut prot)?;
+
+    // SPEC: the lists of a column index have one item per page
+    let num_pages = index.null_pages.len();
+    let num_null_counts = index.null_counts.as_ref().map(|x| x.len());
+    if index.min_values.len() != num_pages
+        || index.max_values.len() != num_pages
+        || num_null_counts.unwrap_or(num_pages) != num_pages
+    {
+        return Err(ParquetError::OutOfSpec(
+            "A column index must have as many null pages, min values, max values and null counts"
+                .to_string(),
+        ));
+    }
+
+    let index = match descriptor.physical_type() {
+        PhysicalType::Boolean => Box::new(BooleanIndex::try_new(index)?) as Box<dyn Index>,
+        PhysicalType::Int32 => Box::new(NativeIndex::<i32>::try_new(index, descriptor)?),
+        PhysicalType::Int64 => Box::new(NativeIndex::<i64>::try_new(index, descriptor)?),
+        PhysicalType::Int96 => Box::new(NativeIndex::<[u32; 3]>::try_new(index, descriptor)?),
+        PhysicalType::Float => Box::new(NativeIndex::<f32>::try_new(index, descriptor)?),
+        PhysicalType::Double => Box::new(NativeIndex::<f64>::try_new(index, descriptor)?),
+        PhysicalType::ByteArray => Box::new(ByteIndex::try_new(index, descriptor)?),
+        PhysicalType::FixedLenByteArray(_) => {
+            Box::new(FixedLenByteIndex::try_new(index, descriptor)?)
+        }
+    };
+
+    Ok(index)
+}
+
+/// Deserializes the [`PageLocation`]s of an [`OffsetIndex`] from `data`.
+pub(super) fn deserialize_offset_index(data: &[u8]) -> Result<Vec<PageLocation>> {
+    let mut prot = TCompactInputProtocol::new(data);
+    let offset = OffsetIndex::read_from_in_protocol(&mut prot)?;
+    Ok(offset.page_locations)
+}
+
+pub(super) fn column_index_range(chunk: &ColumnChunkMetaData) -> Option<(i64, i32)> {
+    chunk.column_index_offset().zip(chunk.column_index_length())
+}
+
+pub(super) fn offset_index_range(chunk: &ColumnChunkMetaData) -> Option<(i64, i32)> {
+    chunk.offset_index_offset().zip(chunk.offset_index_length())
+}
+
+/// Deserializes the column indexes of `chunks` from `data`, positioned by `ranges`.
+pub(super) fn deserialize_column_indexes(
+    chunks: &[ColumnChunkMetaData],
+    data: &[u8],
+    ranges: Vec<Option<Range<usize>>>,
+) -> Result<Vec<Option<Box<dyn Index>>>> {
+    chunks
+        .iter()
+        .zip(ranges)
+        .map(|(chunk, range)| {
+            range
+                .map(|range| deserialize_column_index(&data[range], chunk.descriptor().clone()))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Deserializes the offset indexes of `chunks` from `data`, positioned by `ranges`.
+pub(super) fn deserialize_offset_indexes(
+    data: &[u8],
+    ranges: Vec<Option<Range<usize>>>,
+) -> Result<Vec<Option<Vec<PageLocation>>>> {
+    ranges
+        .into_iter()
+        .map(|range| {
+            range
+                .map(|range| deserialize_offset_index(&data[range]))
+                .transpose()
+        })
+        .collect()
+}
@@ -0,0 +1,11 @@
+mod deserialize;
+mod read;
+#[cfg(feature = "stream")]
+mod stream;
+
+pub use read::{read_columns_indexes, read_pages_locations};
+#[cfg(feature = "stream")]
+pub use stream::{
+    read_columns_indexes as read_columns_indexes_async,
+    read_pages_locations as read_pages_locations_async,
+};
@@ -0,0 +1,193 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use parquet_format_async_temp::PageLocation;
+
+use crate::error::Result;
+use crate::indexes::Index;
+use crate::metadata::ColumnChunkMetaData;
+
+use super::super::metadata::stream_len;
+use super::deserialize::{
+    check_range, column_index_range, deserialize_column_indexes, deserialize_offset_indexes,
+    offset_index_range, prepare_read,
+};
+
+fn read_range<R: Read + Seek>(reader: &mut R, offset: u64, length: usize) -> Result<Vec<u8>> {
+    if length == 0 {
+        return Ok(vec![]);
+    }
+    check_range(offset, length, stream_len(reader)?)?;
+
+    let mut data = vec![0; length];
+    reader.seek(SeekFrom::Start(offset))?;
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// Reads the column indexes of `chunks` from `reader`, returning one [`Index`] per column
+/// chunk, or `None` when the column chunk has no column index.
+/// The indexes of all chunks are read in a single contiguous read.
+/// Downcast each [`Index`] according to its [`Index::physical_type`].
+/// # Error
+/// Errors iff an encrypted column chunk has a column index, since it is encrypted, which is
+/// not supported, or the indexes are out of spec, e.g. not within the file.
+pub fn read_columns_indexes<R: Read + Seek>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Option<Box<dyn Index>>>> {
+    let (offset, length, ranges) = prepare_read(chunks, column_index_range)?;
+
+    let data = read_range(reader, offset, length)?;
+
+    deserialize_column_indexes(chunks, &data, ranges)
+}
+
+/// Reads the offset indexes of `chunks` from `reader`, returning the [`PageLocation`]s
+/// of each column chunk, or `None` when the column chunk has no offset index.
+/// The indexes of all chunks are read in a single contiguous read.
+/// # Error
+/// Errors iff an encrypted column chunk has an offset index, since it is encrypted, which is
+/// not supported, or the indexes are out of spec, e.g. not within the file.
+pub fn read_pages_locations<R: Read + Seek>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Option<Vec<PageLocation>>>> {
+    let (offset, length, ranges) = prepare_read(chunks, offset_index_range)?;
+
+    let data = read_range(reader, offset, length)?;
+
+    deserialize_offset_indexes(&data, ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use parquet_format_async_temp::thrift::protocol::TCompactOutputProtocol;
+    use parquet_format_async_temp::{
+        BoundaryOrder as ParquetBoundaryOrder, ColumnChunk, ColumnIndex, OffsetIndex,
+    };
+
+    use super::super::deserialize::deserialize_column_index;
+    use super::*;
+    use crate::indexes::{BoundaryOrder, NativeIndex, PageIndex};
+    use crate::metadata::ColumnDescriptor;
+    use crate::schema::types::{ParquetType, PhysicalType};
+
+    fn write_indexes(buffer: &mut Vec<u8>) -> (ColumnChunk, ColumnChunk) {
+        let column_index = ColumnIndex {
+            null_pages: vec![false, true],
+            min_values: vec![1i64.to_le_bytes().to_vec(), vec![]],
+            max_values: vec![10i64.to_le_bytes().to_vec(), vec![]],
+            boundary_order: ParquetBoundaryOrder::ASCENDING,
+            null_counts: Some(vec![0, 5]),
+        };
+        let offset_index = OffsetIndex {
+            page_locations: vec![PageLocation::new(4, 20, 0), PageLocation::new(24, 12, 10)],
+        };
+
+        let column_index_offset = buffer.len() as i64;
+        let mut protocol = TCompactOutputProtocol::new(&mut *buffer);
+        let column_index_length = column_index.write_to_out_protocol(&mut protocol).unwrap();
+        let offset_index_offset = buffer.len() as i64;
+        let mut protocol = TCompactOutputProtocol::new(&mut *buffer);
+        let offset_index_length = offset_index.write_to_out_protocol(&mut protocol).unwrap();
+
+        let with_index = ColumnChunk::new(
+            None,
+            0,
+            None,
+            offset_index_offset,
+            offset_index_length as i32,
+            column_index_offset,
+            column_index_length as i32,
+            None,
+            None,
+        );
+        let without_index = ColumnChunk::new(None, 0, None, None, None, None, None, None, None);
+        (with_index, without_index)
+    }
+
+    #[test]
+    fn read_indexes() -> Result<()> {
+        let mut buffer = vec![0; 4];
+        let (with_index, without_index) = write_indexes(&mut buffer);
+
+        let type_ = ParquetType::from_physical("col".to_string(), PhysicalType::Int64);
+        let descriptor = ColumnDescriptor::new(type_.clone(), 1, 0, vec!["col".to_string()], type_);
+        let chunks = vec![
+            ColumnChunkMetaData::new(with_index, descriptor.clone()),
+            ColumnChunkMetaData::new(without_index, descriptor.clone()),
+        ];
+
+        let mut reader = Cursor::new(buffer);
+        let indexes = read_columns_indexes(&mut reader, &chunks)?;
+        assert_eq!(indexes.len(), 2);
+        assert!(indexes[1].is_none());
+        let index = indexes[0]
+            .as_ref()
+            .unwrap()
+            .as_any()
+            .downcast_ref::<NativeIndex<i64>>()
+            .unwrap();
+        assert_eq!(
+            index,
+            &NativeIndex::<i64> {
+                descriptor,
+                indexes: vec![
+                    PageIndex {
+                        min: Some(1),
+                        max: Some(10),
+                        null_count: Some(0)
+                    },
+                    PageIndex {
+                        min: None,
+                        max: None,
+                        null_count: Some(5)
+                    }
+                ],
+                boundary_order: BoundaryOrder::Ascending,
+            }
+        );
+
+        let locations = read_pages_locations(&mut reader, &chunks)?;
+        assert_eq!(
+            locations,
+            vec![
+                Some(vec![
+                    PageLocation::new(4, 20, 0),
+                    PageLocation::new(24, 12, 10)
+                ]),
+                None
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_spec_indexes() -> Result<()> {
+        let mut buffer = vec![0; 4];
+        let (mut with_index, _) = write_indexes(&mut buffer);
+
+        let type_ = ParquetType::from_physical("col".to_string(), PhysicalType::Int64);
+        let descriptor = ColumnDescriptor::new(type_.clone(), 1, 0, vec!["col".to_string()], type_);
+
+        // lists of different lengths
+        let column_index = ColumnIndex {
+            null_pages: vec![false],
+            min_values: vec![1i64.to_le_bytes().to_vec(), vec![]],
+            max_values: vec![10i64.to_le_bytes().to_vec()],
+            boundary_order: ParquetBoundaryOrder::ASCENDING,
+            null_counts: None,
+        };
+        let mut data = vec![];
+        column_index.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut data))?;
+        assert!(deserialize_column_index(&data, descriptor.clone()).is_err());
+
+        // a length past the end of the file is not allocated
+        with_index.column_index_length = Some(i32::MAX);
+        let chunks = vec![ColumnChunkMetaData::new(with_index, descriptor)];
+        assert!(read_columns_indexes(&mut Cursor::new(buffer), &chunks).is_err());
+        Ok(())
+    }
+}
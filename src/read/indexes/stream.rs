@@ -0,0 +1,54 @@
+use std::io::SeekFrom;
+
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use parquet_format_async_temp::PageLocation;
+
+use crate::error::Result;
+use crate::indexes::Index;
+use crate::metadata::ColumnChunkMetaData;
+
+use super::super::stream::stream_len;
+use super::deserialize::{
+    check_range, column_index_range, deserialize_column_indexes, deserialize_offset_indexes,
+    offset_index_range, prepare_read,
+};
+
+async fn read_range<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+    reader: &mut R,
+    offset: u64,
+    length: usize,
+) -> Result<Vec<u8>> {
+    if length == 0 {
+        return Ok(vec![]);
+    }
+    check_range(offset, length, stream_len(reader).await?)?;
+
+    let mut data = vec![0; length];
+    reader.seek(SeekFrom::Start(offset)).await?;
+    reader.read_exact(&mut data).await?;
+    Ok(data)
+}
+
+/// Async version of [`crate::read::read_columns_indexes`].
+pub async fn read_columns_indexes<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Option<Box<dyn Index>>>> {
+    let (offset, length, ranges) = prepare_read(chunks, column_index_range)?;
+
+    let data = read_range(reader, offset, length).await?;
+
+    deserialize_column_indexes(chunks, &data, ranges)
+}
+
+/// Async version of [`crate::read::read_pages_locations`].
+pub async fn read_pages_locations<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Option<Vec<PageLocation>>>> {
+    let (offset, length, ranges) = prepare_read(chunks, offset_index_range)?;
+
+    let data = read_range(reader, offset, length).await?;
+
+    deserialize_offset_indexes(&data, ranges)
+}
@@ -1,9 +1,49 @@
+use crate::encoding::hybrid_rle::HybridRleDecoder;
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::page::{split_buffer, DataPage};
+
 /// Returns the number of bits needed to store the given maximum definition or repetition level.
 #[inline]
 pub fn get_bit_width(max_level: i16) -> u32 {
     16 - max_level.leading_zeros()
 }
 
+/// Returns the decoders of the repetition and definition levels of `page`, in this order.
+/// Levels whose maximum is zero are not stored in pages, and are decoded as zeros.
+/// # Error
+/// Errors iff the levels of the page are not RLE-encoded.
+pub fn decode(page: &DataPage) -> Result<(HybridRleDecoder<'_>, HybridRleDecoder<'_>)> {
+    let descriptor = page.descriptor();
+    for (encoding, max_level) in [
+        (page.repetition_level_encoding(), descriptor.max_rep_level()),
+        (page.definition_level_encoding(), descriptor.max_def_level()),
+    ] {
+        if max_level > 0 && encoding != Encoding::Rle {
+            return Err(general_err!(
+                "Decoding levels encoded with {:?} is not supported",
+                encoding
+            ));
+        }
+    }
+
+    let (rep_levels, def_levels, _) = split_buffer(page, descriptor)?;
+    let length = page.num_values();
+    // levels of a maximum of zero have a bit width of zero, and decode to zeros
+    Ok((
+        HybridRleDecoder::new(
+            rep_levels,
+            get_bit_width(descriptor.max_rep_level()),
+            length,
+        ),
+        HybridRleDecoder::new(
+            def_levels,
+            get_bit_width(descriptor.max_def_level()),
+            length,
+        ),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::get_bit_width;
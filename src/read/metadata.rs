@@ -18,7 +18,7 @@
 use std::convert::TryInto;
 use std::{
     cmp::min,
-    io::{Cursor, Read, Seek, SeekFrom},
+    io::{Read, Seek, SeekFrom},
 };
 
 use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
@@ -27,17 +27,20 @@ use parquet_format_async_temp::{ColumnOrder as TColumnOrder, FileMetaData as TFi
 use super::super::metadata::get_sort_order;
 use super::super::metadata::ColumnOrder;
 use super::super::metadata::SchemaDescriptor;
-use super::super::{metadata::*, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, PARQUET_MAGIC};
+use super::super::{
+    metadata::*, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, PARQUET_MAGIC, PARQUET_MAGIC_ENCRYPTED,
+};
 
+use crate::encryption::{decrypt_metadata, FileDecryptionProperties};
 use crate::error::{ParquetError, Result};
 use crate::schema::types::ParquetType;
 
-pub(super) fn metadata_len(buffer: &[u8], len: usize) -> i32 {
+fn metadata_len(buffer: &[u8], len: usize) -> i32 {
     i32::from_le_bytes(buffer[len - 8..len - 4].try_into().unwrap())
 }
 
 // see (unstable) Seek::stream_len
-fn stream_len(seek: &mut impl Seek) -> std::result::Result<u64, std::io::Error> {
+pub(super) fn stream_len(seek: &mut impl Seek) -> std::result::Result<u64, std::io::Error> {
     let old_pos = seek.seek(SeekFrom::Current(0))?;
     let len = seek.seek(SeekFrom::End(0))?;
 
@@ -50,7 +53,41 @@ fn stream_len(seek: &mut impl Seek) -> std::result::Result<u64, std::io::Error>
     Ok(len)
 }
 
-/// Reads a file's metadata.
+/// Checks that `buffer` (the end of a file) ends with a parquet magic, returning whether
+/// the footer is encrypted.
+pub(super) fn is_encrypted_footer(buffer: &[u8]) -> Result<bool> {
+    let magic = &buffer[buffer.len() - 4..];
+    if magic == PARQUET_MAGIC {
+        Ok(false)
+    } else if magic == PARQUET_MAGIC_ENCRYPTED {
+        Ok(true)
+    } else {
+        Err(general_err!("Invalid Parquet file. Corrupt footer"))
+    }
+}
+
+/// Returns the length of the footer (metadata, length and magic) declared at the end of a file.
+pub(super) fn footer_len(buffer: &[u8], file_size: u64) -> Result<u64> {
+    let metadata_len = metadata_len(buffer, buffer.len());
+
+    if metadata_len < 0 {
+        return Err(general_err!(
+            "Invalid Parquet file. Metadata length is less than zero ({})",
+            metadata_len
+        ));
+    }
+    let footer_metadata_len = FOOTER_SIZE + metadata_len as u64;
+
+    if footer_metadata_len > file_size {
+        return Err(general_err!(
+            "Invalid Parquet file. Metadata start is less than zero ({})",
+            file_size as i64 - footer_metadata_len as i64
+        ));
+    }
+    Ok(footer_metadata_len)
+}
+
+/// Reads the (possibly encrypted) metadata of a file and whether it is encrypted.
 // Layout of Parquet file
 // +---------------------------+-----+---+
 // |      Rest of file         |  B  | A |
@@ -59,7 +96,7 @@ fn stream_len(seek: &mut impl Seek) -> std::result::Result<u64, std::io::Error>
 //
 // The reader first reads DEFAULT_FOOTER_SIZE bytes from the end of the file.
 // If it is not enough according to the length indicated in the footer, it reads more bytes.
-pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
+fn read_footer<R: Read + Seek>(reader: &mut R) -> Result<(Vec<u8>, bool)> {
     // check file is large enough to hold footer
     let file_size = stream_len(reader)?;
     if file_size < FOOTER_SIZE {
@@ -75,42 +112,55 @@ pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
     reader.read_exact(&mut default_len_end_buf)?;
 
     // check this is indeed a parquet file
-    if default_len_end_buf[default_end_len - 4..] != PARQUET_MAGIC {
-        return Err(general_err!("Invalid Parquet file. Corrupt footer"));
-    }
-
-    let metadata_len = metadata_len(&default_len_end_buf, default_end_len);
+    let is_encrypted = is_encrypted_footer(&default_len_end_buf)?;
 
-    if metadata_len < 0 {
-        return Err(general_err!(
-            "Invalid Parquet file. Metadata length is less than zero ({})",
-            metadata_len
-        ));
-    }
-    let footer_metadata_len = FOOTER_SIZE + metadata_len as u64;
+    let footer_metadata_len = footer_len(&default_len_end_buf, file_size)?;
+    let metadata_len = (footer_metadata_len - FOOTER_SIZE) as usize;
 
-    let t_file_metadata = if footer_metadata_len > file_size {
-        return Err(general_err!(
-            "Invalid Parquet file. Metadata start is less than zero ({})",
-            file_size as i64 - footer_metadata_len as i64
-        ));
-    } else if footer_metadata_len < DEFAULT_FOOTER_READ_SIZE {
+    let buffer = if footer_metadata_len < DEFAULT_FOOTER_READ_SIZE {
         // the whole metadata is in the bytes we already read
-        // build up the reader covering the entire metadata
-        let mut reader = Cursor::new(default_len_end_buf);
-        reader.seek(SeekFrom::End(-(footer_metadata_len as i64)))?;
-
-        let mut prot = TCompactInputProtocol::new(reader);
-        TFileMetaData::read_from_in_protocol(&mut prot)
+        let start = default_end_len - footer_metadata_len as usize;
+        default_len_end_buf[start..start + metadata_len].to_vec()
     } else {
         // the end of file read by default is not long enough, read again including all metadata.
         reader.seek(SeekFrom::End(-(footer_metadata_len as i64)))?;
+        let mut buffer = vec![0; metadata_len];
+        reader.read_exact(&mut buffer)?;
+        buffer
+    };
+    Ok((buffer, is_encrypted))
+}
 
-        let mut prot = TCompactInputProtocol::new(reader);
-        TFileMetaData::read_from_in_protocol(&mut prot)
+/// Deserializes the (plaintext) metadata of a file.
+pub(super) fn deserialize_metadata(buffer: &[u8], is_encrypted: bool) -> Result<FileMetaData> {
+    if is_encrypted {
+        return Err(general_err!(
+            "The footer of this file is encrypted. Use `read_metadata_with_decryption` to read it"
+        ));
     }
-    .map_err(|e| ParquetError::General(format!("Could not parse metadata: {}", e)))?;
+    let mut prot = TCompactInputProtocol::new(buffer);
+    let t_file_metadata = TFileMetaData::read_from_in_protocol(&mut prot)
+        .map_err(|e| ParquetError::General(format!("Could not parse metadata: {}", e)))?;
+    from_thrift(t_file_metadata)
+}
+
+/// Deserializes the metadata of a file, decrypting it and its encrypted columns with `properties`.
+pub(super) fn deserialize_metadata_with_decryption(
+    buffer: &[u8],
+    is_encrypted: bool,
+    properties: &FileDecryptionProperties,
+) -> Result<FileMetaData> {
+    let (t_file_metadata, decryptors) = decrypt_metadata(buffer, is_encrypted, properties)?;
+    let mut metadata = from_thrift(t_file_metadata)?;
+    for (row_group, decryptors) in metadata.row_groups.iter_mut().zip(decryptors) {
+        for (column, decryptor) in row_group.columns_mut().iter_mut().zip(decryptors) {
+            column.set_decryptor(decryptor);
+        }
+    }
+    Ok(metadata)
+}
 
+fn from_thrift(t_file_metadata: TFileMetaData) -> Result<FileMetaData> {
     let schema = t_file_metadata.schema.iter().collect::<Vec<_>>();
     let schema_descr = SchemaDescriptor::try_from_thrift(&schema)?;
 
@@ -136,6 +186,29 @@ pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
     ))
 }
 
+/// Reads a file's metadata.
+///
+/// Files with an encrypted footer must be read with [`read_metadata_with_decryption`].
+pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
+    let (buffer, is_encrypted) = read_footer(reader)?;
+    deserialize_metadata(&buffer, is_encrypted)
+}
+
+/// Reads the metadata of a file encrypted with parquet modular encryption, decrypting it with
+/// `properties`.
+///
+/// The footer (encrypted or signed) and the metadata of every encrypted column are decrypted,
+/// and the column chunks of the result carry their keys, so that their pages are decrypted
+/// when read (e.g. via [`super::get_page_iterator`]).
+/// Files that are not encrypted are read as by [`read_metadata`].
+pub fn read_metadata_with_decryption<R: Read + Seek>(
+    reader: &mut R,
+    properties: &FileDecryptionProperties,
+) -> Result<FileMetaData> {
+    let (buffer, is_encrypted) = read_footer(reader)?;
+    deserialize_metadata_with_decryption(&buffer, is_encrypted, properties)
+}
+
 /// Parses column orders from Thrift definition.
 /// If no column orders are defined, returns `None`.
 pub(super) fn parse_column_orders(
@@ -1,9 +1,13 @@
 mod compression;
+mod indexed_page_iterator;
+mod indexes;
 pub mod levels;
 mod metadata;
 mod page_iterator;
 #[cfg(feature = "stream")]
 mod page_stream;
+mod predicate;
+mod record;
 #[cfg(feature = "stream")]
 mod stream;
 
@@ -12,12 +16,22 @@ use std::sync::Arc;
 use std::vec::IntoIter;
 
 pub use compression::{decompress, BasicDecompressor, Decompressor};
-pub use metadata::read_metadata;
+pub use indexed_page_iterator::IndexedPageReader;
+pub use indexes::{read_columns_indexes, read_pages_locations};
+#[cfg(feature = "stream")]
+pub use indexes::{read_columns_indexes_async, read_pages_locations_async};
+pub use metadata::{read_metadata, read_metadata_with_decryption};
+use page_iterator::page_decryptor;
 pub use page_iterator::{PageFilter, PageIterator};
 #[cfg(feature = "stream")]
-pub use page_stream::get_page_stream;
+pub use page_stream::{get_page_stream, get_page_stream_with_options, PageStreamOptions};
+pub use predicate::{select_row_groups, Predicate, Scalar};
+pub use record::{Field, RecordReader, Row};
 #[cfg(feature = "stream")]
-pub use stream::read_metadata as read_metadata_async;
+pub use stream::{
+    read_metadata as read_metadata_async,
+    read_metadata_with_decryption as read_metadata_with_decryption_async,
+};
 
 use crate::error::ParquetError;
 use crate::metadata::{ColumnChunkMetaData, RowGroupMetaData};
@@ -51,16 +65,20 @@ pub fn get_page_iterator<R: Read + Seek>(
 ) -> Result<PageIterator<R>> {
     let pages_filter = pages_filter.unwrap_or_else(|| Arc::new(|_, _| true));
 
+    let decryptor = page_decryptor(column_chunk)?;
     let (col_start, _) = column_chunk.byte_range();
     reader.seek(SeekFrom::Start(col_start))?;
-    Ok(PageIterator::new(
+    let mut iterator = PageIterator::new(
         reader,
         column_chunk.num_values(),
         column_chunk.compression(),
         column_chunk.descriptor().clone(),
         pages_filter,
         buffer,
-    ))
+    );
+    iterator.decryptor = decryptor;
+    iterator.offset = col_start;
+    Ok(iterator)
 }
 
 /// Returns an [`Iterator`] of [`ColumnChunkMetaData`] corresponding to the columns
@@ -4,12 +4,14 @@ use std::{io::Read, sync::Arc};
 use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
 
 use crate::compression::Compression;
-use crate::error::Result;
-use crate::metadata::ColumnDescriptor;
+use crate::encoding::Encoding;
+use crate::encryption::{read_module, PageDecryptor};
+use crate::error::{ParquetError, Result};
+use crate::metadata::{ColumnChunkMetaData, ColumnDescriptor};
 
 use crate::page::{
-    read_dict_page, CompressedDataPage, DataPageHeader, DictPage, EncodedDictPage, PageType,
-    ParquetPageHeader,
+    page_crc, read_dict_page, CompressedDataPage, DataPageHeader, DictPage, EncodedDictPage,
+    PageType, ParquetPageHeader,
 };
 
 /// Type declaration for a page filter
@@ -36,6 +38,15 @@ pub struct PageIterator<R: Read> {
 
     descriptor: ColumnDescriptor,
 
+    // Decrypts the pages of encrypted column chunks
+    pub(super) decryptor: Option<PageDecryptor>,
+
+    // Whether to verify the CRC32 checksum of pages
+    verify_crc: bool,
+
+    // The offset of the next page in the file
+    pub(super) offset: u64,
+
     // The currently allocated buffer.
     pub(crate) buffer: Vec<u8>,
 }
@@ -57,17 +68,38 @@ impl<R: Read> PageIterator<R> {
             current_dictionary: None,
             descriptor,
             pages_filter,
+            decryptor: None,
+            verify_crc: false,
+            offset: 0,
             buffer,
         }
     }
 
     /// Reads Page header from Thrift.
     fn read_page_header(&mut self) -> Result<ParquetPageHeader> {
-        let mut prot = TCompactInputProtocol::new(&mut self.reader);
+        if let Some(decryptor) = &self.decryptor {
+            let module = read_module(&mut self.reader)?;
+            self.offset += module.len() as u64;
+            let header = decryptor.decrypt_header(&module)?;
+            let mut prot = TCompactInputProtocol::new(header.as_slice());
+            return Ok(ParquetPageHeader::read_from_in_protocol(&mut prot)?);
+        }
+        let mut reader = CountingReader {
+            reader: &mut self.reader,
+            count: 0,
+        };
+        let mut prot = TCompactInputProtocol::new(&mut reader);
         let page_header = ParquetPageHeader::read_from_in_protocol(&mut prot)?;
+        self.offset += reader.count;
         Ok(page_header)
     }
 
+    /// Sets whether to verify the CRC32 checksum of the pages that declare one, erroring with
+    /// [`ParquetError::InvalidChecksum`] when a page does not match it.
+    pub fn set_verify_crc(&mut self, verify_crc: bool) {
+        self.verify_crc = verify_crc;
+    }
+
     pub fn reuse_buffer(&mut self, buffer: Vec<u8>) {
         self.buffer = buffer;
     }
@@ -126,10 +158,42 @@ fn next_page<R: Read>(
     Ok(None)
 }
 
+/// A [`Read`] counting the bytes read from it
+struct CountingReader<'a, R: Read> {
+    reader: &'a mut R,
+    count: u64,
+}
+
+impl<'a, R: Read> Read for CountingReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+/// Verifies the CRC32 checksum of the (compressed) `buffer` of the page at `offset`,
+/// if its header declares one.
+pub(super) fn verify_crc(
+    page_header: &ParquetPageHeader,
+    buffer: &[u8],
+    descriptor: &ColumnDescriptor,
+    offset: u64,
+) -> Result<()> {
+    match page_header.crc {
+        Some(crc) if crc != page_crc(buffer) => Err(ParquetError::InvalidChecksum(
+            descriptor.path_in_schema().join("."),
+            offset,
+        )),
+        _ => Ok(()),
+    }
+}
+
 fn build_page<R: Read>(
     reader: &mut PageIterator<R>,
     buffer: &mut Vec<u8>,
 ) -> Result<Option<CompressedDataPage>> {
+    let offset = reader.offset;
     let page_header = reader.read_page_header()?;
     reader.seen_num_values += get_page_header(&page_header)
         .map(|x| x.num_values() as i64)
@@ -145,6 +209,19 @@ fn build_page<R: Read>(
         }
         reader.reader.read_exact(buffer)?;
     }
+    reader.offset += read_size as u64;
+    if reader.verify_crc {
+        verify_crc(
+            &page_header,
+            &buffer[..read_size],
+            &reader.descriptor,
+            offset,
+        )?;
+    }
+    if let Some(decryptor) = reader.decryptor.as_mut() {
+        *buffer = decryptor.decrypt_page(buffer)?;
+        decryptor.advance();
+    }
 
     let result = finish_page(
         page_header,
@@ -164,6 +241,32 @@ fn build_page<R: Read>(
     }
 }
 
+/// Returns the [`PageDecryptor`] of the pages of `column`, if it is encrypted.
+/// # Error
+/// Errors iff the column chunk is encrypted but its metadata was read without its key.
+pub(super) fn page_decryptor(column: &ColumnChunkMetaData) -> Result<Option<PageDecryptor>> {
+    match column.decryptor() {
+        Some(decryptor) => Ok(Some(PageDecryptor::new(
+            decryptor.clone(),
+            has_dictionary(column),
+        ))),
+        None if column.is_encrypted() => Err(general_err!(
+            "The column \"{}\" is encrypted; its pages can only be read with its key",
+            column.descriptor().path_in_schema().join(".")
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Returns whether the column chunk starts with a dictionary page
+pub(super) fn has_dictionary(column: &ColumnChunkMetaData) -> bool {
+    column.dictionary_page_offset().is_some()
+        || column.column_encoding().iter().any(|encoding| {
+            *encoding == Encoding::PlainDictionary.into()
+                || *encoding == Encoding::RleDictionary.into()
+        })
+}
+
 pub(super) enum FinishedPage {
     Data(CompressedDataPage),
     Dict(Arc<dyn DictPage>),
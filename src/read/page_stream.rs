@@ -2,16 +2,30 @@ use std::io::SeekFrom;
 
 use async_stream::try_stream;
 use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, Stream};
-use parquet_format_async_temp::thrift::protocol::TCompactInputStreamProtocol;
+use parquet_format_async_temp::thrift::protocol::{
+    TCompactInputProtocol, TCompactInputStreamProtocol,
+};
 
 use crate::compression::Compression;
+use crate::encryption::PageDecryptor;
 use crate::error::Result;
 use crate::metadata::{ColumnChunkMetaData, ColumnDescriptor};
 use crate::page::{CompressedDataPage, ParquetPageHeader};
 
-use super::page_iterator::{finish_page, get_page_header, FinishedPage};
+use super::page_iterator::{
+    finish_page, get_page_header, page_decryptor, verify_crc, FinishedPage,
+};
 use super::PageFilter;
 
+/// Options of [`get_page_stream_with_options`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PageStreamOptions {
+    /// Whether the CRC32 checksum of the pages that declare one is verified, erroring with
+    /// [`ParquetError::InvalidChecksum`](crate::error::ParquetError::InvalidChecksum) when a
+    /// page does not match it.
+    pub verify_crc: bool,
+}
+
 /// Returns a stream of compressed data pages
 pub async fn get_page_stream<'a, RR: AsyncRead + Unpin + Send + AsyncSeek>(
     column_metadata: &'a ColumnChunkMetaData,
@@ -19,6 +33,25 @@ pub async fn get_page_stream<'a, RR: AsyncRead + Unpin + Send + AsyncSeek>(
     buffer: Vec<u8>,
     pages_filter: PageFilter,
 ) -> Result<impl Stream<Item = Result<CompressedDataPage>> + 'a> {
+    get_page_stream_with_options(
+        column_metadata,
+        reader,
+        buffer,
+        pages_filter,
+        PageStreamOptions::default(),
+    )
+    .await
+}
+
+/// Returns a stream of compressed data pages, read according to `options`
+pub async fn get_page_stream_with_options<'a, RR: AsyncRead + Unpin + Send + AsyncSeek>(
+    column_metadata: &'a ColumnChunkMetaData,
+    reader: &'a mut RR,
+    buffer: Vec<u8>,
+    pages_filter: PageFilter,
+    options: PageStreamOptions,
+) -> Result<impl Stream<Item = Result<CompressedDataPage>> + 'a> {
+    let decryptor = page_decryptor(column_metadata)?;
     let (col_start, _) = column_metadata.byte_range();
     reader.seek(SeekFrom::Start(col_start)).await?;
     Ok(_get_page_stream(
@@ -26,25 +59,31 @@ pub async fn get_page_stream<'a, RR: AsyncRead + Unpin + Send + AsyncSeek>(
         column_metadata.num_values(),
         column_metadata.compression(),
         column_metadata.descriptor(),
+        decryptor,
         buffer,
         pages_filter,
+        options.verify_crc,
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn _get_page_stream<'a, R: AsyncRead + AsyncSeek + Unpin + Send>(
     reader: &'a mut R,
     total_num_values: i64,
     compression: Compression,
     descriptor: &'a ColumnDescriptor,
+    mut decryptor: Option<PageDecryptor>,
     mut buffer: Vec<u8>,
     pages_filter: PageFilter,
+    verify_crc: bool,
 ) -> impl Stream<Item = Result<CompressedDataPage>> + 'a {
     let mut seen_values = 0i64;
     let mut current_dictionary = None;
     try_stream! {
         while seen_values < total_num_values {
             // the header
-            let page_header = read_page_header(reader).await?;
+            let offset = reader.seek(SeekFrom::Current(0)).await?;
+            let page_header = read_page_header(reader, decryptor.as_ref()).await?;
 
             let data_header = get_page_header(&page_header);
             seen_values += data_header.as_ref().map(|x| x.num_values() as i64).unwrap_or_default();
@@ -55,6 +94,9 @@ fn _get_page_stream<'a, R: AsyncRead + AsyncSeek + Unpin + Send>(
                 if !pages_filter(descriptor, &data_header) {
                     // page to be skipped, we sill need to seek
                     reader.seek(SeekFrom::Current(read_size)).await?;
+                    if let Some(decryptor) = decryptor.as_mut() {
+                        decryptor.advance();
+                    }
                     continue
                 }
             }
@@ -65,6 +107,13 @@ fn _get_page_stream<'a, R: AsyncRead + AsyncSeek + Unpin + Send>(
                 buffer.resize(read_size, 0);
                 reader.read_exact(&mut buffer).await?;
             }
+            if verify_crc {
+                self::verify_crc(&page_header, &buffer[..read_size], descriptor, offset)?;
+            }
+            if let Some(decryptor) = decryptor.as_mut() {
+                buffer = decryptor.decrypt_page(&buffer)?;
+                decryptor.advance();
+            }
             let result = finish_page(
                 page_header,
                 &mut buffer,
@@ -90,7 +139,19 @@ fn _get_page_stream<'a, R: AsyncRead + AsyncSeek + Unpin + Send>(
 /// Reads Page header from Thrift.
 async fn read_page_header<R: AsyncRead + Unpin + Send>(
     reader: &mut R,
+    decryptor: Option<&PageDecryptor>,
 ) -> Result<ParquetPageHeader> {
+    if let Some(decryptor) = decryptor {
+        let mut length = [0u8; 4];
+        reader.read_exact(&mut length).await?;
+        let mut module = length.to_vec();
+        module.resize(4 + u32::from_le_bytes(length) as usize, 0);
+        reader.read_exact(&mut module[4..]).await?;
+
+        let header = decryptor.decrypt_header(&module)?;
+        let mut prot = TCompactInputProtocol::new(header.as_slice());
+        return Ok(ParquetPageHeader::read_from_in_protocol(&mut prot)?);
+    }
     let mut prot = TCompactInputStreamProtocol::new(reader);
     let page_header = ParquetPageHeader::stream_from_in_protocol(&mut prot).await?;
     Ok(page_header)
@@ -0,0 +1,517 @@
+use std::cmp::Ordering;
+
+use crate::error::{ParquetError, Result};
+use crate::metadata::{
+    get_column_sort_order, ColumnChunkMetaData, FileMetaData, RowGroupMetaData, SortOrd, SortOrder,
+};
+use crate::schema::types::PhysicalType;
+use crate::statistics::{
+    BinaryStatistics, BooleanStatistics, FixedLenStatistics, PrimitiveStatistics, Statistics,
+};
+
+/// A value compared with the values of a column by a [`Predicate`].
+///
+/// `Binary` values are compared with `BYTE_ARRAY` and `FIXED_LEN_BYTE_ARRAY` columns.
+/// Integers of unsigned logical types are their (wrapping) signed representation, e.g.
+/// `u32::MAX` is `Int32(-1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    Boolean(bool),
+    Int32(i32),
+    Int64(i64),
+    Float(f32),
+    Double(f64),
+    Binary(Vec<u8>),
+}
+
+/// A predicate over the values of the columns of a row group, each identified by its path
+/// in the schema (e.g. `["a", "b"]` for the field `b` of the group `a`).
+///
+/// Like in SQL, null values do not satisfy any comparison, nor its negation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    /// The value is equal to the scalar
+    Eq(Vec<String>, Scalar),
+    /// The value is lower than the scalar
+    Lt(Vec<String>, Scalar),
+    /// The value is greater than the scalar
+    Gt(Vec<String>, Scalar),
+    /// The value is between both scalars, inclusive
+    Between(Vec<String>, Scalar, Scalar),
+    /// The value is equal to one of the scalars
+    In(Vec<String>, Vec<Scalar>),
+    /// The value is null
+    IsNull(Vec<String>),
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+impl Predicate {
+    /// Returns the conjunction of this predicate and `other`.
+    pub fn and(self, other: Predicate) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    /// Returns the disjunction of this predicate and `other`.
+    pub fn or(self, other: Predicate) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Returns whether rows of `row_group` may satisfy this predicate, according to the
+    /// statistics of its column chunks. `false` guarantees that no row does.
+    /// # Error
+    /// Errors iff a column of the predicate does not exist or is repeated, a scalar does not
+    /// have the physical type of its column, or the statistics of a column cannot be decoded.
+    pub fn may_match(&self, row_group: &RowGroupMetaData) -> Result<bool> {
+        Ok(self.evaluate(row_group)? != Truth::Never)
+    }
+
+    fn evaluate(&self, row_group: &RowGroupMetaData) -> Result<Truth> {
+        Ok(match self {
+            Predicate::Eq(path, value) => evaluate_range(
+                column(row_group, path)?,
+                Some((value, true)),
+                Some((value, true)),
+            )?,
+            Predicate::Lt(path, value) => {
+                evaluate_range(column(row_group, path)?, None, Some((value, false)))?
+            }
+            Predicate::Gt(path, value) => {
+                evaluate_range(column(row_group, path)?, Some((value, false)), None)?
+            }
+            Predicate::Between(path, lower, upper) => evaluate_range(
+                column(row_group, path)?,
+                Some((lower, true)),
+                Some((upper, true)),
+            )?,
+            Predicate::In(path, values) => {
+                let column = column(row_group, path)?;
+                values.iter().try_fold(Truth::Never, |truth, value| {
+                    evaluate_range(column, Some((value, true)), Some((value, true)))
+                        .map(|other| truth.max(other))
+                })?
+            }
+            Predicate::IsNull(path) => evaluate_is_null(column(row_group, path)?)?,
+            Predicate::And(left, right) => {
+                left.evaluate(row_group)?.min(right.evaluate(row_group)?)
+            }
+            Predicate::Or(left, right) => left.evaluate(row_group)?.max(right.evaluate(row_group)?),
+            Predicate::Not(predicate) => predicate.evaluate(row_group)?.not(),
+        })
+    }
+}
+
+impl std::ops::Not for Predicate {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+}
+
+/// Returns the indexes of the row groups of `metadata` whose rows may satisfy `predicate`,
+/// according to the statistics of their column chunks.
+/// # Error
+/// Errors iff the predicate cannot be evaluated against a row group (see [`Predicate::may_match`]).
+pub fn select_row_groups(metadata: &FileMetaData, predicate: &Predicate) -> Result<Vec<usize>> {
+    metadata
+        .row_groups
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row_group)| match predicate.may_match(row_group) {
+            Ok(true) => Some(Ok(index)),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect()
+}
+
+/// Whether all, none or some of the rows of a row group satisfy a predicate. Ordered so that
+/// the conjunction is the minimum and the disjunction the maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Truth {
+    Never,
+    Maybe,
+    Always,
+}
+
+impl Truth {
+    fn not(self) -> Self {
+        match self {
+            Truth::Never => Truth::Always,
+            Truth::Maybe => Truth::Maybe,
+            Truth::Always => Truth::Never,
+        }
+    }
+}
+
+fn column<'a>(row_group: &'a RowGroupMetaData, path: &[String]) -> Result<&'a ColumnChunkMetaData> {
+    let column = row_group
+        .columns()
+        .iter()
+        .find(|column| column.descriptor().path_in_schema() == path)
+        .ok_or_else(|| general_err!("The column \"{}\" does not exist", path.join(".")))?;
+    if column.descriptor().max_rep_level() > 0 {
+        return Err(general_err!(
+            "Predicates on the repeated column \"{}\" are not supported",
+            path.join(".")
+        ));
+    }
+    Ok(column)
+}
+
+/// Returns the minimum and maximum values of `statistics`, if both are set.
+fn min_max(statistics: &dyn Statistics) -> Option<(Scalar, Scalar)> {
+    macro_rules! min_max {
+        ($statistics:ty, $scalar:expr) => {{
+            let statistics = statistics.as_any().downcast_ref::<$statistics>().unwrap();
+            statistics
+                .min_value
+                .clone()
+                .zip(statistics.max_value.clone())
+                .map(|(min, max)| ($scalar(min), $scalar(max)))
+        }};
+    }
+    match statistics.physical_type() {
+        PhysicalType::Boolean => min_max!(BooleanStatistics, Scalar::Boolean),
+        PhysicalType::Int32 => min_max!(PrimitiveStatistics<i32>, Scalar::Int32),
+        PhysicalType::Int64 => min_max!(PrimitiveStatistics<i64>, Scalar::Int64),
+        PhysicalType::Float => min_max!(PrimitiveStatistics<f32>, Scalar::Float),
+        PhysicalType::Double => min_max!(PrimitiveStatistics<f64>, Scalar::Double),
+        PhysicalType::ByteArray => min_max!(BinaryStatistics, Scalar::Binary),
+        PhysicalType::FixedLenByteArray(_) => min_max!(FixedLenStatistics, Scalar::Binary),
+        // the order of INT96 is undefined
+        PhysicalType::Int96 => None,
+    }
+}
+
+/// Compares two values of a column with the sort order `order`, or `None` if they are not
+/// comparable.
+fn compare(a: &Scalar, b: &Scalar, order: SortOrder) -> Option<Ordering> {
+    match (a, b) {
+        (Scalar::Boolean(a), Scalar::Boolean(b)) => a.sort_cmp(b, order),
+        (Scalar::Int32(a), Scalar::Int32(b)) => a.sort_cmp(b, order),
+        (Scalar::Int64(a), Scalar::Int64(b)) => a.sort_cmp(b, order),
+        // `None` for NaN, whose statistics are ignored
+        (Scalar::Float(a), Scalar::Float(b)) => a.sort_cmp(b, order),
+        (Scalar::Double(a), Scalar::Double(b)) => a.sort_cmp(b, order),
+        (Scalar::Binary(a), Scalar::Binary(b)) => a.sort_cmp(b, order),
+        _ => None,
+    }
+}
+
+fn check_type(column: &ColumnChunkMetaData, value: &Scalar) -> Result<()> {
+    let is_valid = matches!(
+        (value, column.descriptor().physical_type()),
+        (Scalar::Boolean(_), PhysicalType::Boolean)
+            | (Scalar::Int32(_), PhysicalType::Int32)
+            | (Scalar::Int64(_), PhysicalType::Int64)
+            | (Scalar::Float(_), PhysicalType::Float)
+            | (Scalar::Double(_), PhysicalType::Double)
+            | (
+                Scalar::Binary(_),
+                PhysicalType::ByteArray | PhysicalType::FixedLenByteArray(_)
+            )
+    );
+    if !is_valid {
+        return Err(general_err!(
+            "The column \"{}\" of physical type {:?} cannot be compared with {:?}",
+            column.descriptor().path_in_schema().join("."),
+            column.descriptor().physical_type(),
+            value
+        ));
+    }
+    Ok(())
+}
+
+/// Evaluates whether the values of `column` are within `lower` and `upper`, each a bound and
+/// whether it is inclusive.
+fn evaluate_range(
+    column: &ColumnChunkMetaData,
+    lower: Option<(&Scalar, bool)>,
+    upper: Option<(&Scalar, bool)>,
+) -> Result<Truth> {
+    lower
+        .iter()
+        .chain(upper.iter())
+        .try_for_each(|(value, _)| check_type(column, value))?;
+
+    let statistics = match column.statistics() {
+        Some(statistics) => statistics?,
+        None => return Ok(Truth::Maybe),
+    };
+    let null_count = statistics.null_count();
+    if null_count == Some(column.num_values()) {
+        // nulls do not satisfy comparisons
+        return Ok(Truth::Never);
+    }
+    let (min, max) = match min_max(statistics.as_ref()) {
+        Some(min_max) => min_max,
+        None => return Ok(Truth::Maybe),
+    };
+    let order = get_column_sort_order(column.descriptor());
+
+    // whether `value` satisfies the bound, `None` if unknown
+    let above = |value: &Scalar| {
+        lower.map_or(Some(true), |(lower, inclusive)| {
+            compare(value, lower, order)
+                .map(|ordering| ordering == Ordering::Greater || (inclusive && ordering.is_eq()))
+        })
+    };
+    let below = |value: &Scalar| {
+        upper.map_or(Some(true), |(upper, inclusive)| {
+            compare(value, upper, order)
+                .map(|ordering| ordering == Ordering::Less || (inclusive && ordering.is_eq()))
+        })
+    };
+
+    Ok(match (above(&min), below(&min), above(&max), below(&max)) {
+        (_, Some(false), _, _) | (_, _, Some(false), _) => Truth::Never,
+        (Some(true), _, _, Some(true)) if null_count == Some(0) => Truth::Always,
+        _ => Truth::Maybe,
+    })
+}
+
+fn evaluate_is_null(column: &ColumnChunkMetaData) -> Result<Truth> {
+    let null_count = match column.statistics() {
+        Some(statistics) => statistics?.null_count(),
+        None => None,
+    };
+    Ok(match null_count {
+        Some(0) => Truth::Never,
+        Some(null_count) if null_count == column.num_values() => Truth::Always,
+        _ => Truth::Maybe,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use parquet_format_async_temp::{ColumnChunk, ColumnMetaData, CompressionCodec};
+
+    use super::*;
+
+    use crate::metadata::SchemaDescriptor;
+    use crate::schema::types::physical_type_to_type;
+    use crate::statistics::{serialize_statistics, ParquetStatistics};
+
+    fn row_group(
+        schema: &SchemaDescriptor,
+        statistics: Vec<Option<ParquetStatistics>>,
+        num_rows: i64,
+    ) -> RowGroupMetaData {
+        let columns = schema
+            .columns()
+            .iter()
+            .zip(statistics)
+            .map(|(descriptor, statistics)| {
+                let metadata = ColumnMetaData::new(
+                    physical_type_to_type(descriptor.physical_type()).0,
+                    vec![],
+                    descriptor.path_in_schema().to_vec(),
+                    CompressionCodec::UNCOMPRESSED,
+                    num_rows,
+                    0,
+                    0,
+                    None,
+                    0,
+                    None,
+                    None,
+                    statistics,
+                    None,
+                    None,
+                );
+                let chunk = ColumnChunk::new(None, 0, metadata, None, None, None, None, None, None);
+                ColumnChunkMetaData::new(chunk, descriptor.clone())
+            })
+            .collect();
+        RowGroupMetaData::new(columns, num_rows, 0)
+    }
+
+    fn schema() -> SchemaDescriptor {
+        SchemaDescriptor::try_from_message(
+            "message schema {
+                OPTIONAL INT32 a;
+                OPTIONAL group b {
+                    REQUIRED INT32 c (UINT_32);
+                    OPTIONAL BYTE_ARRAY d (UTF8);
+                }
+                OPTIONAL FIXED_LEN_BYTE_ARRAY (2) e (DECIMAL(4, 2));
+                REPEATED INT64 f;
+            }",
+        )
+        .unwrap()
+    }
+
+    fn i32_stats(
+        schema: &SchemaDescriptor,
+        column: usize,
+        null_count: i64,
+        min_max: Option<(i32, i32)>,
+    ) -> Option<ParquetStatistics> {
+        Some(serialize_statistics(&PrimitiveStatistics::<i32> {
+            descriptor: schema.columns()[column].clone(),
+            null_count: Some(null_count),
+            distinct_count: None,
+            min_value: min_max.map(|x| x.0),
+            max_value: min_max.map(|x| x.1),
+        }))
+    }
+
+    fn fixture() -> (SchemaDescriptor, RowGroupMetaData) {
+        let schema = schema();
+        let statistics = vec![
+            i32_stats(&schema, 0, 1, Some((-10, 10))),
+            // [1, u32::MAX]
+            i32_stats(&schema, 1, 0, Some((1, -1))),
+            Some(serialize_statistics(&BinaryStatistics {
+                descriptor: schema.columns()[2].clone(),
+                null_count: Some(0),
+                distinct_count: None,
+                min_value: Some(b"b".to_vec()),
+                max_value: Some(b"d".to_vec()),
+            })),
+            // [-1.00, 2.56]
+            Some(ParquetStatistics {
+                null_count: Some(0),
+                distinct_count: None,
+                min_value: Some(vec![0xff, 0x9c]),
+                max_value: Some(vec![0x01, 0x00]),
+                min: None,
+                max: None,
+            }),
+            None,
+        ];
+        let row_group = row_group(&schema, statistics, 10);
+        (schema, row_group)
+    }
+
+    fn path(path: &str) -> Vec<String> {
+        path.split('.').map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn comparisons() -> Result<()> {
+        let (_, row_group) = fixture();
+        let a = || path("a");
+        let cases = [
+            (Predicate::Eq(a(), Scalar::Int32(0)), true),
+            (Predicate::Eq(a(), Scalar::Int32(11)), false),
+            (Predicate::Lt(a(), Scalar::Int32(-10)), false),
+            (Predicate::Lt(a(), Scalar::Int32(-9)), true),
+            (Predicate::Gt(a(), Scalar::Int32(10)), false),
+            (
+                Predicate::Between(a(), Scalar::Int32(10), Scalar::Int32(20)),
+                true,
+            ),
+            (
+                Predicate::Between(a(), Scalar::Int32(11), Scalar::Int32(20)),
+                false,
+            ),
+            (
+                Predicate::In(a(), vec![Scalar::Int32(-11), Scalar::Int32(11)]),
+                false,
+            ),
+            (
+                Predicate::In(a(), vec![Scalar::Int32(-11), Scalar::Int32(1)]),
+                true,
+            ),
+            (Predicate::IsNull(a()), true),
+            (Predicate::IsNull(path("b.c")), false),
+        ];
+        for (predicate, expected) in cases {
+            assert_eq!(
+                predicate.may_match(&row_group)?,
+                expected,
+                "{:?}",
+                predicate
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sort_orders() -> Result<()> {
+        let (_, row_group) = fixture();
+        let cases = [
+            // unsigned: -1 is u32::MAX
+            (Predicate::Gt(path("b.c"), Scalar::Int32(i32::MAX)), true),
+            (Predicate::Lt(path("b.c"), Scalar::Int32(1)), false),
+            (
+                Predicate::Eq(path("b.d"), Scalar::Binary(b"a".to_vec())),
+                false,
+            ),
+            (
+                Predicate::Eq(path("b.d"), Scalar::Binary(b"c".to_vec())),
+                true,
+            ),
+            // signed decimals: -1.00 and 2.56
+            (Predicate::Lt(path("e"), Scalar::Binary(vec![0x9c])), false),
+            (
+                Predicate::Lt(path("e"), Scalar::Binary(vec![0xff, 0x9d])),
+                true,
+            ),
+            (
+                Predicate::Gt(path("e"), Scalar::Binary(vec![0x01, 0x00])),
+                false,
+            ),
+        ];
+        for (predicate, expected) in cases {
+            assert_eq!(
+                predicate.may_match(&row_group)?,
+                expected,
+                "{:?}",
+                predicate
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn logical() -> Result<()> {
+        let (_, row_group) = fixture();
+        let c = Predicate::Gt(path("b.c"), Scalar::Int32(0));
+        let a = Predicate::Eq(path("a"), Scalar::Int32(20));
+        assert!(!(c.clone().and(a.clone()).may_match(&row_group)?));
+        assert!(c.clone().or(a.clone()).may_match(&row_group)?);
+        // all values of `c` are greater than 0
+        assert!(!(!c).may_match(&row_group)?);
+        // `a` has nulls, that do not satisfy `a != 20`
+        assert!((!a).may_match(&row_group)?);
+        let null = Predicate::IsNull(path("b.c"));
+        assert!((!null).may_match(&row_group)?);
+        Ok(())
+    }
+
+    #[test]
+    fn select() -> Result<()> {
+        let schema = schema();
+        let row_groups = [(0, 5), (6, 10), (11, 20)]
+            .iter()
+            .map(|min_max| {
+                let mut statistics = vec![i32_stats(&schema, 0, 0, Some(*min_max))];
+                statistics.extend((0..4).map(|_| None));
+                row_group(&schema, statistics, 10)
+            })
+            .collect::<Vec<_>>();
+        let metadata = FileMetaData::new(1, 30, None, row_groups, None, schema, None);
+
+        let predicate = Predicate::Between(path("a"), Scalar::Int32(5), Scalar::Int32(6))
+            .or(Predicate::Gt(path("a"), Scalar::Int32(100)));
+        assert_eq!(select_row_groups(&metadata, &predicate)?, vec![0, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        let (_, row_group) = fixture();
+        // a column that does not exist
+        assert!(Predicate::IsNull(path("b")).may_match(&row_group).is_err());
+        // a repeated column
+        assert!(Predicate::IsNull(path("f")).may_match(&row_group).is_err());
+        // a scalar of the wrong type
+        assert!(Predicate::Eq(path("a"), Scalar::Int64(1))
+            .may_match(&row_group)
+            .is_err());
+    }
+}
@@ -0,0 +1,486 @@
+use std::io::{Read, Seek};
+
+use crate::deserialize::{BinaryValues, BooleanValues, FixedLenValues, NativeValues};
+use crate::error::{ParquetError, Result};
+use crate::metadata::{ColumnDescriptor, FileMetaData};
+use crate::page::{CompressedDataPage, DataPage};
+use crate::schema::types::{ParquetType, PhysicalType};
+use crate::schema::Repetition;
+use crate::FallibleStreamingIterator;
+
+use super::{get_column_iterator, levels, BasicDecompressor, MutStreamingIterator, State};
+
+/// The value of a field of a [`Row`].
+///
+/// Repeated fields are a `List` of their elements. Groups annotated as `LIST` or `MAP` are
+/// also a `List` of their elements or key-value pairs, where the elements of a 3-level `LIST`
+/// are the values of its `element`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    Null,
+    Boolean(bool),
+    Int32(i32),
+    Int64(i64),
+    Int96([u32; 3]),
+    Float(f32),
+    Double(f64),
+    Binary(Vec<u8>),
+    List(Vec<Field>),
+    Group(Row),
+}
+
+/// A record, or a group of it: the name and the value of each of its fields.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Row {
+    fields: Vec<(String, Field)>,
+}
+
+impl Row {
+    pub fn new(fields: Vec<(String, Field)>) -> Self {
+        Self { fields }
+    }
+
+    /// The name and the value of each field of this row
+    pub fn fields(&self) -> &[(String, Field)] {
+        &self.fields
+    }
+
+    /// Returns the value of the field `name`, if it exists.
+    pub fn get(&self, name: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|(field_name, _)| field_name == name)
+            .map(|(_, field)| field)
+    }
+
+    /// The number of fields of this row
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Whether this row has no fields
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn into_fields(self) -> Vec<(String, Field)> {
+        self.fields
+    }
+}
+
+/// The decoded levels and values of a leaf column of a row group
+#[derive(Debug)]
+struct Leaf {
+    rep_levels: Vec<u32>,
+    def_levels: Vec<u32>,
+    values: std::vec::IntoIter<Field>,
+    // the index of the next levels
+    index: usize,
+}
+
+impl Leaf {
+    fn try_new<I>(pages: I, descriptor: &ColumnDescriptor) -> Result<Self>
+    where
+        I: Iterator<Item = Result<CompressedDataPage>>,
+    {
+        let mut rep_levels = vec![];
+        let mut def_levels = vec![];
+        let mut values = vec![];
+        let mut pages = BasicDecompressor::new(pages, vec![]);
+        while let Some(page) = pages.next()? {
+            let (page_rep_levels, page_def_levels) = levels::decode(page)?;
+            rep_levels.extend(page_rep_levels);
+            def_levels.extend(page_def_levels);
+            read_values(page, descriptor.physical_type(), &mut values)?;
+        }
+        Ok(Self {
+            rep_levels,
+            def_levels,
+            values: values.into_iter(),
+            index: 0,
+        })
+    }
+
+    /// Returns the repetition and definition levels of the next value, if any.
+    fn peek(&self) -> Option<(u32, u32)> {
+        self.rep_levels
+            .get(self.index)
+            .zip(self.def_levels.get(self.index))
+            .map(|(rep_level, def_level)| (*rep_level, *def_level))
+    }
+
+    /// Returns the definition level of the next value.
+    fn peek_def_level(&self) -> Result<u32> {
+        self.peek().map(|(_, def_level)| def_level).ok_or_else(|| {
+            ParquetError::OutOfSpec(
+                "The levels of a column ended before the records of its row group".to_string(),
+            )
+        })
+    }
+
+    /// Skips the levels of the next value, i.e. of a null value or an empty list.
+    fn skip(&mut self) -> Result<()> {
+        self.peek_def_level()?;
+        self.index += 1;
+        Ok(())
+    }
+
+    fn next_value(&mut self) -> Result<Field> {
+        self.skip()?;
+        self.values.next().ok_or_else(|| {
+            ParquetError::OutOfSpec(
+                "The values of a column ended before its definition levels".to_string(),
+            )
+        })
+    }
+}
+
+fn read_values(
+    page: &DataPage,
+    physical_type: &PhysicalType,
+    values: &mut Vec<Field>,
+) -> Result<()> {
+    match physical_type {
+        PhysicalType::Boolean => values.extend(BooleanValues::try_new(page)?.map(Field::Boolean)),
+        PhysicalType::Int32 => values.extend(NativeValues::try_new(page)?.map(Field::Int32)),
+        PhysicalType::Int64 => values.extend(NativeValues::try_new(page)?.map(Field::Int64)),
+        PhysicalType::Int96 => values.extend(NativeValues::try_new(page)?.map(Field::Int96)),
+        PhysicalType::Float => values.extend(NativeValues::try_new(page)?.map(Field::Float)),
+        PhysicalType::Double => values.extend(NativeValues::try_new(page)?.map(Field::Double)),
+        PhysicalType::ByteArray => values
+            .extend(BinaryValues::try_new(page)?.map(|value| Field::Binary(value.into_owned()))),
+        PhysicalType::FixedLenByteArray(_) => values
+            .extend(FixedLenValues::try_new(page)?.map(|value| Field::Binary(value.into_owned()))),
+    };
+    Ok(())
+}
+
+/// Assembles the value of `field` from `leaves`, the leaf columns of `field`.
+/// `def_level` and `depth` are the definition and repetition levels of its parent, and
+/// `is_list_element` whether `field` is the repeated field of a `LIST`.
+fn assemble_field(
+    field: &ParquetType,
+    leaves: &mut [Leaf],
+    def_level: u32,
+    depth: u32,
+    is_list_element: bool,
+) -> Result<Field> {
+    match field.get_basic_info().repetition() {
+        Repetition::Required => assemble_value(field, leaves, def_level, depth),
+        Repetition::Optional => {
+            if leaves[0].peek_def_level()? <= def_level {
+                leaves.iter_mut().try_for_each(|leaf| leaf.skip())?;
+                Ok(Field::Null)
+            } else {
+                assemble_value(field, leaves, def_level + 1, depth)
+            }
+        }
+        Repetition::Repeated => {
+            let mut items = vec![];
+            if leaves[0].peek_def_level()? <= def_level {
+                // an empty list
+                leaves.iter_mut().try_for_each(|leaf| leaf.skip())?;
+                return Ok(Field::List(items));
+            }
+            loop {
+                let item = match field {
+                    // the repeated group of a 3-level LIST only wraps its element
+                    ParquetType::GroupType { fields, .. }
+                        if is_list_element && fields.len() == 1 =>
+                    {
+                        assemble_field(&fields[0], leaves, def_level + 1, depth + 1, false)?
+                    }
+                    _ => assemble_value(field, leaves, def_level + 1, depth + 1)?,
+                };
+                items.push(item);
+                // the next value is another element of this list iff it repeats this field
+                match leaves[0].peek() {
+                    Some((rep_level, _)) if rep_level == depth + 1 => {}
+                    _ => break,
+                }
+            }
+            Ok(Field::List(items))
+        }
+    }
+}
+
+/// Assembles the non-null value of `type_` from `leaves`, the leaf columns of `type_`.
+fn assemble_value(
+    type_: &ParquetType,
+    leaves: &mut [Leaf],
+    def_level: u32,
+    depth: u32,
+) -> Result<Field> {
+    match type_ {
+        ParquetType::PrimitiveType { .. } => leaves[0].next_value(),
+        ParquetType::GroupType { fields, .. } => {
+            if (type_.is_list() || type_.is_map()) && fields.len() == 1 {
+                return assemble_field(&fields[0], leaves, def_level, depth, type_.is_list());
+            }
+            let mut leaves = leaves;
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    let (field_leaves, remaining) =
+                        std::mem::take(&mut leaves).split_at_mut(field.num_leaves());
+                    leaves = remaining;
+                    let value = assemble_field(field, field_leaves, def_level, depth, false)?;
+                    Ok((field.name().to_string(), value))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Field::Group(Row::new(fields)))
+        }
+    }
+}
+
+/// An [`Iterator`] of the records of a file, as [`Row`]s of the (projected) fields of its
+/// schema.
+///
+/// The leaf columns of the fields of a row group are read and decoded when the first of its
+/// rows is read, and assembled into a record on every call to `next`.
+#[derive(Debug)]
+pub struct RecordReader<R: Read + Seek> {
+    reader: R,
+    metadata: FileMetaData,
+    fields: Vec<usize>,
+    // the index of the next row group
+    row_group: usize,
+    // the number of remaining rows in the current row group
+    remaining: usize,
+    // the leaves of every projected field in the current row group
+    leaves: Vec<Vec<Leaf>>,
+}
+
+impl<R: Read + Seek> RecordReader<R> {
+    /// Returns a new [`RecordReader`] of the fields `fields` of the schema of `metadata`, or
+    /// of all of its fields if `None`.
+    /// # Error
+    /// Errors iff a field does not exist in the schema.
+    pub fn try_new(reader: R, metadata: FileMetaData, fields: Option<Vec<usize>>) -> Result<Self> {
+        let num_fields = metadata.schema().fields().len();
+        let fields = fields.unwrap_or_else(|| (0..num_fields).collect());
+        if let Some(field) = fields.iter().find(|field| **field >= num_fields) {
+            return Err(general_err!(
+                "The field {} does not exist in a schema of {} fields",
+                field,
+                num_fields
+            ));
+        }
+        Ok(Self {
+            reader,
+            metadata,
+            fields,
+            row_group: 0,
+            remaining: 0,
+            leaves: vec![],
+        })
+    }
+
+    /// Reads and decodes the leaves of the projected fields of the next row group.
+    fn read_row_group(&mut self) -> Result<()> {
+        let row_group = self.row_group;
+        self.row_group += 1;
+        let reader = &mut self.reader;
+        let metadata = &self.metadata;
+        self.leaves = self
+            .fields
+            .iter()
+            .map(|field| {
+                let mut leaves = vec![];
+                let mut columns =
+                    get_column_iterator(&mut *reader, metadata, row_group, *field, None, vec![]);
+                while let State::Some(mut new_iter) = columns.advance()? {
+                    if let Some((pages, column)) = new_iter.get() {
+                        leaves.push(Leaf::try_new(pages, column.descriptor())?);
+                    }
+                    columns = new_iter;
+                }
+                Ok(leaves)
+            })
+            .collect::<Result<_>>()?;
+        self.remaining = self.metadata.row_groups[row_group].num_rows() as usize;
+        Ok(())
+    }
+
+    fn next_row(&mut self) -> Result<Row> {
+        let schema_fields = self.metadata.schema().fields();
+        let fields = self
+            .fields
+            .iter()
+            .zip(self.leaves.iter_mut())
+            .map(|(field, leaves)| {
+                let field = &schema_fields[*field];
+                let value = assemble_field(field, leaves, 0, 0, false)?;
+                Ok((field.name().to_string(), value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.remaining -= 1;
+        Ok(Row::new(fields))
+    }
+}
+
+impl<R: Read + Seek> Iterator for RecordReader<R> {
+    type Item = Result<Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining == 0 {
+            if self.row_group == self.metadata.row_groups.len() {
+                return None;
+            }
+            if let Err(e) = self.read_row_group() {
+                // the reader ends on the first error
+                self.row_group = self.metadata.row_groups.len();
+                return Some(Err(e));
+            }
+        }
+        let row = self.next_row();
+        if row.is_err() {
+            self.row_group = self.metadata.row_groups.len();
+            self.remaining = 0;
+        }
+        Some(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    use crate::metadata::SchemaDescriptor;
+    use crate::read::read_metadata;
+    use crate::tests::options;
+    use crate::write::{
+        shred, Compressor, DynIter, DynStreamingIterator, FileWriter, Value, Version,
+    };
+
+    fn write(schema: &SchemaDescriptor, row_groups: &[Vec<Value>]) -> Result<Vec<u8>> {
+        let options = options(Version::V1);
+        let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+        writer.start()?;
+        for rows in row_groups {
+            let columns = shred(schema, rows)?.into_iter().map(|column| {
+                Ok(DynStreamingIterator::new(Compressor::new_from_vec(
+                    DynIter::new(std::iter::once(column.to_page(&options))),
+                    options.compression,
+                    vec![],
+                )))
+            });
+            writer.write(DynIter::new(columns), rows.len())?;
+        }
+        Ok(writer.end(None)?.1.into_inner())
+    }
+
+    fn schema() -> SchemaDescriptor {
+        SchemaDescriptor::try_from_message(
+            "message schema {
+                REQUIRED INT64 id;
+                OPTIONAL group items (LIST) {
+                    REPEATED group list {
+                        OPTIONAL group element {
+                            REQUIRED BYTE_ARRAY name;
+                            REPEATED INT32 tags;
+                        }
+                    }
+                }
+                OPTIONAL BOOLEAN flag;
+            }",
+        )
+        .unwrap()
+    }
+
+    fn rows() -> Vec<(Value, Row)> {
+        let item = |name: &str, tags: &[i32]| {
+            (
+                Value::Group(vec![
+                    Value::Binary(name.as_bytes().to_vec()),
+                    Value::List(tags.iter().copied().map(Value::Int32).collect()),
+                ]),
+                Field::Group(Row::new(vec![
+                    ("name".to_string(), Field::Binary(name.as_bytes().to_vec())),
+                    (
+                        "tags".to_string(),
+                        Field::List(tags.iter().copied().map(Field::Int32).collect()),
+                    ),
+                ])),
+            )
+        };
+        let row = |id: i64, items: Option<Vec<(Value, Field)>>, flag: Option<bool>| {
+            let (values, fields) = match items {
+                Some(items) => {
+                    let (values, fields) = items.into_iter().unzip();
+                    (Value::List(values), Field::List(fields))
+                }
+                None => (Value::Null, Field::Null),
+            };
+            (
+                Value::Group(vec![
+                    Value::Int64(id),
+                    values,
+                    flag.map_or(Value::Null, Value::Boolean),
+                ]),
+                Row::new(vec![
+                    ("id".to_string(), Field::Int64(id)),
+                    ("items".to_string(), fields),
+                    ("flag".to_string(), flag.map_or(Field::Null, Field::Boolean)),
+                ]),
+            )
+        };
+        vec![
+            row(
+                1,
+                Some(vec![
+                    item("a", &[1, 2]),
+                    (Value::Null, Field::Null),
+                    item("b", &[]),
+                ]),
+                Some(true),
+            ),
+            row(2, None, None),
+            row(3, Some(vec![]), Some(false)),
+            row(4, Some(vec![item("c", &[3])]), None),
+        ]
+    }
+
+    #[test]
+    fn records() -> Result<()> {
+        let schema = schema();
+        let (values, expected): (Vec<_>, Vec<_>) = rows().into_iter().unzip();
+        // two row groups
+        let data = write(&schema, &[values[..3].to_vec(), values[3..].to_vec()])?;
+
+        let mut reader = Cursor::new(data);
+        let metadata = read_metadata(&mut reader)?;
+        let rows = RecordReader::try_new(reader, metadata, None)?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(rows, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn projection() -> Result<()> {
+        let schema = schema();
+        let (values, expected): (Vec<_>, Vec<_>) = rows().into_iter().unzip();
+        let data = write(&schema, &[values])?;
+
+        let mut reader = Cursor::new(data);
+        let metadata = read_metadata(&mut reader)?;
+        let rows = RecordReader::try_new(reader, metadata, Some(vec![2, 0]))?
+            .collect::<Result<Vec<_>>>()?;
+        let expected = expected
+            .into_iter()
+            .map(|row| {
+                Row::new(vec![
+                    ("flag".to_string(), row.get("flag").unwrap().clone()),
+                    ("id".to_string(), row.get("id").unwrap().clone()),
+                ])
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rows, expected);
+
+        let mut reader = Cursor::new(write(&schema, &[])?);
+        let metadata = read_metadata(&mut reader)?;
+        assert!(RecordReader::try_new(reader, metadata, Some(vec![3])).is_err());
+        Ok(())
+    }
+}
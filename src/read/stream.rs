@@ -1,14 +1,15 @@
 use std::io::SeekFrom;
 
-use futures::{io::Cursor, AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
-use parquet_format_async_temp::thrift::protocol::TCompactInputStreamProtocol;
-use parquet_format_async_temp::FileMetaData as TFileMetaData;
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 
-use super::super::{metadata::*, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, PARQUET_MAGIC};
-use super::metadata::{metadata_len, parse_column_orders};
+use super::super::{metadata::FileMetaData, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE};
+use super::metadata::{
+    deserialize_metadata, deserialize_metadata_with_decryption, footer_len, is_encrypted_footer,
+};
+use crate::encryption::FileDecryptionProperties;
 use crate::error::{ParquetError, Result};
 
-async fn stream_len(
+pub(super) async fn stream_len(
     seek: &mut (impl AsyncSeek + std::marker::Unpin),
 ) -> std::result::Result<u64, std::io::Error> {
     let old_pos = seek.seek(SeekFrom::Current(0)).await?;
@@ -23,9 +24,10 @@ async fn stream_len(
     Ok(len)
 }
 
-pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+/// Reads the (possibly encrypted) metadata of a file and whether it is encrypted.
+async fn read_footer<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
     reader: &mut R,
-) -> Result<FileMetaData> {
+) -> Result<(Vec<u8>, bool)> {
     // check file is large enough to hold footer
     let file_size = stream_len(reader).await?;
     if file_size < FOOTER_SIZE {
@@ -43,66 +45,40 @@ pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>
     reader.read_exact(&mut default_len_end_buf).await?;
 
     // check this is indeed a parquet file
-    if default_len_end_buf[default_end_len - 4..] != PARQUET_MAGIC {
-        return Err(general_err!("Invalid Parquet file. Corrupt footer"));
-    }
+    let is_encrypted = is_encrypted_footer(&default_len_end_buf)?;
 
-    let metadata_len = metadata_len(&default_len_end_buf, default_end_len);
-
-    if metadata_len < 0 {
-        return Err(general_err!(
-            "Invalid Parquet file. Metadata length is less than zero ({})",
-            metadata_len
-        ));
-    }
-    let footer_metadata_len = FOOTER_SIZE + metadata_len as u64;
+    let footer_metadata_len = footer_len(&default_len_end_buf, file_size)?;
+    let metadata_len = (footer_metadata_len - FOOTER_SIZE) as usize;
 
-    let t_file_metadata = if footer_metadata_len > file_size {
-        return Err(general_err!(
-            "Invalid Parquet file. Metadata start is less than zero ({})",
-            file_size as i64 - footer_metadata_len as i64
-        ));
-    } else if footer_metadata_len < DEFAULT_FOOTER_READ_SIZE {
+    let buffer = if footer_metadata_len < DEFAULT_FOOTER_READ_SIZE {
         // the whole metadata is in the bytes we already read
-        // build up the reader covering the entire metadata
-        let mut reader = Cursor::new(default_len_end_buf);
-        reader
-            .seek(SeekFrom::End(-(footer_metadata_len as i64)))
-            .await?;
-
-        let mut prot = TCompactInputStreamProtocol::new(reader);
-        TFileMetaData::stream_from_in_protocol(&mut prot).await?
+        let start = default_end_len - footer_metadata_len as usize;
+        default_len_end_buf[start..start + metadata_len].to_vec()
     } else {
         // the end of file read by default is not long enough, read again including all metadata.
         reader
             .seek(SeekFrom::End(-(footer_metadata_len as i64)))
             .await?;
-
-        let mut prot = TCompactInputStreamProtocol::new(reader);
-        TFileMetaData::stream_from_in_protocol(&mut prot).await?
+        let mut buffer = vec![0; metadata_len];
+        reader.read_exact(&mut buffer).await?;
+        buffer
     };
+    Ok((buffer, is_encrypted))
+}
 
-    let schema = t_file_metadata.schema.iter().collect::<Vec<_>>();
-    let schema_descr = SchemaDescriptor::try_from_thrift(&schema)?;
-
-    let row_groups = t_file_metadata
-        .row_groups
-        .into_iter()
-        .map(|rg| RowGroupMetaData::try_from_thrift(&schema_descr, rg))
-        .collect::<Result<Vec<_>>>()?;
-
-    // compute and cache column orders
-    let column_orders = t_file_metadata
-        .column_orders
-        .map(|orders| parse_column_orders(&orders, &schema_descr));
+/// Async version of [`super::read_metadata`].
+pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+    reader: &mut R,
+) -> Result<FileMetaData> {
+    let (buffer, is_encrypted) = read_footer(reader).await?;
+    deserialize_metadata(&buffer, is_encrypted)
+}
 
-    Ok(FileMetaData::new(
-        t_file_metadata.version,
-        t_file_metadata.num_rows,
-        t_file_metadata.created_by,
-        row_groups,
-        t_file_metadata.key_value_metadata,
-        schema_descr,
-        column_orders,
-    ))
+/// Async version of [`super::read_metadata_with_decryption`].
+pub async fn read_metadata_with_decryption<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+    reader: &mut R,
+    properties: &FileDecryptionProperties,
+) -> Result<FileMetaData> {
+    let (buffer, is_encrypted) = read_footer(reader).await?;
+    deserialize_metadata_with_decryption(&buffer, is_encrypted, properties)
 }
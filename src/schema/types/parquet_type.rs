@@ -92,6 +92,42 @@ impl ParquetType {
             _ => false,
         }
     }
+
+    /// Returns the number of leaves (i.e. columns) of this type.
+    pub(crate) fn num_leaves(&self) -> usize {
+        match self {
+            Self::PrimitiveType { .. } => 1,
+            Self::GroupType { fields, .. } => fields.iter().map(|field| field.num_leaves()).sum(),
+        }
+    }
+
+    /// Whether this type is a group annotated as `LIST`.
+    pub(crate) fn is_list(&self) -> bool {
+        matches!(
+            self,
+            Self::GroupType {
+                converted_type: Some(GroupConvertedType::List),
+                ..
+            } | Self::GroupType {
+                logical_type: Some(LogicalType::LIST(_)),
+                ..
+            }
+        )
+    }
+
+    /// Whether this type is a group annotated as `MAP`.
+    pub(crate) fn is_map(&self) -> bool {
+        matches!(
+            self,
+            Self::GroupType {
+                converted_type: Some(GroupConvertedType::Map),
+                ..
+            } | Self::GroupType {
+                logical_type: Some(LogicalType::MAP(_)),
+                ..
+            }
+        )
+    }
 }
 
 /// Constructors
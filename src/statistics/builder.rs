@@ -0,0 +1,234 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+use parquet_format_async_temp::Statistics as ParquetStatistics;
+
+use crate::metadata::{get_column_sort_order, ColumnDescriptor, SortOrd, SortOrder};
+use crate::types::NativeType;
+
+/// The null count and the bounds of the values pushed to a builder, with the sort order of
+/// its column.
+#[derive(Debug, Clone)]
+struct Bounds<T> {
+    order: SortOrder,
+    null_count: i64,
+    min_value: Option<T>,
+    max_value: Option<T>,
+}
+
+impl<T> Bounds<T> {
+    fn new(descriptor: &ColumnDescriptor) -> Self {
+        Self {
+            order: get_column_sort_order(descriptor),
+            null_count: 0,
+            min_value: None,
+            max_value: None,
+        }
+    }
+
+    fn push<B>(&mut self, value: Option<&B>)
+    where
+        B: SortOrd + ToOwned<Owned = T> + ?Sized,
+        T: Borrow<B>,
+    {
+        let value = match value {
+            Some(value) => value,
+            None => {
+                self.null_count += 1;
+                return;
+            }
+        };
+        // values that cannot be compared (e.g. NaN or of an undefined order) are ignored
+        if value.sort_cmp(value, self.order).is_none() {
+            return;
+        }
+        let order = self.order;
+        let is = |bound: &Option<T>, ordering| !matches!(bound, Some(bound) if value.sort_cmp(bound.borrow(), order) != Some(ordering));
+        if is(&self.min_value, Ordering::Less) {
+            self.min_value = Some(value.to_owned());
+        }
+        if is(&self.max_value, Ordering::Greater) {
+            self.max_value = Some(value.to_owned());
+        }
+    }
+
+    fn finish(self, encode: impl Fn(T) -> Vec<u8>) -> ParquetStatistics
+    where
+        T: SortOrd,
+    {
+        ParquetStatistics {
+            null_count: Some(self.null_count),
+            distinct_count: None,
+            max_value: self.max_value.map(SortOrd::max_bound).map(&encode),
+            min_value: self.min_value.map(SortOrd::min_bound).map(&encode),
+            min: None,
+            max: None,
+        }
+    }
+}
+
+/// Builder of the statistics of the values of a column whose physical type is represented by
+/// a [`NativeType`], ordered with the sort order of the column.
+#[derive(Debug, Clone)]
+pub struct PrimitiveStatisticsBuilder<T: NativeType> {
+    bounds: Bounds<T>,
+}
+
+impl<T> PrimitiveStatisticsBuilder<T>
+where
+    T: NativeType + SortOrd,
+{
+    /// Returns a new [`PrimitiveStatisticsBuilder`] of the column `descriptor`.
+    pub fn new(descriptor: &ColumnDescriptor) -> Self {
+        Self {
+            bounds: Bounds::new(descriptor),
+        }
+    }
+
+    /// Updates the statistics with `value`, or a null value.
+    pub fn push(&mut self, value: Option<T>) {
+        self.bounds.push(value.as_ref())
+    }
+
+    /// Updates the statistics with every value of `values`.
+    pub fn extend<I: IntoIterator<Item = Option<T>>>(&mut self, values: I) {
+        values.into_iter().for_each(|value| self.push(value))
+    }
+
+    /// Returns the statistics of the values pushed so far, as written in page headers.
+    pub fn finish(self) -> ParquetStatistics {
+        self.bounds.finish(|x| x.to_le_bytes().as_ref().to_vec())
+    }
+}
+
+/// Builder of the statistics of the values of a `BOOLEAN` column.
+#[derive(Debug, Clone)]
+pub struct BooleanStatisticsBuilder {
+    bounds: Bounds<bool>,
+}
+
+impl BooleanStatisticsBuilder {
+    /// Returns a new [`BooleanStatisticsBuilder`] of the column `descriptor`.
+    pub fn new(descriptor: &ColumnDescriptor) -> Self {
+        Self {
+            bounds: Bounds::new(descriptor),
+        }
+    }
+
+    /// Updates the statistics with `value`, or a null value.
+    pub fn push(&mut self, value: Option<bool>) {
+        self.bounds.push(value.as_ref())
+    }
+
+    /// Updates the statistics with every value of `values`.
+    pub fn extend<I: IntoIterator<Item = Option<bool>>>(&mut self, values: I) {
+        values.into_iter().for_each(|value| self.push(value))
+    }
+
+    /// Returns the statistics of the values pushed so far, as written in page headers.
+    pub fn finish(self) -> ParquetStatistics {
+        self.bounds.finish(|x| vec![x as u8])
+    }
+}
+
+macro_rules! binary_builder {
+    ($name:ident, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            bounds: Bounds<Vec<u8>>,
+        }
+
+        impl $name {
+            #[doc = concat!("Returns a new [`", stringify!($name), "`] of the column `descriptor`.")]
+            pub fn new(descriptor: &ColumnDescriptor) -> Self {
+                Self {
+                    bounds: Bounds::new(descriptor),
+                }
+            }
+
+            /// Updates the statistics with `value`, or a null value.
+            pub fn push(&mut self, value: Option<&[u8]>) {
+                self.bounds.push(value)
+            }
+
+            /// Updates the statistics with every value of `values`.
+            pub fn extend<'a, I: IntoIterator<Item = Option<&'a [u8]>>>(&mut self, values: I) {
+                values.into_iter().for_each(|value| self.push(value))
+            }
+
+            /// Returns the statistics of the values pushed so far, as written in page headers.
+            pub fn finish(self) -> ParquetStatistics {
+                self.bounds.finish(|x| x)
+            }
+        }
+    };
+}
+
+binary_builder!(
+    BinaryStatisticsBuilder,
+    "Builder of the statistics of the values of a `BYTE_ARRAY` column, ordered with the sort order of the column."
+);
+binary_builder!(
+    FixedLenStatisticsBuilder,
+    "Builder of the statistics of the values of a `FIXED_LEN_BYTE_ARRAY` column, ordered with the sort order of the column."
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::descriptor;
+
+    #[test]
+    fn primitive() {
+        let mut builder = PrimitiveStatisticsBuilder::<f64>::new(&descriptor("OPTIONAL DOUBLE a"));
+        builder.extend([Some(1.0), None, Some(f64::NAN), Some(-2.0), None]);
+        let statistics = builder.finish();
+        assert_eq!(statistics.null_count, Some(2));
+        assert_eq!(statistics.min_value, Some((-2.0f64).to_le_bytes().to_vec()));
+        assert_eq!(statistics.max_value, Some(1.0f64.to_le_bytes().to_vec()));
+
+        // the minimum of zeros is -0.0 and their maximum +0.0
+        let mut builder = PrimitiveStatisticsBuilder::<f32>::new(&descriptor("REQUIRED FLOAT a"));
+        builder.extend([Some(0.0), Some(0.0)]);
+        let statistics = builder.finish();
+        assert_eq!(statistics.min_value, Some((-0.0f32).to_le_bytes().to_vec()));
+        assert_eq!(statistics.max_value, Some(0.0f32.to_le_bytes().to_vec()));
+
+        let mut builder =
+            PrimitiveStatisticsBuilder::<i32>::new(&descriptor("REQUIRED INT32 a (UINT_32)"));
+        builder.extend([Some(-1), Some(1)]);
+        let statistics = builder.finish();
+        assert_eq!(statistics.min_value, Some(1i32.to_le_bytes().to_vec()));
+        assert_eq!(statistics.max_value, Some((-1i32).to_le_bytes().to_vec()));
+    }
+
+    #[test]
+    fn boolean() {
+        let mut builder = BooleanStatisticsBuilder::new(&descriptor("OPTIONAL BOOLEAN a"));
+        builder.extend([Some(true), None, Some(true)]);
+        let statistics = builder.finish();
+        assert_eq!(statistics.null_count, Some(1));
+        assert_eq!(statistics.min_value, Some(vec![1]));
+        assert_eq!(statistics.max_value, Some(vec![1]));
+    }
+
+    #[test]
+    fn binary() {
+        let mut builder = BinaryStatisticsBuilder::new(&descriptor("OPTIONAL BYTE_ARRAY a"));
+        builder.extend([Some(b"ab".as_ref()), Some(b"abc"), None, Some(b"a")]);
+        let statistics = builder.finish();
+        assert_eq!(statistics.min_value, Some(b"a".to_vec()));
+        assert_eq!(statistics.max_value, Some(b"abc".to_vec()));
+
+        // decimals are compared as signed integers
+        let mut builder = FixedLenStatisticsBuilder::new(&descriptor(
+            "REQUIRED FIXED_LEN_BYTE_ARRAY (1) a (DECIMAL(2, 0))",
+        ));
+        builder.extend([Some([0x01].as_ref()), Some(&[0xFF]), Some(&[0x00])]);
+        let statistics = builder.finish();
+        assert_eq!(statistics.min_value, Some(vec![0xFF]));
+        assert_eq!(statistics.max_value, Some(vec![0x01]));
+    }
+}
@@ -0,0 +1,104 @@
+/// The number of bits of a hash that select its register
+const PRECISION: u32 = 12;
+
+/// The number of registers of a sketch
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// A HyperLogLog sketch of the (xxh64) hashes of the values of a page, to estimate their number
+/// of distinct values. The sketches of the pages of a column chunk are merged into the estimated
+/// `distinct_count` of its statistics.
+///
+/// The hashes are those of bloom filters, see [`crate::bloom_filter::hash_native`] and
+/// [`crate::bloom_filter::hash_byte`]. The standard error of the estimate is about 1.6%.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    // the maximum rank of the hashes of every register
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperLogLog {
+    /// Returns a new, empty, [`HyperLogLog`].
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS],
+        }
+    }
+
+    /// Inserts the hash of a value into the sketch.
+    pub fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // the position of the first set bit of the remaining bits, capped by a guard bit
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank);
+    }
+
+    /// Merges `other` into this sketch, that then estimates the distinct values of both.
+    pub fn merge(&mut self, other: &Self) {
+        self.registers
+            .iter_mut()
+            .zip(other.registers.iter())
+            .for_each(|(register, other)| *register = (*register).max(*other));
+    }
+
+    /// Returns the estimated number of distinct values inserted into this sketch.
+    pub fn estimate(&self) -> u64 {
+        let m = NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum::<f64>();
+        let estimate = alpha * m * m / sum;
+
+        let num_zeros = self.registers.iter().filter(|x| **x == 0).count();
+        if estimate <= 2.5 * m && num_zeros > 0 {
+            // small range correction: linear counting
+            (m * (m / num_zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bloom_filter::hash_native;
+
+    fn sketch(values: std::ops::Range<i64>) -> HyperLogLog {
+        let mut sketch = HyperLogLog::new();
+        values.for_each(|x| sketch.insert(hash_native(x)));
+        sketch
+    }
+
+    fn assert_close(estimate: u64, expected: u64) {
+        let error = (estimate as f64 - expected as f64).abs() / expected as f64;
+        assert!(error < 0.05, "{} is not close to {}", estimate, expected);
+    }
+
+    #[test]
+    fn estimate() {
+        assert_eq!(HyperLogLog::new().estimate(), 0);
+        assert_eq!(sketch(0..1).estimate(), 1);
+        assert_close(sketch(0..1000).estimate(), 1000);
+        assert_close(sketch(0..100_000).estimate(), 100_000);
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = sketch(0..60_000);
+        // overlapping values are counted once
+        a.merge(&sketch(40_000..100_000));
+        assert_close(a.estimate(), 100_000);
+        assert_eq!(a, sketch(0..100_000));
+    }
+}
@@ -1,6 +1,8 @@
 mod binary;
 mod boolean;
+mod builder;
 mod fixed_len_binary;
+mod hll;
 mod primitive;
 
 use std::{any::Any, sync::Arc};
@@ -13,7 +15,12 @@ use crate::schema::types::PhysicalType;
 
 pub use binary::BinaryStatistics;
 pub use boolean::BooleanStatistics;
+pub use builder::{
+    BinaryStatisticsBuilder, BooleanStatisticsBuilder, FixedLenStatisticsBuilder,
+    PrimitiveStatisticsBuilder,
+};
 pub use fixed_len_binary::FixedLenStatistics;
+pub use hll::HyperLogLog;
 pub use primitive::PrimitiveStatistics;
 
 /// A trait used to describe specific statistics. Each physical type has its own struct.
@@ -95,11 +95,21 @@ impl NativeType for [u32; 3] {
 
     #[inline]
     fn ord(&self, other: &Self) -> std::cmp::Ordering {
-        int96_to_i64_ns(*self).ord(&int96_to_i64_ns(*other))
+        int96_cmp(*self, *other)
     }
 }
 
-pub fn int96_to_i64_ns(value: [u32; 3]) -> i64 {
+/// Compares two legacy (INT96) timestamps by the instant they represent, i.e. by their julian
+/// day and then by their nanoseconds within that day. Unlike [`int96_to_i64_ns`], this is
+/// exact for every date.
+pub fn int96_cmp(a: [u32; 3], b: [u32; 3]) -> std::cmp::Ordering {
+    let nanoseconds = |value: [u32; 3]| ((value[1] as u64) << 32) | value[0] as u64;
+    (a[2], nanoseconds(a)).cmp(&(b[2], nanoseconds(b)))
+}
+
+/// Returns the nanoseconds since the epoch of a legacy (INT96) timestamp, or `None` if it is
+/// outside of the range of `i64` nanoseconds (about 1677-2262).
+pub fn int96_to_i64_ns(value: [u32; 3]) -> Option<i64> {
     const JULIAN_DAY_OF_EPOCH: i64 = 2_440_588;
     const SECONDS_PER_DAY: i64 = 86_400;
     const NANOS_PER_SECOND: i64 = 1_000_000_000;
@@ -108,7 +118,9 @@ pub fn int96_to_i64_ns(value: [u32; 3]) -> i64 {
     let nanoseconds = ((value[1] as i64) << 32) + value[0] as i64;
     let seconds = (day - JULIAN_DAY_OF_EPOCH) * SECONDS_PER_DAY;
 
-    seconds * NANOS_PER_SECOND + nanoseconds
+    seconds
+        .checked_mul(NANOS_PER_SECOND)?
+        .checked_add(nanoseconds)
 }
 
 #[inline]
@@ -0,0 +1,227 @@
+use std::io::Write;
+
+use futures::{AsyncWrite, AsyncWriteExt};
+use parquet_format_async_temp::thrift::protocol::{
+    TCompactOutputProtocol, TCompactOutputStreamProtocol, TOutputProtocol, TOutputStreamProtocol,
+};
+use parquet_format_async_temp::{
+    BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash, BloomFilterHeader,
+    SplitBlockAlgorithm, Uncompressed, XxHash,
+};
+
+use crate::bloom_filter::{hash_byte, optimal_num_of_bytes, BloomFilter};
+use crate::compression::{self, Compression};
+use crate::encoding::{plain_byte_array, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::{split_buffer_v1, split_buffer_v2, CompressedPage, DataPageHeader};
+use crate::schema::types::PhysicalType;
+
+/// Options to build the bloom filter of a column chunk.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BloomFilterOptions {
+    /// The expected number of distinct values of the column chunk
+    pub ndv: usize,
+    /// The target false positive probability, in `(0, 1)`
+    pub fpp: f64,
+}
+
+impl BloomFilterOptions {
+    /// Returns a new empty [`BloomFilter`] sized according to these options.
+    fn new_filter(&self) -> Result<BloomFilter> {
+        if !(self.fpp > 0.0 && self.fpp < 1.0) {
+            return Err(general_err!(
+                "The false positive probability of a bloom filter must be in (0, 1), but it is {}",
+                self.fpp
+            ));
+        }
+        Ok(BloomFilter::new(optimal_num_of_bytes(self.ndv, self.fpp)))
+    }
+}
+
+/// Builds a [`BloomFilter`] from the values of the pages of a column chunk, as they are written.
+///
+/// The pages are compressed by then: each page is decompressed into `scratch` and its values
+/// decoded again, which costs as much as reading the column chunk back.
+pub(super) struct BloomFilterBuilder<'a> {
+    filter: BloomFilter,
+    descriptor: &'a ColumnDescriptor,
+    scratch: Vec<u8>,
+}
+
+impl<'a> BloomFilterBuilder<'a> {
+    pub fn try_new(options: &BloomFilterOptions, descriptor: &'a ColumnDescriptor) -> Result<Self> {
+        if descriptor.physical_type() == &PhysicalType::Boolean {
+            return Err(general_err!(
+                "Bloom filters are not supported for boolean columns (column \"{}\")",
+                descriptor.path_in_schema().join(".")
+            ));
+        }
+        Ok(Self {
+            filter: options.new_filter()?,
+            descriptor,
+            scratch: vec![],
+        })
+    }
+
+    /// Inserts the values of `page`, compressed with `compression`, in the filter.
+    ///
+    /// Values of dictionary-encoded data pages are inserted from their dictionary page.
+    pub fn update(&mut self, page: &CompressedPage, compression: Compression) -> Result<()> {
+        match page {
+            CompressedPage::Dict(page) => {
+                let values = decompress(
+                    &page.buffer,
+                    page.uncompressed_page_size,
+                    compression,
+                    &mut self.scratch,
+                )?;
+                insert_plain(
+                    &mut self.filter,
+                    values,
+                    page.num_values,
+                    self.descriptor.physical_type(),
+                );
+            }
+            CompressedPage::Data(page) => {
+                match page.encoding() {
+                    Encoding::PlainDictionary | Encoding::RleDictionary => {}
+                    Encoding::Plain => {
+                        let values = match page.header() {
+                            DataPageHeader::V1(_) => {
+                                let buffer = decompress(
+                                    &page.buffer,
+                                    page.uncompressed_size(),
+                                    compression,
+                                    &mut self.scratch,
+                                )?;
+                                split_buffer_v1(
+                                    buffer,
+                                    self.descriptor.max_rep_level() > 0,
+                                    self.descriptor.max_def_level() > 0,
+                                )?
+                                .2
+                            }
+                            DataPageHeader::V2(header) => {
+                                let levels_length = (header.repetition_levels_byte_length
+                                    + header.definition_levels_byte_length)
+                                    as usize;
+                                let (_, _, values) = split_buffer_v2(
+                                    &page.buffer,
+                                    header.repetition_levels_byte_length as usize,
+                                    header.definition_levels_byte_length as usize,
+                                )?;
+                                if header.is_compressed.unwrap_or(true) {
+                                    decompress(
+                                        values,
+                                        page.uncompressed_size() - levels_length,
+                                        compression,
+                                        &mut self.scratch,
+                                    )?
+                                } else {
+                                    values
+                                }
+                            }
+                        };
+                        insert_plain(
+                            &mut self.filter,
+                            values,
+                            page.num_values(),
+                            self.descriptor.physical_type(),
+                        );
+                    }
+                    other => {
+                        return Err(general_err!(
+                            "Bloom filters can only be built from plain or dictionary-encoded pages, but column \"{}\" has a page encoded with {:?}",
+                            self.descriptor.path_in_schema().join("."),
+                            other
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> BloomFilter {
+        self.filter
+    }
+}
+
+/// Returns the uncompressed `buffer`, using `scratch` as decompression buffer if needed.
+fn decompress<'a>(
+    buffer: &'a [u8],
+    uncompressed_size: usize,
+    compression: Compression,
+    scratch: &'a mut Vec<u8>,
+) -> Result<&'a [u8]> {
+    if compression == Compression::Uncompressed {
+        return Ok(buffer);
+    }
+    scratch.clear();
+    scratch.resize(uncompressed_size, 0);
+    compression::decompress(compression, buffer, scratch)?;
+    Ok(scratch)
+}
+
+/// Inserts plain-encoded `values` of `physical_type` into `filter`.
+/// `num_values` is an upper bound of the number of values (it may include nulls).
+fn insert_plain(
+    filter: &mut BloomFilter,
+    values: &[u8],
+    num_values: usize,
+    physical_type: &PhysicalType,
+) {
+    let size = match physical_type {
+        PhysicalType::Boolean => unreachable!(),
+        PhysicalType::ByteArray => {
+            plain_byte_array::Decoder::new(values, num_values)
+                .for_each(|value| filter.insert(hash_byte(value)));
+            return;
+        }
+        PhysicalType::Int32 | PhysicalType::Float => 4,
+        PhysicalType::Int64 | PhysicalType::Double => 8,
+        PhysicalType::Int96 => 12,
+        PhysicalType::FixedLenByteArray(size) => *size as usize,
+    };
+    // the hash of a native type is the hash of its plain encoding
+    values
+        .chunks_exact(size)
+        .for_each(|value| filter.insert(hash_byte(value)));
+}
+
+fn bloom_filter_header(filter: &BloomFilter) -> BloomFilterHeader {
+    BloomFilterHeader {
+        num_bytes: filter.bitset().len() as i32,
+        algorithm: BloomFilterAlgorithm::BLOCK(SplitBlockAlgorithm::new()),
+        hash: BloomFilterHash::XXHASH(XxHash::new()),
+        compression: BloomFilterCompression::UNCOMPRESSED(Uncompressed::new()),
+    }
+}
+
+/// Writes `filter` (header and bitset) to `writer`, returning the number of bytes written.
+pub(super) fn write_bloom_filter<W: Write>(writer: &mut W, filter: &BloomFilter) -> Result<u64> {
+    let header = bloom_filter_header(filter);
+
+    let mut protocol = TCompactOutputProtocol::new(&mut *writer);
+    let header_size = header.write_to_out_protocol(&mut protocol)? as u64;
+    protocol.flush()?;
+
+    writer.write_all(filter.bitset())?;
+    Ok(header_size + filter.bitset().len() as u64)
+}
+
+/// Async version of [`write_bloom_filter`].
+pub(super) async fn write_bloom_filter_async<W: AsyncWrite + Unpin + Send>(
+    writer: &mut W,
+    filter: &BloomFilter,
+) -> Result<u64> {
+    let header = bloom_filter_header(filter);
+
+    let mut protocol = TCompactOutputStreamProtocol::new(&mut *writer);
+    let header_size = header.write_to_out_stream_protocol(&mut protocol).await? as u64;
+    protocol.flush().await?;
+
+    writer.write_all(filter.bitset()).await?;
+    Ok(header_size + filter.bitset().len() as u64)
+}
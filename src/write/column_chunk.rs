@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::io::Write;
@@ -8,28 +9,45 @@ use parquet_format_async_temp::thrift::protocol::{
 };
 use parquet_format_async_temp::{ColumnChunk, ColumnMetaData};
 
-use crate::statistics::serialize_statistics;
+use crate::statistics::{serialize_statistics, ParquetStatistics};
 use crate::FallibleStreamingIterator;
 use crate::{
     compression::Compression,
     encoding::Encoding,
+    encryption::ColumnEncryptor,
     error::{ParquetError, Result},
     metadata::ColumnDescriptor,
     page::{CompressedPage, PageType},
     schema::types::{physical_type_to_type, ParquetType},
 };
 
+use super::bloom_filter::{
+    write_bloom_filter, write_bloom_filter_async, BloomFilterBuilder, BloomFilterOptions,
+};
 use super::page::{write_page, write_page_async, PageWriteSpec};
-use super::statistics::reduce;
-use super::DynStreamingIterator;
+use super::statistics::{reduce, SketchReducer};
+use super::{ColumnWriteOptions, DynStreamingIterator, WriteOptions};
+
+/// Returns the total compressed size of the column chunk of the pages `specs`.
+pub(super) fn total_compressed_size(specs: &[PageWriteSpec]) -> i64 {
+    // SPEC: the total compressed size is the total compressed size of each page + the header size
+    specs
+        .iter()
+        .map(|x| x.header_size as i64 + x.header.compressed_page_size as i64)
+        .sum()
+}
 
+#[allow(clippy::too_many_arguments)]
 pub fn write_column_chunk<'a, W, E>(
     writer: &mut W,
     mut offset: u64,
     descriptor: &ColumnDescriptor,
-    compression: Compression,
+    options: &WriteOptions,
+    column: &ColumnWriteOptions,
+    bloom_filter: Option<&BloomFilterOptions>,
+    mut encryptor: Option<ColumnEncryptor>,
     mut compressed_pages: DynStreamingIterator<'a, CompressedPage, E>,
-) -> Result<(ColumnChunk, u64)>
+) -> Result<(ColumnChunk, Vec<PageWriteSpec>, u64)>
 where
     W: Write,
     ParquetError: From<E>,
@@ -39,76 +57,266 @@ where
 
     let initial = offset;
 
+    if bloom_filter.is_some() && encryptor.is_some() {
+        return Err(general_err!(
+            "Bloom filters of encrypted columns are not supported"
+        ));
+    }
+    let mut bloom_filter = bloom_filter
+        .map(|options| BloomFilterBuilder::try_new(options, descriptor))
+        .transpose()?;
+
     let mut specs = vec![];
+    let mut sketches = SketchReducer::default();
+    let mut codec = None;
     while let Some(compressed_page) = compressed_pages.next()? {
-        let spec = write_page(writer, offset, compressed_page)?;
+        let compression = check_page(compressed_page, options, column, descriptor, &mut codec)?;
+        if let Some(bloom_filter) = bloom_filter.as_mut() {
+            bloom_filter.update(compressed_page, compression)?;
+        }
+        if let CompressedPage::Data(page) = compressed_page {
+            sketches.push(page.sketch());
+        }
+        let spec = write_page(
+            writer,
+            offset,
+            compressed_page,
+            options.write_page_crc,
+            encryptor.as_mut(),
+        )?;
         offset += spec.bytes_written;
         specs.push(spec);
     }
+
+    // write the bloom filter right after the pages
+    let bloom_filter_offset = if let Some(bloom_filter) = bloom_filter {
+        let bloom_filter_offset = offset;
+        offset += write_bloom_filter(writer, &bloom_filter.finish())?;
+        Some(bloom_filter_offset)
+    } else {
+        None
+    };
     let mut bytes_written = offset - initial;
 
-    let column_chunk = build_column_chunk(&specs, descriptor, compression)?;
+    let codec = codec.unwrap_or_else(|| options.compression.into());
+    let mut column_chunk = build_column_chunk(
+        &specs,
+        descriptor,
+        options,
+        codec,
+        offset,
+        bloom_filter_offset,
+        sketches.finish().map(|sketch| sketch.estimate() as i64),
+    )?;
+    if let Some(encryptor) = encryptor {
+        encryptor.encrypt_metadata(&mut column_chunk)?;
+    }
 
     // write metadata
     let mut protocol = TCompactOutputProtocol::new(writer);
-    bytes_written += column_chunk.write_to_out_protocol(&mut protocol)? as u64;
+    bytes_written +=
+        inline_column_chunk(&column_chunk).write_to_out_protocol(&mut protocol)? as u64;
     protocol.flush()?;
 
-    Ok((column_chunk, bytes_written))
+    Ok((column_chunk, specs, bytes_written))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn write_column_chunk_async<W, E>(
     writer: &mut W,
     mut offset: u64,
     descriptor: &ColumnDescriptor,
-    compression: Compression,
+    options: &WriteOptions,
+    column: &ColumnWriteOptions,
+    bloom_filter: Option<&BloomFilterOptions>,
+    mut encryptor: Option<ColumnEncryptor>,
     mut compressed_pages: DynStreamingIterator<'_, CompressedPage, E>,
-) -> Result<(ColumnChunk, usize)>
+) -> Result<(ColumnChunk, Vec<PageWriteSpec>, usize)>
 where
     W: AsyncWrite + Unpin + Send,
     ParquetError: From<E>,
     E: std::error::Error,
 {
     let initial = offset;
+
+    if bloom_filter.is_some() && encryptor.is_some() {
+        return Err(general_err!(
+            "Bloom filters of encrypted columns are not supported"
+        ));
+    }
+    let mut bloom_filter = bloom_filter
+        .map(|options| BloomFilterBuilder::try_new(options, descriptor))
+        .transpose()?;
+
     // write every page
     let mut specs = vec![];
+    let mut sketches = SketchReducer::default();
+    let mut codec = None;
     while let Some(compressed_page) = compressed_pages.next()? {
-        let spec = write_page_async(writer, offset, compressed_page).await?;
+        let compression = check_page(compressed_page, options, column, descriptor, &mut codec)?;
+        if let Some(bloom_filter) = bloom_filter.as_mut() {
+            bloom_filter.update(compressed_page, compression)?;
+        }
+        if let CompressedPage::Data(page) = compressed_page {
+            sketches.push(page.sketch());
+        }
+        let spec = write_page_async(
+            writer,
+            offset,
+            compressed_page,
+            options.write_page_crc,
+            encryptor.as_mut(),
+        )
+        .await?;
         offset += spec.bytes_written;
         specs.push(spec);
     }
+
+    // write the bloom filter right after the pages
+    let bloom_filter_offset = if let Some(bloom_filter) = bloom_filter {
+        let bloom_filter_offset = offset;
+        offset += write_bloom_filter_async(writer, &bloom_filter.finish()).await?;
+        Some(bloom_filter_offset)
+    } else {
+        None
+    };
     let mut bytes_written = (offset - initial) as usize;
 
-    let column_chunk = build_column_chunk(&specs, descriptor, compression)?;
+    let codec = codec.unwrap_or_else(|| options.compression.into());
+    let mut column_chunk = build_column_chunk(
+        &specs,
+        descriptor,
+        options,
+        codec,
+        offset,
+        bloom_filter_offset,
+        sketches.finish().map(|sketch| sketch.estimate() as i64),
+    )?;
+    if let Some(encryptor) = encryptor {
+        encryptor.encrypt_metadata(&mut column_chunk)?;
+    }
 
     // write metadata
     let mut protocol = TCompactOutputStreamProtocol::new(writer);
-    bytes_written += column_chunk
+    bytes_written += inline_column_chunk(&column_chunk)
         .write_to_out_stream_protocol(&mut protocol)
         .await?;
     protocol.flush().await?;
 
-    Ok((column_chunk, bytes_written))
+    Ok((column_chunk, specs, bytes_written))
+}
+
+/// Checks that `page` agrees with the options of its `column` and with `codec`, the codec of
+/// the previous pages of its column chunk (set by its first page), so that the metadata of the
+/// column chunk describes all of its pages. Returns the codec of `page`.
+fn check_page(
+    page: &CompressedPage,
+    options: &WriteOptions,
+    column: &ColumnWriteOptions,
+    descriptor: &ColumnDescriptor,
+    codec: &mut Option<Compression>,
+) -> Result<Compression> {
+    let path = || descriptor.path_in_schema().join(".");
+    let compression = match page {
+        CompressedPage::Data(page) => page.compression(),
+        CompressedPage::Dict(page) => page
+            .compression()
+            .unwrap_or_else(|| options.compression.into()),
+    };
+    match codec {
+        Some(codec) if *codec != compression => {
+            return Err(general_err!(
+                "The pages of column \"{}\" are compressed with both {:?} and {:?}",
+                path(),
+                codec,
+                compression
+            ))
+        }
+        Some(_) => {}
+        None => {
+            if column.dictionary == Some(true) && !matches!(page, CompressedPage::Dict(_)) {
+                return Err(general_err!(
+                    "The column \"{}\" is dictionary-encoded, but its first page is not a dictionary page",
+                    path()
+                ));
+            }
+            *codec = Some(compression);
+        }
+    }
+
+    match page {
+        CompressedPage::Dict(_) if column.dictionary == Some(false) => Err(general_err!(
+            "The column \"{}\" is not dictionary-encoded, but has a dictionary page",
+            path()
+        )),
+        CompressedPage::Data(page) => {
+            let encoding = page.encoding();
+            let is_dictionary = matches!(
+                encoding,
+                Encoding::PlainDictionary | Encoding::RleDictionary
+            );
+            let is_expected = if is_dictionary {
+                column.dictionary != Some(false)
+            } else {
+                !matches!(column.encoding, Some(expected) if expected != encoding)
+            };
+            if is_expected {
+                Ok(compression)
+            } else {
+                Err(general_err!(
+                    "The column \"{}\" has a data page encoded with {:?}, contradicting its options {:?}",
+                    path(),
+                    encoding,
+                    column
+                ))
+            }
+        }
+        _ => Ok(compression),
+    }
+}
+
+/// Returns the column chunk written after its pages. The metadata of encrypted column chunks
+/// is only written (encrypted) in the footer.
+fn inline_column_chunk(column_chunk: &ColumnChunk) -> Cow<'_, ColumnChunk> {
+    if column_chunk.crypto_metadata.is_some() {
+        let mut column_chunk = column_chunk.clone();
+        column_chunk.meta_data = None;
+        column_chunk.encrypted_column_metadata = None;
+        Cow::Owned(column_chunk)
+    } else {
+        Cow::Borrowed(column_chunk)
+    }
 }
 
 fn build_column_chunk(
     specs: &[PageWriteSpec],
     descriptor: &ColumnDescriptor,
-    compression: Compression,
+    options: &WriteOptions,
+    codec: Compression,
+    metadata_offset: u64,
+    bloom_filter_offset: Option<u64>,
+    distinct_count: Option<i64>,
 ) -> Result<ColumnChunk> {
     // compute stats to build header at the end of the chunk
 
-    // SPEC: the total compressed size is the total compressed size of each page + the header size
-    let total_compressed_size = specs
-        .iter()
-        .map(|x| x.header_size as i64 + x.header.compressed_page_size as i64)
-        .sum();
+    let total_compressed_size = total_compressed_size(specs);
     // SPEC: the total compressed size is the total compressed size of each page + the header size
     let total_uncompressed_size = specs
         .iter()
         .map(|x| x.header_size as i64 + x.header.uncompressed_page_size as i64)
         .sum();
-    let data_page_offset = specs.first().map(|spec| spec.offset).unwrap_or(0) as i64;
+    let is_dictionary_page =
+        |spec: &&PageWriteSpec| spec.header.type_ == PageType::DictionaryPage.into();
+    let dictionary_page_offset = specs
+        .iter()
+        .find(is_dictionary_page)
+        .map(|spec| spec.offset as i64);
+    let data_page_offset = specs
+        .iter()
+        .find(|spec| !is_dictionary_page(spec))
+        .or_else(|| specs.first())
+        .map(|spec| spec.offset)
+        .unwrap_or(0) as i64;
     let num_values = specs
         .iter()
         .map(|spec| {
@@ -146,7 +354,7 @@ fn build_column_chunk(
                         .unwrap()
                         .encoding,
                 ],
-                _ => todo!(),
+                _ => vec![], // e.g. index pages, that have no encoding
             }
         })
         .flatten()
@@ -154,9 +362,15 @@ fn build_column_chunk(
         .into_iter() // to vec
         .collect();
 
-    let statistics = specs.iter().map(|x| &x.statistics).collect::<Vec<_>>();
-    let statistics = reduce(&statistics)?;
-    let statistics = statistics.map(|x| serialize_statistics(x.as_ref()));
+    let statistics = if options.write_statistics {
+        let statistics = specs.iter().map(|x| &x.statistics).collect::<Vec<_>>();
+        reduce(&statistics)?.map(|x| ParquetStatistics {
+            distinct_count,
+            ..serialize_statistics(x.as_ref())
+        })
+    } else {
+        None
+    };
 
     let type_ = match descriptor.type_() {
         ParquetType::PrimitiveType { physical_type, .. } => physical_type_to_type(physical_type).0,
@@ -171,22 +385,23 @@ fn build_column_chunk(
         type_,
         encodings,
         path_in_schema: descriptor.path_in_schema().to_vec(),
-        codec: compression.into(),
+        codec: codec.into(),
         num_values,
         total_uncompressed_size,
         total_compressed_size,
         key_value_metadata: None,
         data_page_offset,
         index_page_offset: None,
-        dictionary_page_offset: None,
+        dictionary_page_offset,
         statistics,
         encoding_stats: None,
-        bloom_filter_offset: None,
+        bloom_filter_offset: bloom_filter_offset.map(|x| x as i64),
     };
 
     Ok(ColumnChunk {
         file_path: None, // same file for now.
-        file_offset: data_page_offset + total_compressed_size,
+        // the column metadata is written right after the pages and the bloom filter
+        file_offset: metadata_offset as i64,
         meta_data: Some(metadata),
         offset_index_offset: None,
         offset_index_length: None,
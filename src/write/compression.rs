@@ -1,6 +1,6 @@
+use crate::compression::CompressionOptions;
 use crate::error::{ParquetError, Result};
 use crate::page::{CompressedDictPage, CompressedPage, DataPageHeader, EncodedDictPage};
-use crate::parquet_bridge::Compression;
 use crate::FallibleStreamingIterator;
 use crate::{
     compression,
@@ -11,16 +11,19 @@ use crate::{
 fn compress_data(
     page: DataPage,
     mut compressed_buffer: Vec<u8>,
-    compression: Compression,
+    compression: CompressionOptions,
 ) -> Result<CompressedDataPage> {
+    let num_rows = page.num_rows();
     let DataPage {
         mut buffer,
         header,
         dictionary_page,
         descriptor,
+        selected_rows,
+        sketch,
     } = page;
     let uncompressed_page_size = buffer.len();
-    if compression != Compression::Uncompressed {
+    if compression != CompressionOptions::Uncompressed {
         match &header {
             DataPageHeader::V1(_) => {
                 compression::compress(compression, &buffer, &mut compressed_buffer)?;
@@ -40,33 +43,38 @@ fn compress_data(
     } else {
         std::mem::swap(&mut buffer, &mut compressed_buffer);
     };
-    Ok(CompressedDataPage::new(
+    let mut page = CompressedDataPage::new(
         header,
         compressed_buffer,
-        compression,
+        compression.into(),
         uncompressed_page_size,
         dictionary_page,
         descriptor,
-    ))
+    );
+    page.num_rows = num_rows;
+    page.selected_rows = selected_rows;
+    page.sketch = sketch;
+    Ok(page)
 }
 
 fn compress_dict(
     page: EncodedDictPage,
     mut compressed_buffer: Vec<u8>,
-    compression: Compression,
+    compression: CompressionOptions,
 ) -> Result<CompressedDictPage> {
     let EncodedDictPage {
         mut buffer,
         num_values,
     } = page;
     let uncompressed_page_size = buffer.len();
-    if compression != Compression::Uncompressed {
+    if compression != CompressionOptions::Uncompressed {
         compression::compress(compression, &buffer, &mut compressed_buffer)?;
     } else {
         std::mem::swap(&mut buffer, &mut compressed_buffer);
     }
-    Ok(CompressedDictPage::new(
+    Ok(CompressedDictPage::new_with_compression(
         compressed_buffer,
+        compression.into(),
         uncompressed_page_size,
         num_values,
     ))
@@ -75,7 +83,7 @@ fn compress_dict(
 pub fn compress(
     page: EncodedPage,
     compressed_buffer: Vec<u8>,
-    compression: Compression,
+    compression: CompressionOptions,
 ) -> Result<CompressedPage> {
     match page {
         EncodedPage::Data(page) => {
@@ -91,17 +99,17 @@ pub fn compress(
 /// holding a reusable buffer ([`Vec<u8>`]) for compression.
 pub struct Compressor<I: Iterator<Item = Result<EncodedPage>>> {
     iter: I,
-    compression: Compression,
+    compression: CompressionOptions,
     buffer: Vec<u8>,
     current: Option<CompressedPage>,
 }
 
 impl<I: Iterator<Item = Result<EncodedPage>>> Compressor<I> {
-    pub fn new_from_vec(iter: I, compression: Compression, buffer: Vec<u8>) -> Self {
+    pub fn new_from_vec(iter: I, compression: CompressionOptions, buffer: Vec<u8>) -> Self {
         Self::new(iter, compression, buffer)
     }
 
-    pub fn new(iter: I, compression: Compression, buffer: Vec<u8>) -> Self {
+    pub fn new(iter: I, compression: CompressionOptions, buffer: Vec<u8>) -> Self {
         Self {
             iter,
             compression,
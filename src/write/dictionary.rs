@@ -0,0 +1,588 @@
+use std::collections::HashMap;
+
+use crate::bloom_filter::{hash_byte, hash_native};
+use crate::encoding::hybrid_rle::encode_u32;
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::metadata::{ColumnDescriptor, SortOrd};
+use crate::page::{
+    DataPage, DataPageHeader, DataPageHeaderV1, DataPageHeaderV2, EncodedDictPage, EncodedPage,
+};
+use crate::schema::types::PhysicalType;
+use crate::statistics::{
+    BinaryStatisticsBuilder, FixedLenStatisticsBuilder, HyperLogLog, ParquetStatistics,
+    PrimitiveStatisticsBuilder,
+};
+use crate::types::NativeType;
+
+use super::{Version, WriteOptions};
+
+/// The default maximum size (in bytes) of the dictionary of a column chunk, after which
+/// dictionary encoders fall back to plain encoding.
+pub const DEFAULT_MAX_DICTIONARY_SIZE: usize = 1024 * 1024;
+
+/// The state shared by the dictionary encoders of every physical type: the plain-encoded
+/// dictionary and the index of every value in it.
+#[derive(Debug)]
+struct DictEncoder {
+    descriptor: ColumnDescriptor,
+    max_dictionary_size: usize,
+    // the index in the dictionary of every value, by its (unencoded) bytes
+    indices: HashMap<Vec<u8>, u32>,
+    // the PLAIN-encoded dictionary
+    dictionary: Vec<u8>,
+    // whether a data page was dictionary-encoded, in which case a dictionary page is required
+    has_dict_pages: bool,
+    fallback: bool,
+}
+
+impl DictEncoder {
+    fn try_new(
+        descriptor: ColumnDescriptor,
+        max_dictionary_size: usize,
+        is_valid: impl Fn(&PhysicalType) -> bool,
+    ) -> Result<Self> {
+        if !is_valid(descriptor.physical_type()) {
+            return Err(general_err!(
+                "The physical type {:?} of the column \"{}\" is not supported by this dictionary encoder",
+                descriptor.physical_type(),
+                descriptor.path_in_schema().join(".")
+            ));
+        }
+        if descriptor.max_rep_level() > 0 || descriptor.max_def_level() > 1 {
+            return Err(general_err!(
+                "Dictionary encoders only support non-nested columns, but \"{}\" is nested",
+                descriptor.path_in_schema().join(".")
+            ));
+        }
+        Ok(Self {
+            descriptor,
+            max_dictionary_size,
+            indices: HashMap::new(),
+            dictionary: vec![],
+            has_dict_pages: false,
+            fallback: false,
+        })
+    }
+
+    /// Returns the index of `value` in the dictionary, inserting it with `encode` (that
+    /// PLAIN-encodes it into the dictionary) if it is new.
+    fn index(&mut self, value: &[u8], encode: impl FnOnce(&mut Vec<u8>)) -> u32 {
+        if let Some(index) = self.indices.get(value) {
+            return *index;
+        }
+        let index = self.indices.len() as u32;
+        self.indices.insert(value.to_vec(), index);
+        encode(&mut self.dictionary);
+        index
+    }
+
+    /// Removes the values inserted in the dictionary since it had `num_values` values of
+    /// `size` bytes, and falls back to PLAIN encoding.
+    fn fall_back(&mut self, num_values: usize, size: usize) {
+        self.dictionary.truncate(size);
+        self.indices
+            .retain(|_, index| (*index as usize) < num_values);
+        self.fallback = true;
+    }
+
+    /// Encodes the non-null `values` in a single pass, into the indices of their `bytes` in the
+    /// dictionary or, once it fell back, PLAIN-encoded with `plain`. Every value (including
+    /// nulls) is passed to `observe` while encoding, e.g. to build its statistics.
+    ///
+    /// A value that grows the dictionary past `max_dictionary_size` bytes makes the encoder
+    /// fall back: the values of this page are removed from the dictionary and the page is
+    /// PLAIN-encoded instead.
+    fn encode_values<V: Copy, B: AsRef<[u8]>>(
+        &mut self,
+        values: impl Iterator<Item = Option<V>> + Clone,
+        mut observe: impl FnMut(Option<V>),
+        bytes: impl Fn(V) -> B,
+        plain: impl Fn(&[u8], &mut Vec<u8>),
+    ) -> Result<(Vec<u8>, Encoding)> {
+        let (num_values, size) = (self.indices.len(), self.dictionary.len());
+        let mut buffer = vec![];
+        let mut indices = vec![];
+        for (position, value) in values.clone().enumerate() {
+            observe(value);
+            let value = match value {
+                Some(value) => value,
+                None => continue,
+            };
+            let value = bytes(value);
+            let value = value.as_ref();
+            if self.fallback {
+                plain(value, &mut buffer);
+                continue;
+            }
+            indices.push(self.index(value, |dictionary| plain(value, dictionary)));
+            // SPEC: once the dictionary is too large, the remaining pages are PLAIN-encoded
+            if self.dictionary.len() > self.max_dictionary_size {
+                self.fall_back(num_values, size);
+                for value in values.clone().take(position + 1).flatten() {
+                    plain(bytes(value).as_ref(), &mut buffer);
+                }
+            }
+        }
+        if self.fallback {
+            Ok((buffer, Encoding::Plain))
+        } else {
+            Ok((self.encode_indices(indices)?, Encoding::RleDictionary))
+        }
+    }
+
+    /// Encodes `indices` as the values of a data page: the bit width followed by the
+    /// hybrid-RLE encoded indices.
+    fn encode_indices(&mut self, indices: Vec<u32>) -> Result<Vec<u8>> {
+        let max_index = self.indices.len().saturating_sub(1) as u32;
+        let num_bits = (32 - max_index.leading_zeros()) as u8;
+
+        let mut buffer = vec![num_bits];
+        encode_u32(&mut buffer, indices.into_iter(), num_bits)?;
+
+        self.has_dict_pages = true;
+        Ok(buffer)
+    }
+
+    /// Returns a data page with the validity of its values, `validity`, its `values` encoded
+    /// with `encoding` and their `statistics` and `sketch`.
+    fn data_page<I: ExactSizeIterator<Item = bool> + Clone>(
+        &self,
+        validity: I,
+        values: Vec<u8>,
+        encoding: Encoding,
+        statistics: Option<ParquetStatistics>,
+        sketch: Option<HyperLogLog>,
+        options: &WriteOptions,
+    ) -> Result<EncodedPage> {
+        let num_values = validity.len();
+        let num_nulls = validity.clone().filter(|x| !x).count();
+        let is_optional = self.descriptor.max_def_level() == 1;
+        if !is_optional && num_nulls > 0 {
+            return Err(general_err!(
+                "The required column \"{}\" cannot have null values",
+                self.descriptor.path_in_schema().join(".")
+            ));
+        }
+
+        let mut def_levels = vec![];
+        if is_optional {
+            encode_u32(&mut def_levels, validity.map(|x| x as u32), 1)?;
+        }
+
+        let header = match options.version {
+            Version::V1 => {
+                let mut buffer = vec![];
+                if is_optional {
+                    buffer.extend_from_slice(&(def_levels.len() as u32).to_le_bytes());
+                    buffer.extend_from_slice(&def_levels);
+                }
+                def_levels = buffer;
+                DataPageHeader::V1(DataPageHeaderV1 {
+                    num_values: num_values as i32,
+                    encoding: encoding.into(),
+                    definition_level_encoding: Encoding::Rle.into(),
+                    repetition_level_encoding: Encoding::Rle.into(),
+                    statistics,
+                })
+            }
+            Version::V2 => DataPageHeader::V2(DataPageHeaderV2 {
+                num_values: num_values as i32,
+                num_nulls: num_nulls as i32,
+                num_rows: num_values as i32,
+                encoding: encoding.into(),
+                definition_levels_byte_length: def_levels.len() as i32,
+                repetition_levels_byte_length: 0,
+                is_compressed: None,
+                statistics,
+            }),
+        };
+
+        let mut buffer = def_levels;
+        buffer.extend_from_slice(&values);
+        let page = DataPage::new(header, buffer, None, self.descriptor.clone());
+        Ok(EncodedPage::Data(match sketch {
+            Some(sketch) => page.with_sketch(sketch),
+            None => page,
+        }))
+    }
+
+    fn dict_page(&self) -> Option<EncodedPage> {
+        self.has_dict_pages.then(|| {
+            EncodedPage::Dict(EncodedDictPage::new(
+                self.dictionary.clone(),
+                self.indices.len(),
+            ))
+        })
+    }
+}
+
+macro_rules! dict_encoder_methods {
+    () => {
+        /// Whether this encoder fell back to PLAIN encoding, i.e. whether its dictionary grew
+        /// past the maximum dictionary size.
+        pub fn is_fallback(&self) -> bool {
+            self.encoder.fallback
+        }
+
+        /// Returns the dictionary page of every data page dictionary-encoded so far, or `None`
+        /// if no data page was dictionary-encoded.
+        ///
+        /// The dictionary page must be written before the data pages of the column chunk, and
+        /// is only complete after the last data page was encoded. Callers must therefore
+        /// buffer every data page of the column chunk until then; see the encoders' docs.
+        pub fn dict_page(&self) -> Option<EncodedPage> {
+            self.encoder.dict_page()
+        }
+    };
+}
+
+/// Dictionary encoder of columns whose physical type is represented by a [`NativeType`]
+/// (`INT32`, `INT64`, `INT96`, `FLOAT` and `DOUBLE`).
+///
+/// Every call to [`PrimitiveDictEncoder::encode`] returns a data page whose values are the
+/// `RLE_DICTIONARY`-encoded indices of the values in the dictionary, until a value would grow
+/// the dictionary past `max_dictionary_size` bytes. That data page and the remaining ones are
+/// then `PLAIN`-encoded.
+///
+/// Only non-nested columns, required or optional, are supported.
+///
+/// # Memory
+/// Since the dictionary page precedes the data pages in the file but is only complete after the
+/// last data page was encoded, callers must buffer all the data pages of a column chunk before
+/// writing them after [`PrimitiveDictEncoder::dict_page`]. Memory is bounded by
+/// `max_dictionary_size` for the dictionary (and about as much for the index of its values)
+/// plus the size of the buffered pages, so callers
+/// that need to bound it further must cap the number of values of each column chunk, e.g. by
+/// starting a new row group.
+#[derive(Debug)]
+pub struct PrimitiveDictEncoder<T: NativeType> {
+    encoder: DictEncoder,
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: NativeType + SortOrd> PrimitiveDictEncoder<T> {
+    /// Returns a new [`PrimitiveDictEncoder`] of the column `descriptor`.
+    /// # Error
+    /// Errors iff the physical type of `descriptor` is not `T` or the column is nested.
+    pub fn try_new(descriptor: ColumnDescriptor, max_dictionary_size: usize) -> Result<Self> {
+        Ok(Self {
+            encoder: DictEncoder::try_new(descriptor, max_dictionary_size, |x| *x == T::TYPE)?,
+            phantom: Default::default(),
+        })
+    }
+
+    /// Encodes `values` into a data page.
+    pub fn encode(&mut self, values: &[Option<T>], options: &WriteOptions) -> Result<EncodedPage> {
+        let mut statistics = options
+            .write_statistics
+            .then(|| PrimitiveStatisticsBuilder::new(&self.encoder.descriptor));
+        let mut sketch = options.write_distinct_count.then(HyperLogLog::new);
+        let (buffer, encoding) = self.encoder.encode_values(
+            values.iter().copied(),
+            |value| {
+                if let Some(statistics) = statistics.as_mut() {
+                    statistics.push(value);
+                }
+                if let (Some(sketch), Some(value)) = (sketch.as_mut(), value) {
+                    sketch.insert(hash_native(value));
+                }
+            },
+            |value| value.to_le_bytes(),
+            |bytes, buffer| buffer.extend_from_slice(bytes),
+        )?;
+        self.encoder.data_page(
+            values.iter().map(|x| x.is_some()),
+            buffer,
+            encoding,
+            statistics.map(|statistics| statistics.finish()),
+            sketch,
+            options,
+        )
+    }
+
+    dict_encoder_methods!();
+}
+
+/// Dictionary encoder of `BYTE_ARRAY` columns. See [`PrimitiveDictEncoder`], including its
+/// requirement to buffer the data pages of a column chunk.
+#[derive(Debug)]
+pub struct BinaryDictEncoder {
+    encoder: DictEncoder,
+}
+
+fn encode_plain_binary(value: &[u8], buffer: &mut Vec<u8>) {
+    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(value);
+}
+
+impl BinaryDictEncoder {
+    /// Returns a new [`BinaryDictEncoder`] of the column `descriptor`.
+    /// # Error
+    /// Errors iff the physical type of `descriptor` is not `BYTE_ARRAY` or the column is nested.
+    pub fn try_new(descriptor: ColumnDescriptor, max_dictionary_size: usize) -> Result<Self> {
+        Ok(Self {
+            encoder: DictEncoder::try_new(descriptor, max_dictionary_size, |x| {
+                *x == PhysicalType::ByteArray
+            })?,
+        })
+    }
+
+    /// Encodes `values` into a data page.
+    pub fn encode<A: AsRef<[u8]>>(
+        &mut self,
+        values: &[Option<A>],
+        options: &WriteOptions,
+    ) -> Result<EncodedPage> {
+        let mut statistics = options
+            .write_statistics
+            .then(|| BinaryStatisticsBuilder::new(&self.encoder.descriptor));
+        let mut sketch = options.write_distinct_count.then(HyperLogLog::new);
+        let (buffer, encoding) = self.encoder.encode_values(
+            values.iter().map(|x| x.as_ref().map(|x| x.as_ref())),
+            |value| {
+                if let Some(statistics) = statistics.as_mut() {
+                    statistics.push(value);
+                }
+                if let (Some(sketch), Some(value)) = (sketch.as_mut(), value) {
+                    sketch.insert(hash_byte(value));
+                }
+            },
+            |value| value,
+            encode_plain_binary,
+        )?;
+        self.encoder.data_page(
+            values.iter().map(|x| x.is_some()),
+            buffer,
+            encoding,
+            statistics.map(|statistics| statistics.finish()),
+            sketch,
+            options,
+        )
+    }
+
+    dict_encoder_methods!();
+}
+
+/// Dictionary encoder of `FIXED_LEN_BYTE_ARRAY` columns. See [`PrimitiveDictEncoder`],
+/// including its requirement to buffer the data pages of a column chunk.
+#[derive(Debug)]
+pub struct FixedLenDictEncoder {
+    encoder: DictEncoder,
+    size: usize,
+}
+
+impl FixedLenDictEncoder {
+    /// Returns a new [`FixedLenDictEncoder`] of the column `descriptor`.
+    /// # Error
+    /// Errors iff the physical type of `descriptor` is not `FIXED_LEN_BYTE_ARRAY` or the
+    /// column is nested.
+    pub fn try_new(descriptor: ColumnDescriptor, max_dictionary_size: usize) -> Result<Self> {
+        let encoder = DictEncoder::try_new(descriptor, max_dictionary_size, |x| {
+            matches!(x, PhysicalType::FixedLenByteArray(_))
+        })?;
+        let size = match encoder.descriptor.physical_type() {
+            PhysicalType::FixedLenByteArray(size) => *size as usize,
+            _ => unreachable!(),
+        };
+        Ok(Self { encoder, size })
+    }
+
+    /// Encodes `values` into a data page.
+    /// # Error
+    /// Errors iff a value does not have the size declared by the column.
+    pub fn encode<A: AsRef<[u8]>>(
+        &mut self,
+        values: &[Option<A>],
+        options: &WriteOptions,
+    ) -> Result<EncodedPage> {
+        if let Some(value) = values
+            .iter()
+            .flatten()
+            .find(|x| x.as_ref().len() != self.size)
+        {
+            return Err(ParquetError::OutOfSpec(format!(
+                "The values of a FIXED_LEN_BYTE_ARRAY column of size {} cannot have {} bytes",
+                self.size,
+                value.as_ref().len()
+            )));
+        }
+
+        let mut statistics = options
+            .write_statistics
+            .then(|| FixedLenStatisticsBuilder::new(&self.encoder.descriptor));
+        let mut sketch = options.write_distinct_count.then(HyperLogLog::new);
+        let (buffer, encoding) = self.encoder.encode_values(
+            values.iter().map(|x| x.as_ref().map(|x| x.as_ref())),
+            |value| {
+                if let Some(statistics) = statistics.as_mut() {
+                    statistics.push(value);
+                }
+                if let (Some(sketch), Some(value)) = (sketch.as_mut(), value) {
+                    sketch.insert(hash_byte(value));
+                }
+            },
+            |value| value,
+            |bytes, buffer| buffer.extend_from_slice(bytes),
+        )?;
+        self.encoder.data_page(
+            values.iter().map(|x| x.is_some()),
+            buffer,
+            encoding,
+            statistics.map(|statistics| statistics.finish()),
+            sketch,
+            options,
+        )
+    }
+
+    dict_encoder_methods!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::encoding::hybrid_rle::HybridRleDecoder;
+    use crate::page::split_buffer;
+    use crate::tests::{descriptor, options};
+
+    fn unwrap_data(page: EncodedPage) -> DataPage {
+        match page {
+            EncodedPage::Data(page) => page,
+            EncodedPage::Dict(_) => unreachable!(),
+        }
+    }
+
+    fn indices(page: &DataPage, length: usize) -> Vec<u32> {
+        let (_, _, values) = split_buffer(page, page.descriptor()).unwrap();
+        HybridRleDecoder::new(&values[1..], values[0] as u32, length).collect()
+    }
+
+    #[test]
+    fn primitive() -> Result<()> {
+        for version in [Version::V1, Version::V2] {
+            let mut encoder = PrimitiveDictEncoder::<i64>::try_new(
+                descriptor("OPTIONAL INT64 a"),
+                DEFAULT_MAX_DICTIONARY_SIZE,
+            )?;
+            let page = unwrap_data(encoder.encode(
+                &[Some(3), None, Some(1), Some(3), Some(3), Some(2)],
+                &options(version),
+            )?);
+            assert_eq!(page.encoding(), Encoding::RleDictionary);
+            assert_eq!(page.num_values(), 6);
+            assert_eq!(indices(&page, 5), vec![0, 1, 0, 0, 2]);
+
+            let page = unwrap_data(encoder.encode(&[Some(2), Some(4)], &options(version))?);
+            assert_eq!(indices(&page, 2), vec![2, 3]);
+
+            match encoder.dict_page() {
+                Some(EncodedPage::Dict(page)) => {
+                    assert_eq!(page.num_values, 4);
+                    let expected = [3i64, 1, 2, 4]
+                        .iter()
+                        .flat_map(|x| x.to_le_bytes())
+                        .collect::<Vec<_>>();
+                    assert_eq!(page.buffer, expected);
+                }
+                _ => unreachable!(),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fallback() -> Result<()> {
+        let mut encoder = BinaryDictEncoder::try_new(descriptor("REQUIRED BYTE_ARRAY a"), 12)?;
+
+        let page = unwrap_data(encoder.encode(&[Some(b"aa"), Some(b"bb")], &options(Version::V1))?);
+        assert_eq!(page.encoding(), Encoding::RleDictionary);
+        assert!(!encoder.is_fallback());
+
+        // the dictionary would grow past 12 bytes (4 + 2 bytes per value) with "cc"
+        let page = unwrap_data(encoder.encode(
+            &[Some(b"aa"), Some(b"cc"), Some(b"bb")],
+            &options(Version::V1),
+        )?);
+        assert_eq!(page.encoding(), Encoding::Plain);
+        assert_eq!(
+            page.buffer(),
+            &[2, 0, 0, 0, b'a', b'a', 2, 0, 0, 0, b'c', b'c', 2, 0, 0, 0, b'b', b'b']
+        );
+        assert!(encoder.is_fallback());
+
+        let page = unwrap_data(encoder.encode(&[Some(b"dd")], &options(Version::V1))?);
+        assert_eq!(page.encoding(), Encoding::Plain);
+        assert_eq!(page.buffer(), &[2, 0, 0, 0, b'd', b'd']);
+
+        match encoder.dict_page() {
+            Some(EncodedPage::Dict(page)) => {
+                assert_eq!(page.num_values, 2);
+                assert_eq!(page.buffer.len(), 12);
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_len() -> Result<()> {
+        let mut encoder = FixedLenDictEncoder::try_new(
+            descriptor("REQUIRED FIXED_LEN_BYTE_ARRAY(2) a"),
+            DEFAULT_MAX_DICTIONARY_SIZE,
+        )?;
+        assert!(encoder.dict_page().is_none());
+        assert!(encoder
+            .encode(&[Some(b"abc")], &options(Version::V1))
+            .is_err());
+        assert!(encoder
+            .encode::<&[u8]>(&[None], &options(Version::V1))
+            .is_err());
+
+        let page = unwrap_data(encoder.encode(&[Some(b"ab"), Some(b"ab")], &options(Version::V1))?);
+        assert_eq!(indices(&page, 2), vec![0, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn statistics() -> Result<()> {
+        for version in [Version::V1, Version::V2] {
+            let mut encoder = BinaryDictEncoder::try_new(
+                descriptor("OPTIONAL BYTE_ARRAY a"),
+                DEFAULT_MAX_DICTIONARY_SIZE,
+            )?;
+            let page = unwrap_data(encoder.encode(&[Some(b"b")], &options(version))?);
+            assert!(page.statistics().is_none());
+
+            let options = WriteOptions {
+                write_statistics: true,
+                write_distinct_count: false,
+                ..options(version)
+            };
+            let page = unwrap_data(encoder.encode(&[Some(b"b"), None, Some(b"a")], &options)?);
+            let statistics = page.statistics().unwrap()?;
+            let statistics = statistics
+                .as_any()
+                .downcast_ref::<crate::statistics::BinaryStatistics>()
+                .unwrap();
+            assert_eq!(statistics.null_count, Some(1));
+            assert_eq!(statistics.min_value, Some(b"a".to_vec()));
+            assert_eq!(statistics.max_value, Some(b"b".to_vec()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_columns() {
+        assert!(PrimitiveDictEncoder::<i32>::try_new(
+            descriptor("REQUIRED INT64 a"),
+            DEFAULT_MAX_DICTIONARY_SIZE
+        )
+        .is_err());
+        assert!(BinaryDictEncoder::try_new(
+            descriptor("REPEATED BYTE_ARRAY a"),
+            DEFAULT_MAX_DICTIONARY_SIZE
+        )
+        .is_err());
+    }
+}
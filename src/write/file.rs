@@ -1,3 +1,4 @@
+use std::convert::TryInto;
 use std::io::Write;
 
 use parquet_format_async_temp::FileMetaData;
@@ -8,16 +9,20 @@ use parquet_format_async_temp::RowGroup;
 
 pub use crate::metadata::KeyValue;
 use crate::{
+    encryption::{FileEncryptionProperties, FileEncryptor},
     error::{ParquetError, Result},
     metadata::SchemaDescriptor,
     FOOTER_SIZE, PARQUET_MAGIC,
 };
 
-use super::{row_group::write_row_group, RowGroupIter, WriteOptions};
+use super::{
+    bloom_filter::BloomFilterOptions, column_index, indexes::write_indexes, page::PageWriteSpec,
+    row_group::write_row_group, ColumnWriteOptions, RowGroupIter, WriteOptions,
+};
 
-pub(super) fn start_file<W: Write>(writer: &mut W) -> Result<u64> {
-    writer.write_all(&PARQUET_MAGIC)?;
-    Ok(PARQUET_MAGIC.len() as u64)
+pub(super) fn start_file<W: Write>(writer: &mut W, magic: [u8; 4]) -> Result<u64> {
+    writer.write_all(&magic)?;
+    Ok(magic.len() as u64)
 }
 
 pub(super) fn end_file<W: Write>(mut writer: &mut W, metadata: FileMetaData) -> Result<u64> {
@@ -38,6 +43,30 @@ pub(super) fn end_file<W: Write>(mut writer: &mut W, metadata: FileMetaData) ->
     Ok(metadata_len as u64 + FOOTER_SIZE)
 }
 
+/// Returns the (4 bytes, little endian) length of an encrypted file's `footer`
+pub(super) fn footer_len(footer: &[u8]) -> Result<[u8; 4]> {
+    let len: i32 = footer.len().try_into().map_err(|_| {
+        ParquetError::OutOfSpec(format!(
+            "A footer can only contain i32::MAX bytes. This one contains {}",
+            footer.len()
+        ))
+    })?;
+    Ok(len.to_le_bytes())
+}
+
+pub(super) fn end_encrypted_file<W: Write>(
+    writer: &mut W,
+    metadata: FileMetaData,
+    encryptor: &FileEncryptor,
+) -> Result<u64> {
+    let footer = encryptor.footer(metadata)?;
+    writer.write_all(&footer)?;
+
+    writer.write_all(&footer_len(&footer)?)?;
+    writer.write_all(&encryptor.magic())?;
+    Ok(footer.len() as u64 + FOOTER_SIZE)
+}
+
 /// An interface to write a parquet file.
 /// Use `start` to write the header, `write` to write a row group,
 /// and `end` to write the footer.
@@ -46,9 +75,13 @@ pub struct FileWriter<W: Write> {
     schema: SchemaDescriptor,
     options: WriteOptions,
     created_by: Option<String>,
+    bloom_filters: Vec<Option<BloomFilterOptions>>,
+    column_options: Vec<ColumnWriteOptions>,
+    encryptor: Option<FileEncryptor>,
 
     offset: u64,
     row_groups: Vec<RowGroup>,
+    page_specs: Vec<Vec<Vec<PageWriteSpec>>>,
 }
 
 // Accessors
@@ -62,6 +95,20 @@ impl<W: Write> FileWriter<W> {
     pub fn schema(&self) -> &SchemaDescriptor {
         &self.schema
     }
+
+    /// The [`BloomFilterOptions`] of the column whose dot-separated path is `path`, if any
+    pub fn bloom_filter(&self, path: &str) -> Option<&BloomFilterOptions> {
+        let index = column_index(&self.schema, path).ok()?;
+        self.bloom_filters[index].as_ref()
+    }
+
+    /// The options of the column whose dot-separated path is `path`, i.e. those of the file
+    /// with the overrides of the column applied, and its [`ColumnWriteOptions`]
+    pub fn column_options(&self, path: &str) -> Option<(WriteOptions, &ColumnWriteOptions)> {
+        let index = column_index(&self.schema, path).ok()?;
+        let column = &self.column_options[index];
+        Some((self.options.with_column(column), column))
+    }
 }
 
 impl<W: Write> FileWriter<W> {
@@ -72,19 +119,75 @@ impl<W: Write> FileWriter<W> {
         options: WriteOptions,
         created_by: Option<String>,
     ) -> Self {
+        let bloom_filters = vec![None; schema.columns().len()];
+        let column_options = vec![ColumnWriteOptions::default(); schema.columns().len()];
         Self {
             writer,
             schema,
             options,
             created_by,
+            bloom_filters,
+            column_options,
+            encryptor: None,
             offset: 0,
             row_groups: vec![],
+            page_specs: vec![],
+        }
+    }
+
+    /// Sets whether the column whose dot-separated path is `path` has a bloom filter built
+    /// from its values, for every row group written afterwards.
+    /// The bloom filter is written after the pages of each column chunk.
+    ///
+    /// Pages are written compressed, so building the bloom filter decompresses and decodes
+    /// the PLAIN-encoded data pages and the dictionary pages of the column a second time.
+    /// # Error
+    /// Errors iff no column has this path.
+    pub fn set_bloom_filter(
+        &mut self,
+        path: &str,
+        options: Option<BloomFilterOptions>,
+    ) -> Result<()> {
+        let index = column_index(&self.schema, path)?;
+        self.bloom_filters[index] = options;
+        Ok(())
+    }
+
+    /// Sets the [`ColumnWriteOptions`] of the column whose dot-separated path is `path`, for
+    /// every row group written afterwards.
+    /// # Error
+    /// Errors iff no column has this path.
+    pub fn set_column_options(&mut self, path: &str, options: ColumnWriteOptions) -> Result<()> {
+        let index = column_index(&self.schema, path)?;
+        self.column_options[index] = options;
+        Ok(())
+    }
+
+    /// Sets the [`FileEncryptionProperties`] used to encrypt the file, or `None` to write it
+    /// in plaintext. The page indexes of encrypted columns are not written.
+    /// # Error
+    /// Errors iff the file was already started, a key is invalid, a column key is declared for
+    /// a column that does not exist, or the feature `encryption` is not active.
+    pub fn set_encryption(&mut self, properties: Option<FileEncryptionProperties>) -> Result<()> {
+        if self.offset != 0 {
+            return Err(general_err!(
+                "The encryption of a file must be set before calling `start`"
+            ));
         }
+        self.encryptor = properties
+            .map(|properties| FileEncryptor::try_new(properties, &self.schema))
+            .transpose()?;
+        Ok(())
     }
 
     /// Writes the header of the file
     pub fn start(&mut self) -> Result<()> {
-        self.offset = start_file(&mut self.writer)? as u64;
+        let magic = self
+            .encryptor
+            .as_ref()
+            .map(|encryptor| encryptor.magic())
+            .unwrap_or(PARQUET_MAGIC);
+        self.offset = start_file(&mut self.writer, magic)? as u64;
         Ok(())
     }
 
@@ -101,22 +204,37 @@ impl<W: Write> FileWriter<W> {
                 "You must call `start` before writing the first row group".to_string(),
             ));
         }
-        let (group, size) = write_row_group(
+        let ordinal = self.row_groups.len();
+        let (group, specs, size) = write_row_group(
             &mut self.writer,
             self.offset,
             self.schema.columns(),
-            self.options.compression,
+            &self.options,
+            &self.column_options,
+            &self.bloom_filters,
+            self.encryptor
+                .as_ref()
+                .map(|encryptor| (encryptor, ordinal)),
             row_group,
             num_rows,
         )?;
         self.offset += size;
         self.row_groups.push(group);
+        self.page_specs.push(specs);
         Ok(())
     }
 
     /// Writes the footer of the parquet file. Returns the total size of the file and the
     /// underlying writer.
     pub fn end(mut self, key_value_metadata: Option<Vec<KeyValue>>) -> Result<(u64, W)> {
+        // write the page indexes (column and offset indexes) of every column chunk
+        self.offset += write_indexes(
+            &mut self.writer,
+            self.offset,
+            &mut self.row_groups,
+            &self.page_specs,
+        )?;
+
         // compute file stats
         let num_rows = self.row_groups.iter().map(|group| group.num_rows).sum();
 
@@ -132,7 +250,11 @@ impl<W: Write> FileWriter<W> {
             None,
         );
 
-        let len = end_file(&mut self.writer, metadata)?;
+        let len = if let Some(encryptor) = &self.encryptor {
+            end_encrypted_file(&mut self.writer, metadata, encryptor)?
+        } else {
+            end_file(&mut self.writer, metadata)?
+        };
         Ok((self.offset + len, self.writer))
     }
 }
@@ -162,7 +284,7 @@ mod tests {
         let mut writer = Cursor::new(vec![]);
 
         // write the file
-        start_file(&mut writer)?;
+        start_file(&mut writer, PARQUET_MAGIC)?;
         end_file(&mut writer, metadata.into_thrift()?)?;
 
         let a = writer.into_inner();
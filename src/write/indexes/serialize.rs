@@ -0,0 +1,205 @@
+use std::convert::TryInto;
+
+use parquet_format_async_temp::{ColumnIndex, OffsetIndex, PageLocation};
+
+use crate::error::{ParquetError, Result};
+use crate::statistics::serialize_statistics;
+use crate::write::page::PageWriteSpec;
+use crate::write::statistics::boundary_order;
+
+fn is_data_page(spec: &PageWriteSpec) -> bool {
+    spec.header.data_page_header.is_some() || spec.header.data_page_header_v2.is_some()
+}
+
+/// The number of values (including nulls) of a data page
+fn num_values(spec: &PageWriteSpec) -> Option<i64> {
+    match (
+        &spec.header.data_page_header,
+        &spec.header.data_page_header_v2,
+    ) {
+        (Some(header), _) => Some(header.num_values as i64),
+        (_, Some(header)) => Some(header.num_values as i64),
+        _ => None,
+    }
+}
+
+/// Serializes the statistics of the data pages of a column chunk into a [`ColumnIndex`].
+/// Returns `None` when the column chunk has no data pages or some of its pages have no
+/// min and max statistics without being only nulls, since the index would not be able to
+/// describe them.
+pub fn serialize_column_index(pages: &[PageWriteSpec]) -> Result<Option<ColumnIndex>> {
+    let mut null_pages = vec![];
+    let mut min_values = vec![];
+    let mut max_values = vec![];
+    let mut null_counts = Some(vec![]);
+    // the statistics of the non-null pages, whose bounds define the boundary order
+    let mut bounds = vec![];
+
+    for spec in pages.iter().filter(|x| is_data_page(x)) {
+        let page_statistics = match &spec.statistics {
+            Some(statistics) => statistics.as_ref(),
+            None => return Ok(None),
+        };
+        let statistics = serialize_statistics(page_statistics);
+        match (statistics.min_value, statistics.max_value) {
+            (Some(min), Some(max)) => {
+                bounds.push(page_statistics);
+                null_pages.push(false);
+                min_values.push(min);
+                max_values.push(max);
+            }
+            (None, None)
+                if statistics.null_count.is_some() && statistics.null_count == num_values(spec) =>
+            {
+                // SPEC: writers have to set the min and max of null pages to byte[0]
+                null_pages.push(true);
+                min_values.push(vec![]);
+                max_values.push(vec![]);
+            }
+            _ => return Ok(None),
+        }
+        match (null_counts.as_mut(), statistics.null_count) {
+            (Some(null_counts), Some(null_count)) => null_counts.push(null_count),
+            _ => null_counts = None,
+        }
+    }
+
+    if null_pages.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(ColumnIndex {
+        null_pages,
+        min_values,
+        max_values,
+        boundary_order: boundary_order(&bounds)?,
+        null_counts,
+    }))
+}
+
+/// Serializes the locations of the data pages of a column chunk into an [`OffsetIndex`].
+/// Returns `None` when the column chunk has no data pages or the number of rows of some of
+/// its pages is unknown.
+pub fn serialize_offset_index(pages: &[PageWriteSpec]) -> Result<Option<OffsetIndex>> {
+    let mut first_row_index = 0;
+    let mut page_locations = vec![];
+
+    for spec in pages.iter().filter(|x| is_data_page(x)) {
+        let num_rows = match spec.num_rows {
+            Some(num_rows) => num_rows,
+            None => return Ok(None),
+        };
+        let compressed_page_size = spec.bytes_written.try_into().map_err(|_| {
+            ParquetError::OutOfSpec(format!(
+                "A page can only contain i32::MAX bytes. This one contains {}",
+                spec.bytes_written
+            ))
+        })?;
+
+        page_locations.push(PageLocation {
+            offset: spec.offset as i64,
+            compressed_page_size,
+            first_row_index: first_row_index as i64,
+        });
+        first_row_index += num_rows;
+    }
+
+    if page_locations.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(OffsetIndex { page_locations }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parquet_format_async_temp::{
+        BoundaryOrder, DataPageHeader, Encoding, PageHeader, PageType,
+    };
+
+    use super::*;
+    use crate::metadata::SchemaDescriptor;
+    use crate::statistics::{PrimitiveStatistics, Statistics};
+
+    fn spec(num_values: i32, min_value: Option<i32>, null_count: Option<i64>) -> PageWriteSpec {
+        let schema =
+            SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 a; }").unwrap();
+        let statistics: Arc<dyn Statistics> = Arc::new(PrimitiveStatistics::<i32> {
+            descriptor: schema.columns()[0].clone(),
+            null_count,
+            distinct_count: None,
+            max_value: min_value,
+            min_value,
+        });
+        PageWriteSpec {
+            header: PageHeader {
+                type_: PageType::DATA_PAGE,
+                uncompressed_page_size: 0,
+                compressed_page_size: 0,
+                crc: None,
+                data_page_header: Some(DataPageHeader {
+                    num_values,
+                    encoding: Encoding::PLAIN,
+                    definition_level_encoding: Encoding::RLE,
+                    repetition_level_encoding: Encoding::RLE,
+                    statistics: None,
+                }),
+                index_page_header: None,
+                dictionary_page_header: None,
+                data_page_header_v2: None,
+            },
+            header_size: 0,
+            offset: 0,
+            bytes_written: 0,
+            statistics: Some(statistics),
+            num_rows: Some(num_values as usize),
+        }
+    }
+
+    #[test]
+    fn null_pages() -> Result<()> {
+        let index =
+            serialize_column_index(&[spec(2, Some(1), Some(0)), spec(3, None, Some(3))])?.unwrap();
+        assert_eq!(index.null_pages, vec![false, true]);
+        assert_eq!(index.min_values, vec![1i32.to_le_bytes().to_vec(), vec![]]);
+        assert_eq!(index.null_counts, Some(vec![0, 3]));
+        Ok(())
+    }
+
+    #[test]
+    fn no_min_max_with_values() -> Result<()> {
+        // a page without min and max that is not only nulls cannot be described by the index
+        let pages = [spec(2, Some(1), Some(0)), spec(3, None, Some(1))];
+        assert!(serialize_column_index(&pages)?.is_none());
+        let pages = [spec(2, Some(1), Some(0)), spec(3, None, None)];
+        assert!(serialize_column_index(&pages)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn boundary_orders() -> Result<()> {
+        let order = |mins: &[Option<i32>]| -> Result<BoundaryOrder> {
+            let pages = mins
+                .iter()
+                .map(|min| spec(2, *min, Some(if min.is_some() { 0 } else { 2 })))
+                .collect::<Vec<_>>();
+            Ok(serialize_column_index(&pages)?.unwrap().boundary_order)
+        };
+        // null pages are ignored
+        assert_eq!(
+            order(&[Some(1), None, Some(1), Some(3)])?,
+            BoundaryOrder::ASCENDING
+        );
+        assert_eq!(
+            order(&[Some(3), Some(-1), None])?,
+            BoundaryOrder::DESCENDING
+        );
+        assert_eq!(
+            order(&[Some(1), Some(3), Some(2)])?,
+            BoundaryOrder::UNORDERED
+        );
+        Ok(())
+    }
+}
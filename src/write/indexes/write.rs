@@ -0,0 +1,88 @@
+use std::io::Write;
+
+use futures::{AsyncWrite, AsyncWriteExt};
+use parquet_format_async_temp::thrift::protocol::{TCompactOutputProtocol, TOutputProtocol};
+use parquet_format_async_temp::RowGroup;
+
+use crate::error::Result;
+use crate::write::page::PageWriteSpec;
+
+use super::serialize::{serialize_column_index, serialize_offset_index};
+
+/// Serializes the column indexes and the offset indexes of all column chunks of `row_groups`,
+/// to be written at `offset`, and stores their location in the column chunks.
+/// `specs` contains the [`PageWriteSpec`] of every page, per column chunk, per row group.
+/// The indexes of encrypted column chunks are not serialized.
+fn serialize_indexes(
+    offset: u64,
+    row_groups: &mut [RowGroup],
+    specs: &[Vec<Vec<PageWriteSpec>>],
+) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+
+    // SPEC: the column indexes of all row groups are stored one after the other,
+    // followed by the offset indexes.
+    for (group, specs) in row_groups.iter_mut().zip(specs.iter()) {
+        for (column, pages) in group.columns.iter_mut().zip(specs.iter()) {
+            if column.crypto_metadata.is_some() {
+                continue;
+            }
+            if let Some(index) = serialize_column_index(pages)? {
+                let start = offset + buffer.len() as u64;
+                let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+                let length = index.write_to_out_protocol(&mut protocol)?;
+                protocol.flush()?;
+
+                column.column_index_offset = Some(start as i64);
+                column.column_index_length = Some(length as i32);
+            }
+        }
+    }
+
+    for (group, specs) in row_groups.iter_mut().zip(specs.iter()) {
+        for (column, pages) in group.columns.iter_mut().zip(specs.iter()) {
+            if column.crypto_metadata.is_some() {
+                continue;
+            }
+            if let Some(index) = serialize_offset_index(pages)? {
+                let start = offset + buffer.len() as u64;
+                let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+                let length = index.write_to_out_protocol(&mut protocol)?;
+                protocol.flush()?;
+
+                column.offset_index_offset = Some(start as i64);
+                column.offset_index_length = Some(length as i32);
+            }
+        }
+    }
+
+    Ok(buffer)
+}
+
+/// Writes the column indexes and the offset indexes of all column chunks of `row_groups`
+/// into `writer`, starting at `offset`, and stores their location in the column chunks.
+/// `specs` contains the [`PageWriteSpec`] of every page, per column chunk, per row group.
+/// The indexes of encrypted column chunks are not written.
+/// Returns the number of bytes written.
+pub fn write_indexes<W: Write>(
+    writer: &mut W,
+    offset: u64,
+    row_groups: &mut [RowGroup],
+    specs: &[Vec<Vec<PageWriteSpec>>],
+) -> Result<u64> {
+    let buffer = serialize_indexes(offset, row_groups, specs)?;
+    writer.write_all(&buffer)?;
+    Ok(buffer.len() as u64)
+}
+
+/// Async version of [`write_indexes`].
+pub async fn write_indexes_async<W: AsyncWrite + Unpin + Send>(
+    writer: &mut W,
+    offset: u64,
+    row_groups: &mut [RowGroup],
+    specs: &[Vec<Vec<PageWriteSpec>>],
+) -> Result<u64> {
+    let buffer = serialize_indexes(offset, row_groups, specs)?;
+    writer.write_all(&buffer).await?;
+    Ok(buffer.len() as u64)
+}
@@ -1,6 +1,10 @@
+mod bloom_filter;
 mod column_chunk;
 mod compression;
+mod dictionary;
 mod file;
+mod indexes;
+mod nested;
 mod page;
 mod row_group;
 pub(self) mod statistics;
@@ -13,27 +17,99 @@ pub use stream::FileStreamer;
 mod dyn_iter;
 pub use dyn_iter::{DynIter, DynStreamingIterator};
 
+pub use bloom_filter::BloomFilterOptions;
 pub use compression::{compress, Compressor};
+pub use dictionary::{
+    BinaryDictEncoder, FixedLenDictEncoder, PrimitiveDictEncoder, DEFAULT_MAX_DICTIONARY_SIZE,
+};
 
 pub use file::FileWriter;
 
+pub use nested::{shred, ShreddedColumn, Value};
+
 pub use row_group::ColumnOffsetsMetadata;
 
-use crate::compression::Compression;
+use crate::compression::CompressionOptions;
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::metadata::SchemaDescriptor;
 use crate::page::CompressedPage;
 
 pub type RowGroupIter<'a, E> =
     DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>;
 
-/// Write options of different interfaces on this crate
+/// Write options of different interfaces on this crate.
+///
+/// Prefer building them with `..Default::default()`, so that new options do not break callers.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct WriteOptions {
     /// Whether to write statistics
     pub write_statistics: bool,
-    /// Whether to use compression
-    pub compression: Compression,
+    /// Whether to estimate the number of distinct values of every column chunk from a
+    /// [`HyperLogLog`](crate::statistics::HyperLogLog) sketch of each of its pages, written in
+    /// its statistics (when `write_statistics` is set)
+    pub write_distinct_count: bool,
+    /// Whether to use compression, and its parameters
+    pub compression: CompressionOptions,
     /// Which Parquet version to use
     pub version: Version,
+    /// Whether to write the CRC32 checksum of every page in its header
+    pub write_page_crc: bool,
+}
+
+impl Default for WriteOptions {
+    /// Uncompressed version 2 pages with statistics, without distinct counts nor checksums.
+    fn default() -> Self {
+        Self {
+            write_statistics: true,
+            write_distinct_count: false,
+            compression: CompressionOptions::Uncompressed,
+            version: Version::V2,
+            write_page_crc: false,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Returns these options with the overrides of `column` applied.
+    pub fn with_column(&self, column: &ColumnWriteOptions) -> Self {
+        Self {
+            write_statistics: column.write_statistics.unwrap_or(self.write_statistics),
+            compression: column.compression.unwrap_or(self.compression),
+            ..*self
+        }
+    }
+}
+
+/// Returns the index (in [`SchemaDescriptor::columns`]) of the column of `schema` whose
+/// dot-separated path is `path`, by which the per-column options of writers are set.
+/// # Error
+/// Errors iff no column has this path.
+pub(super) fn column_index(schema: &SchemaDescriptor, path: &str) -> Result<usize> {
+    schema
+        .columns()
+        .iter()
+        .position(|column| column.path_in_schema().join(".") == path)
+        .ok_or_else(|| general_err!("The column \"{}\" does not exist", path))
+}
+
+/// Write options of a single column, by dot-separated column path, that override the
+/// [`WriteOptions`] of the file when set.
+///
+/// The codec and statistics are applied when writing the column chunks. Pages are encoded
+/// and compressed before being written (e.g. with [`PrimitiveDictEncoder`] and [`Compressor`]),
+/// with the options of their column; writing a page that contradicts them is an error.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ColumnWriteOptions {
+    /// Whether to use compression, and its parameters
+    pub compression: Option<CompressionOptions>,
+    /// Whether to write statistics
+    pub write_statistics: Option<bool>,
+    /// Whether the values are dictionary-encoded, i.e. whether column chunks start with a
+    /// dictionary page (data pages may still fall back to `encoding`)
+    pub dictionary: Option<bool>,
+    /// The encoding of the values of data pages that are not dictionary-encoded
+    pub encoding: Option<Encoding>,
 }
 
 /// The parquet version to use
@@ -0,0 +1,670 @@
+use crate::bloom_filter::{hash_byte, hash_native};
+use crate::encoding::hybrid_rle::{bitpacked_encode, encode_u32};
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::metadata::{ColumnDescriptor, SchemaDescriptor};
+use crate::page::{DataPage, DataPageHeader, DataPageHeaderV1, DataPageHeaderV2, EncodedPage};
+use crate::read::levels::get_bit_width;
+use crate::schema::types::{ParquetType, PhysicalType};
+use crate::schema::Repetition;
+use crate::statistics::{
+    BinaryStatisticsBuilder, BooleanStatisticsBuilder, FixedLenStatisticsBuilder, HyperLogLog,
+    ParquetStatistics, PrimitiveStatisticsBuilder,
+};
+
+use super::{Version, WriteOptions};
+
+/// A nested value, to be shredded into the repetition and definition levels and the values of
+/// the leaves of its [`ParquetType`] by [`shred`].
+///
+/// * Primitive fields take the variant of their physical type (`Binary` for both `BYTE_ARRAY`
+///   and `FIXED_LEN_BYTE_ARRAY`).
+/// * Groups take a `Group` with a value per field, in the order of the fields.
+/// * Repeated fields take a `List` with a value per element.
+/// * Groups annotated as `LIST` or `MAP` can also take a `List` of their elements or key-value
+///   pairs, in which case the elements of a 3-level `LIST` are the values of its `element`.
+/// * Optional fields take `Null` for null values. `Null` is also an empty list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Boolean(bool),
+    Int32(i32),
+    Int64(i64),
+    Int96([u32; 3]),
+    Float(f32),
+    Double(f64),
+    Binary(Vec<u8>),
+    List(Vec<Value>),
+    Group(Vec<Value>),
+}
+
+/// The repetition and definition levels and the (non-null) values of a leaf column, as shredded
+/// by [`shred`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShreddedColumn {
+    descriptor: ColumnDescriptor,
+    rep_levels: Vec<u32>,
+    def_levels: Vec<u32>,
+    values: Vec<Value>,
+    num_rows: usize,
+}
+
+impl ShreddedColumn {
+    fn new(descriptor: ColumnDescriptor) -> Self {
+        Self {
+            descriptor,
+            rep_levels: vec![],
+            def_levels: vec![],
+            values: vec![],
+            num_rows: 0,
+        }
+    }
+
+    fn push(&mut self, rep_level: u32, def_level: u32) {
+        self.rep_levels.push(rep_level);
+        self.def_levels.push(def_level);
+    }
+
+    /// The descriptor of the column
+    pub fn descriptor(&self) -> &ColumnDescriptor {
+        &self.descriptor
+    }
+
+    /// The repetition level of every value of the column, including nulls and empty lists
+    pub fn rep_levels(&self) -> &[u32] {
+        &self.rep_levels
+    }
+
+    /// The definition level of every value of the column, including nulls and empty lists
+    pub fn def_levels(&self) -> &[u32] {
+        &self.def_levels
+    }
+
+    /// The non-null values of the column
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// The number of rows of the column
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Returns a data page with the RLE-encoded levels and the PLAIN-encoded values of this
+    /// column.
+    pub fn to_page(&self, options: &WriteOptions) -> Result<EncodedPage> {
+        let rep_levels = encode_levels(&self.rep_levels, self.descriptor.max_rep_level())?;
+        let def_levels = encode_levels(&self.def_levels, self.descriptor.max_def_level())?;
+        let num_values = self.def_levels.len();
+        // every slot with a definition level below the maximum (a null value or an empty list)
+        // counts as a null
+        let num_nulls = num_values - self.values.len();
+
+        let mut buffer = vec![];
+        match options.version {
+            Version::V1 => {
+                // SPEC: v1 levels are prefixed by their length (4 bytes)
+                if self.descriptor.max_rep_level() > 0 {
+                    buffer.extend_from_slice(&(rep_levels.len() as u32).to_le_bytes());
+                    buffer.extend_from_slice(&rep_levels);
+                }
+                if self.descriptor.max_def_level() > 0 {
+                    buffer.extend_from_slice(&(def_levels.len() as u32).to_le_bytes());
+                    buffer.extend_from_slice(&def_levels);
+                }
+            }
+            Version::V2 => {
+                buffer.extend_from_slice(&rep_levels);
+                buffer.extend_from_slice(&def_levels);
+            }
+        }
+
+        let mut statistics = options
+            .write_statistics
+            .then(|| StatisticsBuilder::new(&self.descriptor));
+        let mut sketch = options.write_distinct_count.then(HyperLogLog::new);
+        encode_plain(
+            &self.values,
+            self.descriptor.physical_type(),
+            &mut buffer,
+            |value| {
+                if let Some(statistics) = statistics.as_mut() {
+                    statistics.push(Some(value));
+                }
+                if let Some(sketch) = sketch.as_mut() {
+                    sketch.insert(hash_value(value));
+                }
+            },
+        )?;
+        let statistics = statistics.map(|mut statistics| {
+            (0..num_nulls).for_each(|_| statistics.push(None));
+            statistics.finish()
+        });
+
+        let header = match options.version {
+            Version::V1 => DataPageHeader::V1(DataPageHeaderV1 {
+                num_values: num_values as i32,
+                encoding: Encoding::Plain.into(),
+                definition_level_encoding: Encoding::Rle.into(),
+                repetition_level_encoding: Encoding::Rle.into(),
+                statistics,
+            }),
+            Version::V2 => DataPageHeader::V2(DataPageHeaderV2 {
+                num_values: num_values as i32,
+                num_nulls: num_nulls as i32,
+                num_rows: self.num_rows as i32,
+                encoding: Encoding::Plain.into(),
+                definition_levels_byte_length: def_levels.len() as i32,
+                repetition_levels_byte_length: rep_levels.len() as i32,
+                is_compressed: None,
+                statistics,
+            }),
+        };
+
+        let page = DataPage::new(header, buffer, None, self.descriptor.clone());
+        Ok(EncodedPage::Data(match sketch {
+            Some(sketch) => page.with_sketch(sketch),
+            None => page,
+        }))
+    }
+}
+
+/// Returns the hash of the (non-null) `value` inserted in sketches of distinct values.
+fn hash_value(value: &Value) -> u64 {
+    match value {
+        Value::Boolean(value) => hash_byte([*value as u8]),
+        Value::Int32(value) => hash_native(*value),
+        Value::Int64(value) => hash_native(*value),
+        Value::Int96(value) => hash_native(*value),
+        Value::Float(value) => hash_native(*value),
+        Value::Double(value) => hash_native(*value),
+        Value::Binary(value) => hash_byte(value),
+        _ => unreachable!("leaf values are primitive"),
+    }
+}
+
+/// The statistics builder of a column of any physical type, fed with its [`Value`]s.
+enum StatisticsBuilder {
+    Boolean(BooleanStatisticsBuilder),
+    Int32(PrimitiveStatisticsBuilder<i32>),
+    Int64(PrimitiveStatisticsBuilder<i64>),
+    Int96(PrimitiveStatisticsBuilder<[u32; 3]>),
+    Float(PrimitiveStatisticsBuilder<f32>),
+    Double(PrimitiveStatisticsBuilder<f64>),
+    Binary(BinaryStatisticsBuilder),
+    FixedLen(FixedLenStatisticsBuilder),
+}
+
+impl StatisticsBuilder {
+    fn new(descriptor: &ColumnDescriptor) -> Self {
+        match descriptor.physical_type() {
+            PhysicalType::Boolean => Self::Boolean(BooleanStatisticsBuilder::new(descriptor)),
+            PhysicalType::Int32 => Self::Int32(PrimitiveStatisticsBuilder::new(descriptor)),
+            PhysicalType::Int64 => Self::Int64(PrimitiveStatisticsBuilder::new(descriptor)),
+            PhysicalType::Int96 => Self::Int96(PrimitiveStatisticsBuilder::new(descriptor)),
+            PhysicalType::Float => Self::Float(PrimitiveStatisticsBuilder::new(descriptor)),
+            PhysicalType::Double => Self::Double(PrimitiveStatisticsBuilder::new(descriptor)),
+            PhysicalType::ByteArray => Self::Binary(BinaryStatisticsBuilder::new(descriptor)),
+            PhysicalType::FixedLenByteArray(_) => {
+                Self::FixedLen(FixedLenStatisticsBuilder::new(descriptor))
+            }
+        }
+    }
+
+    /// Updates the statistics with `value`, or a null value.
+    fn push(&mut self, value: Option<&Value>) {
+        macro_rules! push {
+            ($builder:expr, $variant:ident, $value:ident => $map:expr) => {
+                $builder.push(value.map(|value| match value {
+                    Value::$variant($value) => $map,
+                    _ => unreachable!("the values of the column are of its type"),
+                }))
+            };
+        }
+        match self {
+            Self::Boolean(builder) => push!(builder, Boolean, x => *x),
+            Self::Int32(builder) => push!(builder, Int32, x => *x),
+            Self::Int64(builder) => push!(builder, Int64, x => *x),
+            Self::Int96(builder) => push!(builder, Int96, x => *x),
+            Self::Float(builder) => push!(builder, Float, x => *x),
+            Self::Double(builder) => push!(builder, Double, x => *x),
+            Self::Binary(builder) => push!(builder, Binary, x => x.as_slice()),
+            Self::FixedLen(builder) => push!(builder, Binary, x => x.as_slice()),
+        }
+    }
+
+    fn finish(self) -> ParquetStatistics {
+        match self {
+            Self::Boolean(builder) => builder.finish(),
+            Self::Int32(builder) => builder.finish(),
+            Self::Int64(builder) => builder.finish(),
+            Self::Int96(builder) => builder.finish(),
+            Self::Float(builder) => builder.finish(),
+            Self::Double(builder) => builder.finish(),
+            Self::Binary(builder) => builder.finish(),
+            Self::FixedLen(builder) => builder.finish(),
+        }
+    }
+}
+
+fn encode_levels(levels: &[u32], max_level: i16) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+    if max_level > 0 {
+        let num_bits = get_bit_width(max_level) as u8;
+        encode_u32(&mut buffer, levels.iter().copied(), num_bits)?;
+    }
+    Ok(buffer)
+}
+
+/// PLAIN-encodes `values` into `buffer`, passing every value to `observe` while encoding.
+fn encode_plain(
+    values: &[Value],
+    physical_type: &PhysicalType,
+    buffer: &mut Vec<u8>,
+    mut observe: impl FnMut(&Value),
+) -> Result<()> {
+    if *physical_type == PhysicalType::Boolean {
+        let values = values.iter().map(|value| {
+            observe(value);
+            matches!(value, Value::Boolean(true))
+        });
+        bitpacked_encode(buffer, values)?;
+        return Ok(());
+    }
+    values.iter().for_each(|value| {
+        observe(value);
+        match value {
+            Value::Int32(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+            Value::Int64(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+            Value::Int96(value) => value
+                .iter()
+                .for_each(|x| buffer.extend_from_slice(&x.to_le_bytes())),
+            Value::Float(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+            Value::Double(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+            Value::Binary(value) => {
+                // the length of fixed-length values is in the schema
+                if *physical_type == PhysicalType::ByteArray {
+                    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+                }
+                buffer.extend_from_slice(value)
+            }
+            _ => unreachable!("leaf values are primitive"),
+        }
+    });
+    Ok(())
+}
+
+/// Whether `value` is a (non-null) value of the primitive type `physical_type`
+fn is_of_type(value: &Value, physical_type: &PhysicalType) -> bool {
+    match (value, physical_type) {
+        (Value::Boolean(_), PhysicalType::Boolean)
+        | (Value::Int32(_), PhysicalType::Int32)
+        | (Value::Int64(_), PhysicalType::Int64)
+        | (Value::Int96(_), PhysicalType::Int96)
+        | (Value::Float(_), PhysicalType::Float)
+        | (Value::Double(_), PhysicalType::Double)
+        | (Value::Binary(_), PhysicalType::ByteArray) => true,
+        (Value::Binary(value), PhysicalType::FixedLenByteArray(size)) => {
+            value.len() == *size as usize
+        }
+        _ => false,
+    }
+}
+
+fn invalid_value(type_: &ParquetType, value: &Value) -> ParquetError {
+    general_err!(
+        "The value {:?} is not a valid value of the field \"{}\"",
+        value,
+        type_.name()
+    )
+}
+
+/// Shreds the `value` of `field` into `columns`, the leaves of `field`.
+/// `rep_level` is the repetition level of its first leaf value, `def_level` and `depth` the
+/// definition and repetition levels of its parent, and `is_list_element` whether `field` is
+/// the repeated field of a `LIST` passed as a `List` of its elements.
+fn shred_field(
+    field: &ParquetType,
+    value: &Value,
+    rep_level: u32,
+    def_level: u32,
+    depth: u32,
+    is_list_element: bool,
+    columns: &mut [ShreddedColumn],
+) -> Result<()> {
+    match field.get_basic_info().repetition() {
+        Repetition::Required => {
+            if let Value::Null = value {
+                return Err(general_err!(
+                    "The required field \"{}\" cannot be null",
+                    field.name()
+                ));
+            }
+            shred_value(field, value, rep_level, def_level, depth, columns)
+        }
+        Repetition::Optional => match value {
+            Value::Null => {
+                columns
+                    .iter_mut()
+                    .for_each(|column| column.push(rep_level, def_level));
+                Ok(())
+            }
+            _ => shred_value(field, value, rep_level, def_level + 1, depth, columns),
+        },
+        Repetition::Repeated => {
+            let items = match value {
+                Value::List(items) => items.as_slice(),
+                Value::Null => &[],
+                _ => return Err(invalid_value(field, value)),
+            };
+            if items.is_empty() {
+                columns
+                    .iter_mut()
+                    .for_each(|column| column.push(rep_level, def_level));
+                return Ok(());
+            }
+            items.iter().enumerate().try_for_each(|(i, item)| {
+                // the first element continues the parent; the others repeat this field
+                let rep_level = if i == 0 { rep_level } else { depth + 1 };
+                match field {
+                    // the repeated group of a 3-level LIST only wraps its element
+                    ParquetType::GroupType { fields, .. }
+                        if is_list_element && fields.len() == 1 =>
+                    {
+                        shred_field(
+                            &fields[0],
+                            item,
+                            rep_level,
+                            def_level + 1,
+                            depth + 1,
+                            false,
+                            columns,
+                        )
+                    }
+                    _ => {
+                        if let Value::Null = item {
+                            return Err(general_err!(
+                                "The elements of the repeated field \"{}\" cannot be null",
+                                field.name()
+                            ));
+                        }
+                        shred_value(field, item, rep_level, def_level + 1, depth + 1, columns)
+                    }
+                }
+            })
+        }
+    }
+}
+
+/// Shreds the non-null `value` of `type_` into `columns`, the leaves of `type_`.
+fn shred_value(
+    type_: &ParquetType,
+    value: &Value,
+    rep_level: u32,
+    def_level: u32,
+    depth: u32,
+    columns: &mut [ShreddedColumn],
+) -> Result<()> {
+    match type_ {
+        ParquetType::PrimitiveType { physical_type, .. } => {
+            if !is_of_type(value, physical_type) {
+                return Err(invalid_value(type_, value));
+            }
+            columns[0].push(rep_level, def_level);
+            columns[0].values.push(value.clone());
+            Ok(())
+        }
+        ParquetType::GroupType { fields, .. } => match value {
+            Value::List(_) if (type_.is_list() || type_.is_map()) && fields.len() == 1 => {
+                shred_field(
+                    &fields[0],
+                    value,
+                    rep_level,
+                    def_level,
+                    depth,
+                    type_.is_list(),
+                    columns,
+                )
+            }
+            Value::Group(values) if values.len() == fields.len() => {
+                let mut columns = columns;
+                fields
+                    .iter()
+                    .zip(values.iter())
+                    .try_for_each(|(field, value)| {
+                        let (field_columns, remaining) =
+                            std::mem::take(&mut columns).split_at_mut(field.num_leaves());
+                        columns = remaining;
+                        shred_field(
+                            field,
+                            value,
+                            rep_level,
+                            def_level,
+                            depth,
+                            false,
+                            field_columns,
+                        )
+                    })
+            }
+            _ => Err(invalid_value(type_, value)),
+        },
+    }
+}
+
+/// Shreds `rows`, whose values are a `Group` with a value per field of `schema`, into the
+/// repetition and definition levels and the values of every column of `schema`, in the order
+/// of [`SchemaDescriptor::columns`] (record shredding, as described by Dremel).
+/// # Error
+/// Errors iff a value does not match its field in `schema`.
+pub fn shred(schema: &SchemaDescriptor, rows: &[Value]) -> Result<Vec<ShreddedColumn>> {
+    let mut columns = schema
+        .columns()
+        .iter()
+        .cloned()
+        .map(ShreddedColumn::new)
+        .collect::<Vec<_>>();
+    let root = ParquetType::new_root(schema.name().to_string(), schema.fields().to_vec());
+    for row in rows {
+        shred_value(&root, row, 0, 0, 0, &mut columns)?;
+    }
+    columns
+        .iter_mut()
+        .for_each(|column| column.num_rows = rows.len());
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::deserialize::{NativeValues, Nested, NestedDecoder};
+    use crate::tests::options;
+
+    fn list_of_structs() -> SchemaDescriptor {
+        SchemaDescriptor::try_from_message(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL group element {
+                            REQUIRED INT32 b;
+                            OPTIONAL BYTE_ARRAY c;
+                        }
+                    }
+                }
+            }",
+        )
+        .unwrap()
+    }
+
+    fn element(b: i32, c: Option<&str>) -> Value {
+        let c = c.map_or(Value::Null, |c| Value::Binary(c.as_bytes().to_vec()));
+        Value::Group(vec![Value::Int32(b), c])
+    }
+
+    #[test]
+    fn list_of_struct() -> Result<()> {
+        let schema = list_of_structs();
+        // [{b: 1, c: "x"}, None, {b: 2, c: None}], None, [], [{b: 3, c: "y"}]
+        let rows = vec![
+            Value::Group(vec![Value::List(vec![
+                element(1, Some("x")),
+                Value::Null,
+                element(2, None),
+            ])]),
+            Value::Group(vec![Value::Null]),
+            Value::Group(vec![Value::List(vec![])]),
+            Value::Group(vec![Value::List(vec![element(3, Some("y"))])]),
+        ];
+        let columns = shred(&schema, &rows)?;
+
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].rep_levels(), &[0, 1, 1, 0, 0, 0]);
+        assert_eq!(columns[0].def_levels(), &[3, 2, 3, 0, 1, 3]);
+        assert_eq!(
+            columns[0].values(),
+            &[Value::Int32(1), Value::Int32(2), Value::Int32(3)]
+        );
+        assert_eq!(columns[1].rep_levels(), &[0, 1, 1, 0, 0, 0]);
+        assert_eq!(columns[1].def_levels(), &[4, 2, 3, 0, 1, 4]);
+        assert_eq!(columns[1].values().len(), 2);
+        assert_eq!(columns[1].num_rows(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn dremel() -> Result<()> {
+        // the document of the Dremel paper, restricted to `Name.Language.Code`
+        let schema = SchemaDescriptor::try_from_message(
+            "message document {
+                REPEATED group name {
+                    REPEATED group language {
+                        REQUIRED BYTE_ARRAY code;
+                    }
+                }
+            }",
+        )?;
+        let language = |code: &str| Value::Group(vec![Value::Binary(code.as_bytes().to_vec())]);
+        let rows = vec![
+            Value::Group(vec![Value::List(vec![
+                Value::Group(vec![Value::List(vec![language("en-us"), language("en")])]),
+                Value::Group(vec![Value::List(vec![])]),
+                Value::Group(vec![Value::List(vec![language("en-gb")])]),
+            ])]),
+            Value::Group(vec![Value::List(vec![Value::Group(vec![Value::Null])])]),
+        ];
+        let columns = shred(&schema, &rows)?;
+
+        assert_eq!(columns[0].rep_levels(), &[0, 2, 1, 1, 0]);
+        assert_eq!(columns[0].def_levels(), &[2, 2, 1, 2, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn map() -> Result<()> {
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema {
+                OPTIONAL group a (MAP) {
+                    REPEATED group key_value {
+                        REQUIRED BYTE_ARRAY key;
+                        OPTIONAL INT64 value;
+                    }
+                }
+            }",
+        )?;
+        let pair = |key: &str, value: Value| {
+            Value::Group(vec![Value::Binary(key.as_bytes().to_vec()), value])
+        };
+        let rows = vec![Value::Group(vec![Value::List(vec![
+            pair("x", Value::Int64(1)),
+            pair("y", Value::Null),
+        ])])];
+        let columns = shred(&schema, &rows)?;
+
+        assert_eq!(columns[0].def_levels(), &[2, 2]);
+        assert_eq!(columns[1].rep_levels(), &[0, 1]);
+        assert_eq!(columns[1].def_levels(), &[3, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_values() {
+        let schema = list_of_structs();
+        // a null `b`, that is required
+        let rows = vec![Value::Group(vec![Value::List(vec![Value::Group(vec![
+            Value::Null,
+            Value::Null,
+        ])])])];
+        assert!(shred(&schema, &rows).is_err());
+        // a value of the wrong type
+        let rows = vec![Value::Group(vec![Value::List(vec![Value::Group(vec![
+            Value::Int64(1),
+            Value::Null,
+        ])])])];
+        assert!(shred(&schema, &rows).is_err());
+        // a group without a value for every field
+        let rows = vec![Value::Group(vec![])];
+        assert!(shred(&schema, &rows).is_err());
+    }
+
+    #[test]
+    fn page_roundtrip() -> Result<()> {
+        let schema = list_of_structs();
+        let rows = vec![
+            Value::Group(vec![Value::List(vec![element(1, None), Value::Null])]),
+            Value::Group(vec![Value::Null]),
+            Value::Group(vec![Value::List(vec![element(2, Some("z"))])]),
+        ];
+        let columns = shred(&schema, &rows)?;
+
+        for version in [Version::V1, Version::V2] {
+            let page = match columns[0].to_page(&options(version))? {
+                EncodedPage::Data(page) => page,
+                _ => unreachable!(),
+            };
+            let mut decoder = NestedDecoder::try_new(page.descriptor())?;
+            decoder.extend_from_page(&page)?;
+            assert_eq!(
+                decoder.finish(),
+                vec![
+                    Nested::Optional(vec![true, false, true]),
+                    Nested::Repeated(vec![0, 2, 2, 3]),
+                    Nested::Optional(vec![true, false, true]),
+                    Nested::Required(3),
+                ]
+            );
+            let values = NativeValues::<i32>::try_new(&page)?.collect::<Vec<_>>();
+            assert_eq!(values, vec![1, 2]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn statistics() -> Result<()> {
+        let schema = list_of_structs();
+        let rows = vec![
+            Value::Group(vec![Value::List(vec![element(1, None), Value::Null])]),
+            Value::Group(vec![Value::Null]),
+            Value::Group(vec![Value::List(vec![element(2, Some("z"))])]),
+        ];
+        let columns = shred(&schema, &rows)?;
+        let options = WriteOptions {
+            write_statistics: true,
+            write_distinct_count: false,
+            ..options(Version::V2)
+        };
+
+        let page = match columns[1].to_page(&options)? {
+            EncodedPage::Data(page) => page,
+            _ => unreachable!(),
+        };
+        let statistics = page.statistics().unwrap()?;
+        let statistics = statistics
+            .as_any()
+            .downcast_ref::<crate::statistics::BinaryStatistics>()
+            .unwrap();
+        assert_eq!(statistics.null_count, Some(3));
+        assert_eq!(statistics.min_value, Some(b"z".to_vec()));
+        assert_eq!(statistics.max_value, Some(b"z".to_vec()));
+        Ok(())
+    }
+}
@@ -8,9 +8,11 @@ use parquet_format_async_temp::thrift::protocol::{
 };
 use parquet_format_async_temp::{DictionaryPageHeader, Encoding, PageType};
 
+use crate::encryption::ColumnEncryptor;
 use crate::error::{ParquetError, Result};
 use crate::page::{
-    CompressedDataPage, CompressedDictPage, CompressedPage, DataPageHeader, ParquetPageHeader,
+    page_crc, CompressedDataPage, CompressedDictPage, CompressedPage, DataPageHeader,
+    ParquetPageHeader,
 };
 use crate::statistics::Statistics;
 
@@ -39,74 +41,89 @@ pub struct PageWriteSpec {
     pub offset: u64,
     pub bytes_written: u64,
     pub statistics: Option<Arc<dyn Statistics>>,
+    pub num_rows: Option<usize>,
 }
 
 pub fn write_page<W: Write>(
     writer: &mut W,
     offset: u64,
     compressed_page: &CompressedPage,
+    write_crc: bool,
+    encryptor: Option<&mut ColumnEncryptor>,
 ) -> Result<PageWriteSpec> {
-    let header = match &compressed_page {
-        CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
-        CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
-    }?;
-
-    let header_size = write_page_header(writer, &header)?;
-    let mut bytes_written = header_size as u64;
+    let mut header = assemble_page_header(compressed_page)?;
 
-    bytes_written += match &compressed_page {
-        CompressedPage::Data(compressed_page) => {
-            writer.write_all(&compressed_page.buffer)?;
-            compressed_page.buffer.len() as u64
-        }
-        CompressedPage::Dict(compressed_page) => {
-            writer.write_all(&compressed_page.buffer)?;
-            compressed_page.buffer.len() as u64
+    let (header, header_size, bytes_written) = if let Some(encryptor) = encryptor {
+        let (header, header_module, page_module) =
+            encrypt_page(encryptor, header, compressed_page, write_crc)?;
+        writer.write_all(&header_module)?;
+        writer.write_all(&page_module)?;
+        let header_size = header_module.len() as u64;
+        (header, header_size, header_size + page_module.len() as u64)
+    } else {
+        let buffer = page_buffer(compressed_page);
+        if write_crc {
+            header.crc = Some(page_crc(buffer));
         }
+        let header_size = write_page_header(writer, &header)?;
+        writer.write_all(buffer)?;
+        (header, header_size, header_size + buffer.len() as u64)
     };
 
-    let statistics = match &compressed_page {
-        CompressedPage::Data(compressed_page) => compressed_page.statistics().transpose()?,
-        CompressedPage::Dict(_) => None,
-    };
-
-    Ok(PageWriteSpec {
-        header,
-        header_size,
-        offset,
-        bytes_written,
-        statistics,
-    })
+    build_spec(compressed_page, header, header_size, offset, bytes_written)
 }
 
 pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
     writer: &mut W,
     offset: u64,
     compressed_page: &CompressedPage,
+    write_crc: bool,
+    encryptor: Option<&mut ColumnEncryptor>,
 ) -> Result<PageWriteSpec> {
-    let header = match &compressed_page {
-        CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
-        CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
-    }?;
-
-    let header_size = write_page_header_async(writer, &header).await?;
-    let mut bytes_written = header_size as u64;
+    let mut header = assemble_page_header(compressed_page)?;
 
-    bytes_written += match &compressed_page {
-        CompressedPage::Data(compressed_page) => {
-            writer.write_all(&compressed_page.buffer).await?;
-            compressed_page.buffer.len() as u64
-        }
-        CompressedPage::Dict(compressed_page) => {
-            writer.write_all(&compressed_page.buffer).await?;
-            compressed_page.buffer.len() as u64
+    let (header, header_size, bytes_written) = if let Some(encryptor) = encryptor {
+        let (header, header_module, page_module) =
+            encrypt_page(encryptor, header, compressed_page, write_crc)?;
+        writer.write_all(&header_module).await?;
+        writer.write_all(&page_module).await?;
+        let header_size = header_module.len() as u64;
+        (header, header_size, header_size + page_module.len() as u64)
+    } else {
+        let buffer = page_buffer(compressed_page);
+        if write_crc {
+            header.crc = Some(page_crc(buffer));
         }
+        let header_size = write_page_header_async(writer, &header).await?;
+        writer.write_all(buffer).await?;
+        (header, header_size, header_size + buffer.len() as u64)
     };
 
+    build_spec(compressed_page, header, header_size, offset, bytes_written)
+}
+
+fn page_buffer(compressed_page: &CompressedPage) -> &[u8] {
+    match compressed_page {
+        CompressedPage::Data(compressed_page) => &compressed_page.buffer,
+        CompressedPage::Dict(compressed_page) => &compressed_page.buffer,
+    }
+}
+
+fn build_spec(
+    compressed_page: &CompressedPage,
+    header: ParquetPageHeader,
+    header_size: u64,
+    offset: u64,
+    bytes_written: u64,
+) -> Result<PageWriteSpec> {
     let statistics = match &compressed_page {
         CompressedPage::Data(compressed_page) => compressed_page.statistics().transpose()?,
         CompressedPage::Dict(_) => None,
     };
+    let num_rows = match &compressed_page {
+        CompressedPage::Data(compressed_page) => compressed_page.num_rows(),
+        CompressedPage::Dict(_) => None,
+    };
 
     Ok(PageWriteSpec {
         header,
@@ -114,9 +131,45 @@ pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
         offset,
         bytes_written,
         statistics,
+        num_rows,
     })
 }
 
+/// Encrypts the page and then its header, whose compressed size becomes the size of the
+/// encrypted page. Returns the header and the encrypted header and page.
+fn encrypt_page(
+    encryptor: &mut ColumnEncryptor,
+    mut header: ParquetPageHeader,
+    compressed_page: &CompressedPage,
+    write_crc: bool,
+) -> Result<(ParquetPageHeader, Vec<u8>, Vec<u8>)> {
+    let dictionary = matches!(compressed_page, CompressedPage::Dict(_));
+
+    let page_module = encryptor.encrypt_page(dictionary, page_buffer(compressed_page))?;
+    header.compressed_page_size = page_module.len().try_into().map_err(|_| {
+        ParquetError::OutOfSpec(format!(
+            "An encrypted page can only contain i32::MAX bytes. This one contains {}",
+            page_module.len()
+        ))
+    })?;
+    if write_crc {
+        header.crc = Some(page_crc(&page_module));
+    }
+
+    let mut serialized = vec![];
+    write_page_header(&mut serialized, &header)?;
+    let header_module = encryptor.encrypt_page_header(dictionary, &serialized)?;
+    encryptor.advance(dictionary);
+    Ok((header, header_module, page_module))
+}
+
+fn assemble_page_header(compressed_page: &CompressedPage) -> Result<ParquetPageHeader> {
+    match compressed_page {
+        CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
+        CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
+    }
+}
+
 fn assemble_data_page_header(page: &CompressedDataPage) -> Result<ParquetPageHeader> {
     let (uncompressed_page_size, compressed_page_size) =
         maybe_bytes(page.uncompressed_size(), page.compressed_size())?;
@@ -196,6 +249,7 @@ mod tests {
     fn dict_too_large() {
         let page = CompressedDictPage {
             buffer: vec![],
+            compression: None,
             uncompressed_page_size: i32::MAX as usize + 1,
             num_values: 100,
         };
@@ -206,6 +260,7 @@ mod tests {
     fn dict_too_many_values() {
         let page = CompressedDictPage {
             buffer: vec![],
+            compression: None,
             uncompressed_page_size: 0,
             num_values: i32::MAX as usize + 1,
         };
@@ -4,15 +4,17 @@ use futures::AsyncWrite;
 use parquet_format_async_temp::{ColumnChunk, RowGroup};
 
 use crate::{
-    compression::Compression,
+    encryption::FileEncryptor,
     error::{ParquetError, Result},
     metadata::{ColumnChunkMetaData, ColumnDescriptor},
     page::CompressedPage,
 };
 
 use super::{
-    column_chunk::{write_column_chunk, write_column_chunk_async},
-    DynIter, DynStreamingIterator,
+    bloom_filter::BloomFilterOptions,
+    column_chunk::{total_compressed_size, write_column_chunk, write_column_chunk_async},
+    page::PageWriteSpec,
+    ColumnWriteOptions, DynIter, DynStreamingIterator, WriteOptions,
 };
 
 pub struct ColumnOffsetsMetadata {
@@ -51,6 +53,7 @@ impl ColumnOffsetsMetadata {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn write_row_group<
     'a,
     W,
@@ -59,26 +62,46 @@ pub fn write_row_group<
     writer: &mut W,
     mut offset: u64,
     descriptors: &[ColumnDescriptor],
-    compression: Compression,
+    options: &WriteOptions,
+    column_options: &[ColumnWriteOptions],
+    bloom_filters: &[Option<BloomFilterOptions>],
+    encryptor: Option<(&FileEncryptor, usize)>,
     columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
     num_rows: usize,
-) -> Result<(RowGroup, u64)>
+) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
 where
     W: Write,
     ParquetError: From<E>,
     E: std::error::Error,
 {
-    let column_iter = descriptors.iter().zip(columns);
+    let column_iter = descriptors.iter().enumerate().zip(columns);
 
     let initial = offset;
-    let columns = column_iter
-        .map(|(descriptor, page_iter)| {
-            let (column, size) =
-                write_column_chunk(writer, offset, descriptor, compression, page_iter?)?;
+    let (columns, specs) = column_iter
+        .map(|((index, descriptor), page_iter)| {
+            let column = column_options.get(index).copied().unwrap_or_default();
+            let options = options.with_column(&column);
+            let bloom_filter = bloom_filters.get(index).and_then(|x| x.as_ref());
+            let encryptor = encryptor
+                .map(|(encryptor, ordinal)| encryptor.column(ordinal, index, descriptor))
+                .transpose()?
+                .flatten();
+            let (column, page_specs, size) = write_column_chunk(
+                writer,
+                offset,
+                descriptor,
+                &options,
+                &column,
+                bloom_filter,
+                encryptor,
+                page_iter?,
+            )?;
             offset += size;
-            Ok(column)
+            Ok((column, page_specs))
         })
-        .collect::<Result<Vec<_>>>()?;
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip::<_, _, Vec<_>, Vec<_>>();
     let bytes_written = offset - initial;
 
     // compute row group stats
@@ -90,10 +113,8 @@ where
         })
         .unwrap_or(None);
 
-    let total_byte_size = columns
-        .iter()
-        .map(|c| c.meta_data.as_ref().unwrap().total_compressed_size)
-        .sum();
+    // the metadata of columns encrypted with a column key is not in the (encrypted) footer
+    let total_byte_size = specs.iter().map(|specs| total_compressed_size(specs)).sum();
 
     Ok((
         RowGroup {
@@ -103,12 +124,15 @@ where
             sorting_columns: None,
             file_offset,
             total_compressed_size: None,
-            ordinal: None,
+            // SPEC: the ordinal of row groups is part of the AAD of encrypted modules
+            ordinal: encryptor.map(|(_, ordinal)| ordinal as i16),
         },
+        specs,
         bytes_written,
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn write_row_group_async<
     'a,
     W,
@@ -117,24 +141,45 @@ pub async fn write_row_group_async<
     writer: &mut W,
     mut offset: u64,
     descriptors: &[ColumnDescriptor],
-    compression: Compression,
+    options: &WriteOptions,
+    column_options: &[ColumnWriteOptions],
+    bloom_filters: &[Option<BloomFilterOptions>],
+    encryptor: Option<(&FileEncryptor, usize)>,
     columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
     num_rows: usize,
-) -> Result<(RowGroup, u64)>
+) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
 where
     W: AsyncWrite + Unpin + Send,
     ParquetError: From<E>,
     E: std::error::Error,
 {
-    let column_iter = descriptors.iter().zip(columns);
+    let column_iter = descriptors.iter().enumerate().zip(columns);
 
     let initial = offset;
     let mut columns = vec![];
-    for (descriptor, page_iter) in column_iter {
-        let (spec, size) =
-            write_column_chunk_async(writer, offset, descriptor, compression, page_iter?).await?;
+    let mut specs = vec![];
+    for ((index, descriptor), page_iter) in column_iter {
+        let column = column_options.get(index).copied().unwrap_or_default();
+        let options = options.with_column(&column);
+        let bloom_filter = bloom_filters.get(index).and_then(|x| x.as_ref());
+        let encryptor = encryptor
+            .map(|(encryptor, ordinal)| encryptor.column(ordinal, index, descriptor))
+            .transpose()?
+            .flatten();
+        let (column, page_specs, size) = write_column_chunk_async(
+            writer,
+            offset,
+            descriptor,
+            &options,
+            &column,
+            bloom_filter,
+            encryptor,
+            page_iter?,
+        )
+        .await?;
         offset += size as u64;
-        columns.push(spec);
+        columns.push(column);
+        specs.push(page_specs);
     }
     let bytes_written = offset - initial;
 
@@ -147,10 +192,8 @@ where
         })
         .unwrap_or(None);
 
-    let total_byte_size = columns
-        .iter()
-        .map(|c| c.meta_data.as_ref().unwrap().total_compressed_size)
-        .sum();
+    // the metadata of columns encrypted with a column key is not in the (encrypted) footer
+    let total_byte_size = specs.iter().map(|specs| total_compressed_size(specs)).sum();
 
     Ok((
         RowGroup {
@@ -160,8 +203,10 @@ where
             sorting_columns: None,
             file_offset: file_offest,
             total_compressed_size: None,
-            ordinal: None,
+            // SPEC: the ordinal of row groups is part of the AAD of encrypted modules
+            ordinal: encryptor.map(|(_, ordinal)| ordinal as i16),
         },
+        specs,
         bytes_written,
     ))
 }
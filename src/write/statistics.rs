@@ -1,6 +1,10 @@
+use std::cmp::Ordering;
 use std::sync::Arc;
 
+use parquet_format_async_temp::BoundaryOrder;
+
 use crate::error::{ParquetError, Result};
+use crate::metadata::{get_column_sort_order, SortOrd, SortOrder};
 use crate::schema::types::PhysicalType;
 use crate::statistics::*;
 use crate::types::NativeType;
@@ -28,54 +32,158 @@ pub fn reduce(stats: &[&Option<Arc<dyn Statistics>>]) -> Result<Option<Arc<dyn S
             "The statistics do not have the same data_type".to_string(),
         ));
     };
-    Ok(match stats[0].physical_type() {
-        PhysicalType::Boolean => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_boolean(stats)))
-        }
-        PhysicalType::Int32 => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<i32, _>(stats)))
-        }
-        PhysicalType::Int64 => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<i64, _>(stats)))
-        }
-        PhysicalType::Float => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<f32, _>(stats)))
+    Ok(Some(match stats[0].physical_type() {
+        PhysicalType::Boolean => Arc::new(reduce_boolean(downcast(&stats)?.into_iter())),
+        PhysicalType::Int32 => Arc::new(reduce_primitive::<i32, _>(downcast(&stats)?.into_iter())),
+        PhysicalType::Int64 => Arc::new(reduce_primitive::<i64, _>(downcast(&stats)?.into_iter())),
+        PhysicalType::Int96 => Arc::new(reduce_primitive::<[u32; 3], _>(
+            downcast(&stats)?.into_iter(),
+        )),
+        PhysicalType::Float => Arc::new(reduce_primitive::<f32, _>(downcast(&stats)?.into_iter())),
+        PhysicalType::Double => Arc::new(reduce_primitive::<f64, _>(downcast(&stats)?.into_iter())),
+        PhysicalType::ByteArray => Arc::new(reduce_binary(downcast(&stats)?.into_iter())),
+        PhysicalType::FixedLenByteArray(_) => {
+            Arc::new(reduce_fix_len_binary(downcast(&stats)?.into_iter()))
         }
-        PhysicalType::Double => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<f64, _>(stats)))
+    }))
+}
+
+/// Returns the [`BoundaryOrder`] of a column index whose non-null pages have the statistics
+/// `stats`, in order: ascending (resp. descending) when both their minimums and their maximums
+/// are, and unordered when any of them is missing or not comparable.
+/// # Error
+/// Errors iff the statistics do not have the same physical type.
+pub fn boundary_order(stats: &[&dyn Statistics]) -> Result<BoundaryOrder> {
+    let physical_type = match stats.first() {
+        Some(x) => x.physical_type(),
+        None => return Ok(BoundaryOrder::UNORDERED),
+    };
+    Ok(match physical_type {
+        PhysicalType::Boolean => {
+            let stats = downcast::<BooleanStatistics>(stats)?;
+            let bounds = stats.iter().map(|x| (&x.min_value, &x.max_value));
+            bounds_order(bounds, SortOrder::Unsigned)
         }
+        PhysicalType::Int32 => primitive_boundary_order::<i32>(stats)?,
+        PhysicalType::Int64 => primitive_boundary_order::<i64>(stats)?,
+        PhysicalType::Int96 => primitive_boundary_order::<[u32; 3]>(stats)?,
+        PhysicalType::Float => primitive_boundary_order::<f32>(stats)?,
+        PhysicalType::Double => primitive_boundary_order::<f64>(stats)?,
         PhysicalType::ByteArray => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_binary(stats)))
+            let stats = downcast::<BinaryStatistics>(stats)?;
+            let bounds = stats.iter().map(|x| (&x.min_value, &x.max_value));
+            bounds_order(bounds, get_column_sort_order(&stats[0].descriptor))
         }
         PhysicalType::FixedLenByteArray(_) => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_fix_len_binary(stats)))
+            let stats = downcast::<FixedLenStatistics>(stats)?;
+            let bounds = stats.iter().map(|x| (&x.min_value, &x.max_value));
+            bounds_order(bounds, get_column_sort_order(&stats[0].descriptor))
         }
-        _ => todo!(),
     })
 }
 
+fn primitive_boundary_order<T: NativeType + SortOrd>(
+    stats: &[&dyn Statistics],
+) -> Result<BoundaryOrder> {
+    let stats = downcast::<PrimitiveStatistics<T>>(stats)?;
+    let bounds = stats.iter().map(|x| (&x.min_value, &x.max_value));
+    Ok(bounds_order(
+        bounds,
+        get_column_sort_order(&stats[0].descriptor),
+    ))
+}
+
+/// Returns the order of consecutive minimums and maximums `bounds` with the sort order `order`.
+fn bounds_order<'a, T, I>(bounds: I, order: SortOrder) -> BoundaryOrder
+where
+    T: SortOrd + 'a,
+    I: Iterator<Item = (&'a Option<T>, &'a Option<T>)>,
+{
+    let (mut is_ascending, mut is_descending) = (true, true);
+    let mut previous: Option<(&T, &T)> = None;
+    for bounds in bounds {
+        let (min, max) = match bounds {
+            (Some(min), Some(max)) => (min, max),
+            _ => return BoundaryOrder::UNORDERED,
+        };
+        if let Some((previous_min, previous_max)) = previous {
+            match (
+                previous_min.sort_cmp(min, order),
+                previous_max.sort_cmp(max, order),
+            ) {
+                (Some(min), Some(max)) => {
+                    is_ascending &= min != Ordering::Greater && max != Ordering::Greater;
+                    is_descending &= min != Ordering::Less && max != Ordering::Less;
+                }
+                _ => return BoundaryOrder::UNORDERED,
+            }
+        }
+        previous = Some((min, max));
+    }
+    if is_ascending {
+        BoundaryOrder::ASCENDING
+    } else if is_descending {
+        BoundaryOrder::DESCENDING
+    } else {
+        BoundaryOrder::UNORDERED
+    }
+}
+
+/// Merges the sketches of the data pages of a column chunk as they are written, so that no
+/// page's sketch outlives its page.
+#[derive(Debug, Default)]
+pub struct SketchReducer {
+    sketch: Option<HyperLogLog>,
+    // a data page without sketch, whose distinct values would be missing from the estimate
+    is_incomplete: bool,
+}
+
+impl SketchReducer {
+    /// Merges the sketch of a data page, if any, into the sketch of the column chunk.
+    pub fn push(&mut self, sketch: Option<&HyperLogLog>) {
+        match (self.sketch.as_mut(), sketch) {
+            (_, None) => self.is_incomplete = true,
+            (Some(sketch), Some(other)) => sketch.merge(other),
+            (None, Some(other)) => self.sketch = Some(other.clone()),
+        }
+    }
+
+    /// Returns the sketch of the column chunk, or `None` if any data page had no sketch.
+    pub fn finish(self) -> Option<HyperLogLog> {
+        if self.is_incomplete {
+            None
+        } else {
+            self.sketch
+        }
+    }
+}
+
+/// Downcasts `stats` to their concrete type.
+/// # Error
+/// Errors iff any of the statistics is not of type `T`.
+fn downcast<'a, T: 'static>(stats: &[&'a dyn Statistics]) -> Result<Vec<&'a T>> {
+    stats
+        .iter()
+        .map(|x| {
+            x.as_any().downcast_ref::<T>().ok_or_else(|| {
+                general_err!(
+                    "Statistics of the physical type {:?} cannot be reduced as {}",
+                    x.physical_type(),
+                    std::any::type_name::<T>()
+                )
+            })
+        })
+        .collect()
+}
+
 fn reduce_binary<'a, I: Iterator<Item = &'a BinaryStatistics>>(mut stats: I) -> BinaryStatistics {
-    let initial = stats.next().unwrap().clone();
+    let mut initial = stats.next().unwrap().clone();
+    let order = get_column_sort_order(&initial.descriptor);
+    initial.min_value = reduce_bound(initial.min_value, None, order, false);
+    initial.max_value = reduce_bound(initial.max_value, None, order, true);
     stats.fold(initial, |mut acc, new| {
-        acc.min_value = match (acc.min_value, &new.min_value) {
-            (None, None) => None,
-            (Some(x), None) => Some(x),
-            (None, Some(x)) => Some(x.clone()),
-            (Some(x), Some(y)) => Some(ord_binary(x, y.clone(), false)),
-        };
-        acc.max_value = match (acc.max_value, &new.max_value) {
-            (None, None) => None,
-            (Some(x), None) => Some(x),
-            (None, Some(x)) => Some(x.clone()),
-            (Some(x), Some(y)) => Some(ord_binary(x, y.clone(), true)),
-        };
+        acc.min_value = reduce_bound(acc.min_value, new.min_value.as_ref(), order, false);
+        acc.max_value = reduce_bound(acc.max_value, new.max_value.as_ref(), order, true);
         acc.null_count = match (acc.null_count, &new.null_count) {
             (None, None) => None,
             (Some(x), None) => Some(x),
@@ -90,20 +198,13 @@ fn reduce_binary<'a, I: Iterator<Item = &'a BinaryStatistics>>(mut stats: I) ->
 fn reduce_fix_len_binary<'a, I: Iterator<Item = &'a FixedLenStatistics>>(
     mut stats: I,
 ) -> FixedLenStatistics {
-    let initial = stats.next().unwrap().clone();
+    let mut initial = stats.next().unwrap().clone();
+    let order = get_column_sort_order(&initial.descriptor);
+    initial.min_value = reduce_bound(initial.min_value, None, order, false);
+    initial.max_value = reduce_bound(initial.max_value, None, order, true);
     stats.fold(initial, |mut acc, new| {
-        acc.min_value = match (acc.min_value, &new.min_value) {
-            (None, None) => None,
-            (Some(x), None) => Some(x),
-            (None, Some(x)) => Some(x.clone()),
-            (Some(x), Some(y)) => Some(ord_binary(x, y.clone(), false)),
-        };
-        acc.max_value = match (acc.max_value, &new.max_value) {
-            (None, None) => None,
-            (Some(x), None) => Some(x),
-            (None, Some(x)) => Some(x.clone()),
-            (Some(x), Some(y)) => Some(ord_binary(x, y.clone(), true)),
-        };
+        acc.min_value = reduce_bound(acc.min_value, new.min_value.as_ref(), order, false);
+        acc.max_value = reduce_bound(acc.max_value, new.max_value.as_ref(), order, true);
         acc.null_count = match (acc.null_count, &new.null_count) {
             (None, None) => None,
             (Some(x), None) => Some(x),
@@ -115,27 +216,24 @@ fn reduce_fix_len_binary<'a, I: Iterator<Item = &'a FixedLenStatistics>>(
     })
 }
 
-fn ord_binary(a: Vec<u8>, b: Vec<u8>, max: bool) -> Vec<u8> {
-    for (v1, v2) in a.iter().zip(b.iter()) {
-        match v1.cmp(v2) {
-            std::cmp::Ordering::Greater => {
-                if max {
-                    return a;
-                } else {
-                    return b;
-                }
-            }
-            std::cmp::Ordering::Less => {
-                if max {
-                    return b;
-                } else {
-                    return a;
-                }
-            }
-            _ => {}
-        }
+/// Returns the minimum (or the maximum, if `max`) of two bounds with the sort order `order`.
+/// Bounds that cannot be compared, e.g. NaN or of an undefined order, are ignored.
+fn reduce_bound<T: SortOrd + Clone>(
+    a: Option<T>,
+    b: Option<&T>,
+    order: SortOrder,
+    max: bool,
+) -> Option<T> {
+    let is_comparable = |x: &T| x.sort_cmp(x, order).is_some();
+    match (a.filter(is_comparable), b.filter(|x| is_comparable(x))) {
+        (None, None) => None,
+        (Some(a), None) => Some(a),
+        (None, Some(b)) => Some(b.clone()),
+        (Some(a), Some(b)) => Some(match (b.sort_cmp(&a, order), max) {
+            (Some(Ordering::Greater), true) | (Some(Ordering::Less), false) => b.clone(),
+            _ => a,
+        }),
     }
-    a
 }
 
 fn reduce_boolean<'a, I: Iterator<Item = &'a BooleanStatistics>>(
@@ -166,27 +264,18 @@ fn reduce_boolean<'a, I: Iterator<Item = &'a BooleanStatistics>>(
     })
 }
 
-fn reduce_primitive<
-    'a,
-    T: NativeType + std::cmp::PartialOrd,
+fn reduce_primitive<'a, T, I>(mut stats: I) -> PrimitiveStatistics<T>
+where
+    T: NativeType + SortOrd,
     I: Iterator<Item = &'a PrimitiveStatistics<T>>,
->(
-    mut stats: I,
-) -> PrimitiveStatistics<T> {
-    let initial = stats.next().unwrap().clone();
+{
+    let mut initial = stats.next().unwrap().clone();
+    let order = get_column_sort_order(&initial.descriptor);
+    initial.min_value = reduce_bound(initial.min_value, None, order, false);
+    initial.max_value = reduce_bound(initial.max_value, None, order, true);
     stats.fold(initial, |mut acc, new| {
-        acc.min_value = match (acc.min_value, &new.min_value) {
-            (None, None) => None,
-            (Some(x), None) => Some(x),
-            (None, Some(x)) => Some(*x),
-            (Some(x), Some(y)) => Some(if x > *y { *y } else { x }),
-        };
-        acc.max_value = match (acc.max_value, &new.max_value) {
-            (None, None) => None,
-            (Some(x), None) => Some(x),
-            (None, Some(x)) => Some(*x),
-            (Some(x), Some(y)) => Some(if x < *y { x } else { *y }),
-        };
+        acc.min_value = reduce_bound(acc.min_value, new.min_value.as_ref(), order, false);
+        acc.max_value = reduce_bound(acc.max_value, new.max_value.as_ref(), order, true);
         acc.null_count = match (acc.null_count, &new.null_count) {
             (None, None) => None,
             (Some(x), None) => Some(x),
@@ -197,3 +286,243 @@ fn reduce_primitive<
         acc
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metadata::SchemaDescriptor;
+    use crate::types::int96_to_i64_ns;
+
+    fn int96_stats(
+        min_value: Option<[u32; 3]>,
+        max_value: Option<[u32; 3]>,
+        null_count: Option<i64>,
+    ) -> Option<Arc<dyn Statistics>> {
+        let schema =
+            SchemaDescriptor::try_from_message("message schema { OPTIONAL INT96 a; }").unwrap();
+        Some(Arc::new(PrimitiveStatistics::<[u32; 3]> {
+            descriptor: schema.columns()[0].clone(),
+            null_count,
+            distinct_count: None,
+            max_value,
+            min_value,
+        }))
+    }
+
+    #[test]
+    fn int96() -> Result<()> {
+        // 1970-01-02 00:00:00, 1970-01-01 00:00:01 and 1970-01-01 00:00:00
+        let day = [0, 0, 2_440_589];
+        let second = [1_000_000_000, 0, 2_440_588];
+        let epoch = [0, 0, 2_440_588];
+
+        let a = int96_stats(Some(second), Some(day), Some(1));
+        let b = int96_stats(Some(epoch), Some(second), None);
+        let c = int96_stats(None, None, Some(2));
+        let reduced = reduce(&[&a, &None, &b, &c])?.unwrap();
+        let reduced = reduced
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<[u32; 3]>>()
+            .unwrap();
+        // the order of INT96 is undefined
+        assert_eq!(reduced.min_value, None);
+        assert_eq!(reduced.max_value, None);
+        assert_eq!(reduced.null_count, Some(3));
+        Ok(())
+    }
+
+    #[test]
+    fn int96_out_of_range() {
+        // 9999-12-31 23:59:59, 0001-01-01 00:00:00 and 1970-01-01 00:00:00
+        let last = [1_437_873_664, 20_116, 5_373_484];
+        let first = [0, 0, 1_721_426];
+        let epoch = [0, 0, 2_440_588];
+
+        assert_eq!(int96_to_i64_ns(last), None);
+        assert_eq!(int96_to_i64_ns(first), None);
+        assert_eq!(int96_to_i64_ns(epoch), Some(0));
+    }
+
+    #[test]
+    fn primitive() -> Result<()> {
+        let schema =
+            SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 a; }").unwrap();
+        let stats = |min_value, max_value| -> Option<Arc<dyn Statistics>> {
+            Some(Arc::new(PrimitiveStatistics::<i32> {
+                descriptor: schema.columns()[0].clone(),
+                null_count: Some(0),
+                distinct_count: None,
+                max_value: Some(max_value),
+                min_value: Some(min_value),
+            }))
+        };
+        let reduced = reduce(&[&stats(-1, 3), &stats(0, 5), &stats(-2, 1)])?.unwrap();
+        let reduced = reduced
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<i32>>()
+            .unwrap();
+        assert_eq!(reduced.min_value, Some(-2));
+        assert_eq!(reduced.max_value, Some(5));
+        Ok(())
+    }
+
+    /// Reduces the raw statistics `stats` of the single column of `message`.
+    fn reduce_raw(message: &str, stats: &[(Vec<u8>, Vec<u8>)]) -> Result<Arc<dyn Statistics>> {
+        let schema = SchemaDescriptor::try_from_message(message).unwrap();
+        let stats = stats
+            .iter()
+            .map(|(min_value, max_value)| {
+                let stats = ParquetStatistics {
+                    max: None,
+                    min: None,
+                    null_count: Some(0),
+                    distinct_count: None,
+                    max_value: Some(max_value.clone()),
+                    min_value: Some(min_value.clone()),
+                };
+                deserialize_statistics(&stats, schema.columns()[0].clone()).map(Some)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let stats = stats.iter().collect::<Vec<_>>();
+        Ok(reduce(&stats)?.unwrap())
+    }
+
+    #[test]
+    fn unsigned() -> Result<()> {
+        let reduced = reduce_raw(
+            "message schema { REQUIRED INT32 a (UINT_32); }",
+            &[
+                (
+                    (-1i32).to_le_bytes().to_vec(),
+                    (-1i32).to_le_bytes().to_vec(),
+                ),
+                (1i32.to_le_bytes().to_vec(), 2i32.to_le_bytes().to_vec()),
+            ],
+        )?;
+        let reduced = reduced
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<i32>>()
+            .unwrap();
+        assert_eq!(reduced.min_value, Some(1));
+        assert_eq!(reduced.max_value, Some(-1));
+        Ok(())
+    }
+
+    #[test]
+    fn decimal() -> Result<()> {
+        let reduced = reduce_raw(
+            "message schema { REQUIRED FIXED_LEN_BYTE_ARRAY (2) a (DECIMAL(4, 2)); }",
+            &[
+                (vec![0xFF, 0x00], vec![0x00, 0x01]),
+                (vec![0x00, 0x00], vec![0x7F, 0x00]),
+            ],
+        )?;
+        let reduced = reduced
+            .as_any()
+            .downcast_ref::<FixedLenStatistics>()
+            .unwrap();
+        assert_eq!(reduced.min_value, Some(vec![0xFF, 0x00]));
+        assert_eq!(reduced.max_value, Some(vec![0x7F, 0x00]));
+        Ok(())
+    }
+
+    #[test]
+    fn binary() -> Result<()> {
+        let reduced = reduce_raw(
+            "message schema { REQUIRED BYTE_ARRAY a (UTF8); }",
+            &[
+                (b"ab".to_vec(), b"ab".to_vec()),
+                (b"abc".to_vec(), b"b".to_vec()),
+            ],
+        )?;
+        let reduced = reduced.as_any().downcast_ref::<BinaryStatistics>().unwrap();
+        assert_eq!(reduced.min_value, Some(b"ab".to_vec()));
+        assert_eq!(reduced.max_value, Some(b"b".to_vec()));
+        Ok(())
+    }
+
+    #[test]
+    fn nan() -> Result<()> {
+        let reduced = reduce_raw(
+            "message schema { REQUIRED DOUBLE a; }",
+            &[
+                (
+                    f64::NAN.to_le_bytes().to_vec(),
+                    f64::NAN.to_le_bytes().to_vec(),
+                ),
+                (
+                    1.0f64.to_le_bytes().to_vec(),
+                    f64::NAN.to_le_bytes().to_vec(),
+                ),
+                (
+                    (-1.0f64).to_le_bytes().to_vec(),
+                    0.5f64.to_le_bytes().to_vec(),
+                ),
+            ],
+        )?;
+        let reduced = reduced
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<f64>>()
+            .unwrap();
+        assert_eq!(reduced.min_value, Some(-1.0));
+        assert_eq!(reduced.max_value, Some(0.5));
+        Ok(())
+    }
+
+    #[test]
+    fn sketches() {
+        let sketch = |values: std::ops::Range<i32>| {
+            let mut sketch = HyperLogLog::new();
+            values.for_each(|x| sketch.insert(crate::bloom_filter::hash_native(x)));
+            sketch
+        };
+        let (a, b) = (sketch(0..10), sketch(5..20));
+        let mut reducer = SketchReducer::default();
+        reducer.push(Some(&a));
+        reducer.push(Some(&b));
+        assert_eq!(reducer.finish().unwrap().estimate(), 20);
+
+        let mut reducer = SketchReducer::default();
+        reducer.push(Some(&a));
+        reducer.push(None);
+        reducer.push(Some(&b));
+        assert!(reducer.finish().is_none());
+        assert!(SketchReducer::default().finish().is_none());
+    }
+
+    #[derive(Debug)]
+    struct Invalid;
+
+    impl Statistics for Invalid {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn physical_type(&self) -> &PhysicalType {
+            &PhysicalType::Int96
+        }
+
+        fn null_count(&self) -> Option<i64> {
+            None
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let stats: Option<Arc<dyn Statistics>> = Some(Arc::new(Invalid));
+        assert!(reduce(&[&stats]).is_err());
+
+        let int32: Option<Arc<dyn Statistics>> = Some(Arc::new(PrimitiveStatistics::<i32> {
+            descriptor: SchemaDescriptor::try_from_message("message schema { REQUIRED INT32 a; }")
+                .unwrap()
+                .columns()[0]
+                .clone(),
+            null_count: None,
+            distinct_count: None,
+            max_value: None,
+            min_value: None,
+        }));
+        assert!(reduce(&[&int32, &int96_stats(None, None, None)]).is_err());
+    }
+}